@@ -0,0 +1,263 @@
+use crate::core::{
+    Activity, Collection, CollectionPage, IntransitiveActivity, Link, Null, Object, ObjectType,
+    OrderedCollection, OrderedCollectionPage,
+};
+use crate::extended::{
+    Accept, Add, Announce, Application, Arrive, Article, Audio, Block, Create, Delete, Dislike,
+    Event, Flag, Follow, Group, Ignore, Image, Invite, Join, Leave, Like, Listen, Move, Note,
+    Offer, Organization, Page, Person, Place, Profile, Question, Read, Reject, Relationship,
+    Remove, Service, TentativeAccept, TentativeReject, Tombstone, Travel, Undo, Update, Video,
+    View,
+};
+use crate::Serde;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Generates [AnyObject] from a list of variants, each named after the `type`
+/// value it is dispatched on.
+macro_rules! any_object {
+    ($($variant:ident($variant_type:ty)),* $(,)?) => {
+        /// Any core or extended Activity Streams type. Deserializing inspects the
+        /// `type` property and picks the matching variant; documents with an
+        /// unknown or missing `type` fall back to [AnyObject::Object].
+        #[derive(Debug)]
+        pub enum AnyObject {
+            $($variant($variant_type),)*
+        }
+
+        impl AnyObject {
+            /// Returns the `type` value this variant is dispatched on.
+            pub fn kind(&self) -> &'static str {
+                match self {
+                    $(AnyObject::$variant(_) => stringify!($variant),)*
+                }
+            }
+
+            /// Deserializes `value` as the first of `object_types` that is known,
+            /// falling back to a generic [Object].
+            fn from_value(object_types: &[&str], value: serde_json::Value) -> serde_json::Result<Self> {
+                for object_type in object_types {
+                    $(
+                        if *object_type == stringify!($variant) {
+                            return serde_json::from_value::<$variant_type>(value).map(AnyObject::$variant);
+                        }
+                    )*
+                }
+                serde_json::from_value::<Object<Null>>(value).map(AnyObject::Object)
+            }
+        }
+
+        impl Serialize for AnyObject {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                match self {
+                    $(AnyObject::$variant(object) => object.serialize(serializer),)*
+                }
+            }
+        }
+    };
+}
+
+any_object! {
+    // core
+    Object(Object<Null>),
+    Link(Link),
+    Activity(Activity),
+    IntransitiveActivity(IntransitiveActivity),
+    Collection(Collection<AnyObject>),
+    OrderedCollection(OrderedCollection<AnyObject>),
+    CollectionPage(CollectionPage<AnyObject>),
+    OrderedCollectionPage(OrderedCollectionPage<AnyObject>),
+    // activity types
+    Accept(Accept),
+    Add(Add),
+    Announce(Announce),
+    Arrive(Arrive),
+    Block(Block),
+    Create(Create),
+    Delete(Delete),
+    Dislike(Dislike),
+    Flag(Flag),
+    Follow(Follow),
+    Ignore(Ignore),
+    Invite(Invite),
+    Join(Join),
+    Leave(Leave),
+    Like(Like),
+    Listen(Listen),
+    Move(Move),
+    Offer(Offer),
+    Question(Question),
+    Reject(Reject),
+    Read(Read),
+    Remove(Remove),
+    TentativeReject(TentativeReject),
+    TentativeAccept(TentativeAccept),
+    Travel(Travel),
+    Undo(Undo),
+    Update(Update),
+    View(View),
+    // actor types
+    Application(Application),
+    Group(Group),
+    Organization(Organization),
+    Person(Person),
+    Service(Service),
+    // object types
+    Article(Article),
+    Audio(Audio),
+    Document(crate::extended::Document),
+    Event(Event),
+    Image(Image),
+    Note(Note),
+    Page(Page),
+    Place(Place),
+    Profile(Profile),
+    Relationship(Relationship),
+    Tombstone(Tombstone),
+    Video(Video),
+}
+
+impl Serde for AnyObject {}
+
+impl<'de> Deserialize<'de> for AnyObject {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let object_type = value
+            .get("type")
+            .cloned()
+            .and_then(|t| serde_json::from_value::<ObjectType>(t).ok());
+        let object_types: Vec<&str> = object_type.iter().flat_map(ObjectType::iter).collect();
+        AnyObject::from_value(&object_types, value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Document;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn deserialize_note() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Note",
+  "name": "Name",
+  "content": "Content"
+}"#;
+        let document: Document<AnyObject> = Document::from_json(String::from(actual)).unwrap();
+        match document.object {
+            AnyObject::Note(note) => assert_eq!(note.content, Some(String::from("Content"))),
+            other => panic!("expected a Note, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_create() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Create",
+  "summary": "Sally created a note",
+  "actor": {
+    "type": "Person",
+    "name": "Sally"
+  },
+  "object": {
+    "type": "Note",
+    "name": "A Simple Note"
+  }
+}"#;
+        let document: Document<AnyObject> = Document::from_json(String::from(actual)).unwrap();
+        assert_eq!(document.object.kind(), "Create");
+        match document.object {
+            AnyObject::Create(create) => {
+                assert_eq!(create.summary, Some(String::from("Sally created a note")))
+            }
+            other => panic!("expected a Create, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_multiple_types() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": ["toot:Custom", "Note"],
+  "content": "Content"
+}"#;
+        let document: Document<AnyObject> = Document::from_json(String::from(actual)).unwrap();
+        match document.object {
+            AnyObject::Note(note) => assert!(note.has_type("toot:Custom")),
+            other => panic!("expected a Note, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_collection_items() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Collection",
+  "totalItems": 3,
+  "items": [
+    {
+      "type": "Person",
+      "name": "Sally"
+    },
+    {
+      "type": "Link",
+      "href": "http://example.org/abc"
+    },
+    {
+      "type": "http://example.org/Custom",
+      "name": "Custom"
+    }
+  ]
+}"#;
+        let document: Document<AnyObject> = Document::from_json(String::from(actual)).unwrap();
+        let collection = match document.object {
+            AnyObject::Collection(collection) => collection,
+            other => panic!("expected a Collection, got {:?}", other),
+        };
+        let kinds: Vec<&str> = collection.items.iter().map(AnyObject::kind).collect();
+        assert_eq!(kinds, vec!["Person", "Link", "Object"]);
+    }
+
+    #[test]
+    fn deserialize_wrong_shape() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Link",
+  "name": "A link without an href"
+}"#;
+        let result: crate::Result<Document<AnyObject>> = Document::from_json(String::from(actual));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Place",
+  "name": "Work",
+  "latitude": 36.75
+}"#;
+        let document: Document<AnyObject> = Document::from_json(String::from(actual)).unwrap();
+        assert_eq!(document.to_json_pretty().unwrap(), actual);
+    }
+}
@@ -1,7 +1,12 @@
-use crate::extended::{Actor, ActorBuilder};
+use crate::extended::Actor;
+use crate::iri::Iri;
+use crate::jsonld;
+use crate::language::{self, LanguageMap, LanguageTag};
 use crate::Serde;
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
 
 /// [Null]-type object that implements [Serde] for convenience
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -10,6 +15,31 @@ pub struct Null {}
 // TODO: create a derive macro for this
 impl Serde for Null {}
 
+impl Extension for Null {
+    fn terms() -> Vec<(String, TermDefinition)> {
+        Vec::new()
+    }
+}
+
+/// A typed vendor extension, i.e. a struct holding properties outside the
+/// Activity Streams vocabulary (say Mastodon's `sensitive` or
+/// `manuallyApprovesFollowers`). Extensions are attached through the
+/// `ExtensionT` parameter of [Object] and the types built on it, and are
+/// (de)serialized alongside the standard properties. Their members must not
+/// be flattened themselves.
+pub trait Extension: Serde + Clone {
+    /// The JSON-LD term definitions for the properties the extension adds,
+    /// included in the `@context` by [ContextBuilder::extension], and by
+    /// [Document::new] for objects carrying the extension.
+    fn terms() -> Vec<(String, TermDefinition)>;
+
+    /// Remote contexts the extension's properties are defined in, e.g.
+    /// `https://w3id.org/security/v1` for `publicKey`.
+    fn contexts() -> Vec<String> {
+        Vec::new()
+    }
+}
+
 // TODO: rename to something else as there's a [Document] in the Activity
 // Streams spec.
 /// Outer object for serialization and deserialization. Not an Activity Streams
@@ -23,12 +53,49 @@ pub struct Document<T> {
     pub object: T,
 }
 
-impl<T> Serde for Document<T> where T: Serde {}
+impl<T> Serde for Document<T>
+where
+    T: Serde,
+{
+    /// Parses `json`, normalizing its terms first; see
+    /// [Document::from_json_ld].
+    fn from_json(json: String) -> crate::Result<Self> {
+        Self::from_json_ld(json)
+    }
+}
 
 impl<T: Serde> Document<T> {
-    pub fn new(context: Context, object: T) -> Self {
+    /// Wraps `object` with `context`, to which the terms of the typed
+    /// [Extension] of `object`, if any, are added.
+    pub fn new(mut context: Context, object: T) -> Self {
+        T::extend_context(&mut context);
         Document { context, object }
     }
+
+    /// Parses a document written with any JSON-LD context, normalizing its
+    /// terms to those of the Activity Streams context, and of the typed
+    /// [Extension] of `T`, first; see [jsonld::normalize_for]. This is what
+    /// [Serde::from_json] does for documents.
+    pub fn from_json_ld(json: String) -> crate::Result<Self> {
+        Self::from_json_ld_with(json, &jsonld::BundledContextLoader::new())
+    }
+
+    /// Like [Document::from_json_ld], resolving remote contexts with `loader`.
+    pub fn from_json_ld_with(
+        json: String,
+        loader: &dyn jsonld::ContextLoader,
+    ) -> crate::Result<Self> {
+        let document: serde_json::Value = crate::error::from_str(&json)?;
+        let normalized = jsonld::normalize_for::<T>(&document, loader)?;
+        crate::error::from_value(normalized).map_err(|error| {
+            // The path may not exist as written, e.g. where normalizing
+            // replaced an array of one value by the value, so prefer the error
+            // the document as written gives.
+            crate::error::from_value::<Self>(document)
+                .err()
+                .unwrap_or(error)
+        })
+    }
 }
 
 /// JSON-LD uses the special @context property to define the processing context.
@@ -40,41 +107,329 @@ impl<T: Serde> Document<T> {
 /// alternative URL "http://www.w3.org/ns/activitystreams" instead. This can be
 /// done using a string, object, or array.
 /// <https://www.w3.org/TR/activitystreams-core/#jsonld>
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// A context of a single IRI or object is serialized bare, even if it was
+/// given as a one-element array; longer contexts are serialized as arrays.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
 pub struct Context {
-    #[serde(rename = "@vocab")]
-    namespace: String,
+    entries: OneOrMany<ContextEntry>,
+}
+
+impl Context {
+    /// The IRI of the normative Activity Streams 2.0 context.
+    pub const ACTIVITY_STREAMS: &'static str = "https://www.w3.org/ns/activitystreams";
+
+    /// The entries of the context, in order. Later entries take precedence.
+    pub fn entries(&self) -> &[ContextEntry] {
+        self.entries.as_slice()
+    }
+
+    /// The remote contexts referenced by IRI.
+    pub fn iris(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter_map(ContextEntry::as_iri)
+    }
+
+    /// Whether the context references the remote context at `iri`.
+    pub fn includes(&self, iri: &str) -> bool {
+        self.iris().any(|i| i == iri)
+    }
+
+    /// The inline context definitions.
+    pub fn definitions(&self) -> impl Iterator<Item = &ContextDefinition> {
+        self.entries.iter().filter_map(ContextEntry::as_definition)
+    }
+
+    /// The default vocabulary (`@vocab`) of the document, if any.
+    pub fn vocab(&self) -> Option<&str> {
+        self.definitions().filter_map(|d| d.vocab.as_deref()).last()
+    }
+
+    /// The default language (`@language`) of the document, if any.
+    pub fn language(&self) -> Option<&str> {
+        self.definitions()
+            .filter_map(|d| d.language.as_deref())
+            .last()
+    }
+
+    /// The inline definition of `term`, if any.
+    pub fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.definitions().filter_map(|d| d.terms.get(term)).last()
+    }
+
+    /// Adds the remote contexts and term definitions of the extension `E`
+    /// the context lacks. Terms it already defines are left as they are.
+    pub fn extend<E: Extension>(&mut self) {
+        let iris: Vec<String> = E::contexts()
+            .into_iter()
+            .filter(|iri| !self.includes(iri))
+            .collect();
+        let terms: Vec<(String, TermDefinition)> = E::terms()
+            .into_iter()
+            .filter(|(term, _)| self.term(term).is_none())
+            .collect();
+        if iris.is_empty() && terms.is_empty() {
+            return;
+        }
+        let mut entries =
+            std::mem::replace(&mut self.entries, OneOrMany::Many(Vec::new())).into_vec();
+        // Remote contexts go before the inline definitions, which may refer to
+        // their prefixes.
+        let at = entries
+            .iter()
+            .position(|entry| entry.as_definition().is_some())
+            .unwrap_or(entries.len());
+        entries.splice(at..at, iris.into_iter().map(ContextEntry::Iri));
+        if !terms.is_empty() {
+            match entries.iter_mut().rev().find_map(|entry| match entry {
+                ContextEntry::Definition(definition) => Some(definition),
+                ContextEntry::Iri(_) => None,
+            }) {
+                Some(definition) => definition.terms.extend(terms),
+                None => entries.push(ContextEntry::Definition(ContextDefinition {
+                    terms: terms.into_iter().collect(),
+                    ..Default::default()
+                })),
+            }
+        }
+        self.entries = match entries.len() {
+            1 => OneOrMany::One(entries.remove(0)),
+            _ => OneOrMany::Many(entries),
+        };
+    }
+
+    /// Processes the context, resolving the remote contexts it references
+    /// with `loader`.
+    pub fn resolve(
+        &self,
+        loader: &dyn jsonld::ContextLoader,
+    ) -> crate::Result<jsonld::ActiveContext> {
+        let context = serde_json::to_value(self).map_err(crate::Error::Serialize)?;
+        jsonld::ActiveContext::parse(&context, loader)
+    }
+}
+
+impl From<ContextEntry> for Context {
+    fn from(entry: ContextEntry) -> Self {
+        Context {
+            entries: OneOrMany::One(entry),
+        }
+    }
+}
+
+impl From<Vec<ContextEntry>> for Context {
+    fn from(entries: Vec<ContextEntry>) -> Self {
+        Context {
+            entries: OneOrMany::Many(entries),
+        }
+    }
+}
+
+/// A single member of an `@context`: either a reference to a remote context
+/// or an inline context definition.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ContextEntry {
+    Iri(String),
+    Definition(ContextDefinition),
+}
+
+impl ContextEntry {
+    pub fn as_iri(&self) -> Option<&str> {
+        match self {
+            ContextEntry::Iri(iri) => Some(iri),
+            _ => None,
+        }
+    }
+
+    pub fn as_definition(&self) -> Option<&ContextDefinition> {
+        match self {
+            ContextEntry::Definition(definition) => Some(definition),
+            _ => None,
+        }
+    }
+}
+
+/// An inline context definition: the `@vocab`, `@language`, `@base`,
+/// `@version`, `@protected` and `@propagate` keywords and any number of term
+/// definitions.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ContextDefinition {
+    #[serde(rename = "@vocab", skip_serializing_if = "Option::is_none")]
+    pub vocab: Option<String>,
+
+    #[serde(rename = "@language", skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    #[serde(rename = "@base", skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+
+    #[serde(rename = "@version", skip_serializing_if = "Option::is_none")]
+    pub version: Option<f64>,
+
+    /// Whether the terms defined here may not be redefined by later
+    /// contexts.
+    #[serde(rename = "@protected", skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,
 
-    #[serde(skip_serializing_if = "Option::is_none", rename = "@language")]
-    language: Option<String>,
+    /// Whether the definition applies to the nodes nested in the one it
+    /// appears in.
+    #[serde(rename = "@propagate", skip_serializing_if = "Option::is_none")]
+    pub propagate: Option<bool>,
+
+    #[serde(flatten)]
+    pub terms: BTreeMap<String, TermDefinition>,
+}
+
+/// The definition of a single term, either the (compact) IRI it expands to,
+/// e.g. `"toot": "http://joinmastodon.org/ns#"` or
+/// `"sensitive": "as:sensitive"`, or an expanded definition such as
+/// `"featured": {"@id": "toot:featured", "@type": "@id"}`. A term defined
+/// as `null` is explicitly left undefined, e.g. to keep an inherited
+/// definition from applying.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum TermDefinition {
+    Iri(String),
+    Expanded(ExpandedTermDefinition),
+    Null,
+}
+
+impl TermDefinition {
+    /// The (possibly compact) IRI the term maps to.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            TermDefinition::Iri(iri) => Some(iri),
+            TermDefinition::Expanded(definition) => definition.id.as_deref(),
+            TermDefinition::Null => None,
+        }
+    }
+}
+
+impl From<String> for TermDefinition {
+    fn from(iri: String) -> Self {
+        TermDefinition::Iri(iri)
+    }
+}
+
+impl From<&str> for TermDefinition {
+    fn from(iri: &str) -> Self {
+        TermDefinition::Iri(iri.to_string())
+    }
+}
+
+impl From<ExpandedTermDefinition> for TermDefinition {
+    fn from(definition: ExpandedTermDefinition) -> Self {
+        TermDefinition::Expanded(definition)
+    }
+}
+
+/// An expanded term definition. Keywords not modelled here are kept in
+/// `other`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ExpandedTermDefinition {
+    #[serde(rename = "@id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    pub term_type: Option<String>,
+
+    /// The container mapping, e.g. `@list`, or `["@set", "@language"]`.
+    #[serde(rename = "@container", skip_serializing_if = "Option::is_none")]
+    pub container: Option<OneOrMany<String>>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl ExpandedTermDefinition {
+    pub fn new(id: String) -> Self {
+        ExpandedTermDefinition {
+            id: Some(id),
+            ..Default::default()
+        }
+    }
+
+    /// Sets `@type`, e.g. `@id` for terms whose values are IRIs.
+    pub fn term_type(mut self, term_type: String) -> Self {
+        self.term_type = Some(term_type);
+        self
+    }
+
+    pub fn container(mut self, container: impl Into<OneOrMany<String>>) -> Self {
+        self.container = Some(container.into());
+        self
+    }
 }
 
 /// Builder struct for [Context].
+///
+/// [ContextBuilder::new] starts from `{"@vocab": ACTIVITY_STREAMS}`, while
+/// [ContextBuilder::activity_streams] references the Activity Streams context
+/// by IRI, which also makes its `as:` prefix available to term definitions.
 pub struct ContextBuilder {
-    namespace: String,
-    language: Option<String>,
+    iris: Vec<String>,
+    definition: ContextDefinition,
 }
 
 impl ContextBuilder {
-    const NAMESPACE: &'static str = "https://www.w3.org/ns/activitystreams";
+    const NAMESPACE: &'static str = Context::ACTIVITY_STREAMS;
 
     pub fn new() -> Self {
         ContextBuilder {
-            namespace: ContextBuilder::NAMESPACE.to_string(),
-            language: None,
+            iris: Vec::new(),
+            definition: ContextDefinition {
+                vocab: Some(ContextBuilder::NAMESPACE.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn activity_streams() -> Self {
+        ContextBuilder {
+            iris: vec![ContextBuilder::NAMESPACE.to_string()],
+            definition: ContextDefinition::default(),
         }
     }
 
-    // TODO: extend this to other options per the docs
     pub fn language(mut self, language: String) -> Self {
-        self.language = Some(language);
+        self.definition.language = Some(language);
+        self
+    }
+
+    /// Adds a reference to a remote context, e.g.
+    /// `https://w3id.org/security/v1`.
+    pub fn add_context(mut self, iri: Iri) -> Self {
+        self.iris.push(iri.into());
+        self
+    }
+
+    /// Adds a term definition, e.g. `add_term("toot", "http://joinmastodon.org/ns#")`
+    /// or `add_term("sensitive", "as:sensitive")`.
+    pub fn add_term(mut self, term: String, definition: impl Into<TermDefinition>) -> Self {
+        self.definition.terms.insert(term, definition.into());
+        self
+    }
+
+    /// Adds the remote contexts and term definitions of the extension `E`.
+    pub fn extension<E: Extension>(mut self) -> Self {
+        for iri in E::contexts() {
+            if !self.iris.contains(&iri) {
+                self.iris.push(iri);
+            }
+        }
+        self.definition.terms.extend(E::terms());
         self
     }
 
     pub fn build(self) -> Context {
-        Context {
-            namespace: self.namespace,
-            language: self.language,
+        let mut entries: Vec<ContextEntry> = self.iris.into_iter().map(ContextEntry::Iri).collect();
+        if self.definition != ContextDefinition::default() {
+            entries.push(ContextEntry::Definition(self.definition));
+        }
+        match entries.len() {
+            1 => Context::from(entries.remove(0)),
+            _ => Context::from(entries),
         }
     }
 }
@@ -97,59 +452,632 @@ impl Default for ContextBuilder {
 /// mediaType | duration
 /// All properties are optional (including the id and type).
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Object<AttributedToT> {
+pub struct Object<AttributedToT, ExtensionT = Null> {
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
-    pub object_type: Option<String>,
+    pub object_type: Option<ObjectType>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
+    pub id: Option<Iri>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub url: Option<String>,
+    pub url: Option<OneOrMany<LinkReference>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub published: Option<DateTime<Utc>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub image: Option<Box<Link>>,
+    pub image: Option<OneOrMany<Reference<Object<Null>>>>,
 
-    #[serde(
-        rename = "attributedTo",
-        skip_serializing_if = "Vec::is_empty",
-        default = "Vec::new"
-    )]
-    pub attributed_to: Vec<AttributedToT>,
+    #[serde(rename = "attributedTo", skip_serializing_if = "Option::is_none")]
+    pub attributed_to: Option<OneOrMany<Reference<AttributedToT>>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub audience: Option<Box<Object<Null>>>,
+    pub audience: Option<OneOrMany<Reference<Object<Null>>>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(rename = "contentMap", skip_serializing_if = "Option::is_none")]
+    pub content_map: Option<LanguageMap>,
+
+    #[serde(rename = "nameMap", skip_serializing_if = "Option::is_none")]
+    pub name_map: Option<LanguageMap>,
+
+    #[serde(rename = "summaryMap", skip_serializing_if = "Option::is_none")]
+    pub summary_map: Option<LanguageMap>,
+
+    #[serde(rename = "startTime", skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<DateTime<Utc>>,
+
+    #[serde(rename = "endTime", skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(rename = "inReplyTo", skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replies: Option<Reference<Collection<Reference<Object<Null>>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bto: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cc: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bcc: Option<OneOrMany<Reference<Object<Null>>>>,
+
+    #[serde(rename = "mediaType", skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+
+    /// Properties of the typed [Extension], if any.
+    #[serde(flatten)]
+    pub extension: ExtensionT,
+
+    /// Members not defined by Activity Streams, such as vendor extensions,
+    /// kept so they are re-emitted on serialization.
+    #[serde(flatten)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
 }
 
-impl<AttributedToT> Serde for Object<AttributedToT> where AttributedToT: Serde + Clone {}
+impl<AttributedToT, ExtensionT> Serde for Object<AttributedToT, ExtensionT>
+where
+    AttributedToT: Serde + Clone,
+    ExtensionT: Extension,
+{
+    fn extend_context(context: &mut Context) {
+        context.extend::<ExtensionT>();
+    }
+}
+
+impl<AttributedToT, ExtensionT> Object<AttributedToT, ExtensionT> {
+    /// Whether `object_type` is, or includes, the given type.
+    pub fn has_type(&self, object_type: &str) -> bool {
+        self.object_type
+            .as_ref()
+            .is_some_and(|t| t.has_type(object_type))
+    }
+
+    /// The `content` best matching `languages`, in order of preference,
+    /// taken from `contentMap` and falling back to the plain `content`.
+    pub fn content_for(&self, languages: &[&str]) -> Option<&str> {
+        language::resolve(
+            self.content_map.as_ref(),
+            self.content.as_deref(),
+            languages,
+            None,
+        )
+    }
+
+    /// Like [Object::content_for], but treats the plain `content` as being in
+    /// the `@language` of `context`.
+    pub fn content_in(&self, context: &Context, languages: &[&str]) -> Option<&str> {
+        language::resolve(
+            self.content_map.as_ref(),
+            self.content.as_deref(),
+            languages,
+            context.language(),
+        )
+    }
+
+    /// The `name` best matching `languages`; see [Object::content_for].
+    pub fn name_for(&self, languages: &[&str]) -> Option<&str> {
+        language::resolve(
+            self.name_map.as_ref(),
+            self.name.as_deref(),
+            languages,
+            None,
+        )
+    }
+
+    /// The `name` best matching `languages`; see [Object::content_in].
+    pub fn name_in(&self, context: &Context, languages: &[&str]) -> Option<&str> {
+        language::resolve(
+            self.name_map.as_ref(),
+            self.name.as_deref(),
+            languages,
+            context.language(),
+        )
+    }
+
+    /// The `summary` best matching `languages`; see [Object::content_for].
+    pub fn summary_for(&self, languages: &[&str]) -> Option<&str> {
+        language::resolve(
+            self.summary_map.as_ref(),
+            self.summary.as_deref(),
+            languages,
+            None,
+        )
+    }
+
+    /// The `summary` best matching `languages`; see [Object::content_in].
+    pub fn summary_in(&self, context: &Context, languages: &[&str]) -> Option<&str> {
+        language::resolve(
+            self.summary_map.as_ref(),
+            self.summary.as_deref(),
+            languages,
+            context.language(),
+        )
+    }
+}
+
+/// The value of the `type` property. Activity Streams allows either a single
+/// type or an array of types (e.g. `["Note", "toot:Custom"]`); the original
+/// shape is preserved when serializing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ObjectType {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl ObjectType {
+    /// Whether this is, or includes, the given type.
+    pub fn has_type(&self, object_type: &str) -> bool {
+        self.iter().any(|t| t == object_type)
+    }
+
+    /// Iterates over every type, in document order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let types: &[String] = match self {
+            ObjectType::Single(t) => std::slice::from_ref(t),
+            ObjectType::Multiple(ts) => ts,
+        };
+        types.iter().map(String::as_str)
+    }
+}
+
+impl From<String> for ObjectType {
+    fn from(object_type: String) -> Self {
+        ObjectType::Single(object_type)
+    }
+}
+
+impl From<&str> for ObjectType {
+    fn from(object_type: &str) -> Self {
+        ObjectType::Single(object_type.to_string())
+    }
+}
+
+impl From<Vec<String>> for ObjectType {
+    fn from(object_types: Vec<String>) -> Self {
+        ObjectType::Multiple(object_types)
+    }
+}
+
+/// A reference to another entity. Most properties that point at an [Object]
+/// (e.g. `actor`, `object`, `attributedTo` or `image`) may be given as a bare
+/// IRI, an embedded object, or a [Link].
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Reference<T> {
+    Iri(Iri),
+    Link(Box<Link>),
+    Object(Box<T>),
+}
+
+/// Unlike `#[serde(untagged)]`, reports why the value didn't parse as the
+/// form it was given in rather than that it matched none of them.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Reference<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        if let Value::String(iri) = value {
+            return Iri::try_from(iri)
+                .map(Reference::Iri)
+                .map_err(crate::error::raise);
+        }
+        if let Ok(link) = Link::deserialize(&value) {
+            return Ok(Reference::Link(Box::new(link)));
+        }
+        // Whatever made it not a link doesn't matter for an object.
+        crate::error::forget_raised();
+        T::deserialize(value)
+            .map(|object| Reference::Object(Box::new(object)))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl<T> Reference<T> {
+    pub fn as_iri(&self) -> Option<&Iri> {
+        match self {
+            Reference::Iri(iri) => Some(iri),
+            _ => None,
+        }
+    }
+
+    pub fn as_link(&self) -> Option<&Link> {
+        match self {
+            Reference::Link(link) => Some(link),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&T> {
+        match self {
+            Reference::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+}
+
+impl<AttributedToT, ExtensionT> Reference<Object<AttributedToT, ExtensionT>> {
+    /// The IRI referred to: the IRI itself, the `id` of an embedded object or
+    /// the `href` of a link.
+    pub fn id(&self) -> Option<&Iri> {
+        match self {
+            Reference::Iri(iri) => Some(iri),
+            Reference::Link(link) => Some(&link.href.href),
+            Reference::Object(object) => object.id.as_ref(),
+        }
+    }
+}
+
+impl<T> Serde for Reference<T>
+where
+    T: Serde,
+{
+    fn extend_context(context: &mut Context) {
+        T::extend_context(context);
+    }
+}
+
+impl<T> From<Iri> for Reference<T> {
+    fn from(iri: Iri) -> Self {
+        Reference::Iri(iri)
+    }
+}
+
+impl<T> From<http::Uri> for Reference<T> {
+    fn from(uri: http::Uri) -> Self {
+        Reference::Iri(uri.into())
+    }
+}
+
+impl<T> From<Link> for Reference<T> {
+    fn from(link: Link) -> Self {
+        Reference::Link(Box::new(link))
+    }
+}
+
+impl<T> From<LinkBuilder> for Reference<T> {
+    fn from(link: LinkBuilder) -> Self {
+        Reference::Link(Box::new(link.build()))
+    }
+}
+
+impl<AttributedToT, ExtensionT> From<Object<AttributedToT, ExtensionT>>
+    for Reference<Object<AttributedToT, ExtensionT>>
+{
+    fn from(object: Object<AttributedToT, ExtensionT>) -> Self {
+        Reference::Object(Box::new(object))
+    }
+}
+
+impl<CollectionT> From<Collection<CollectionT>> for Reference<Collection<CollectionT>> {
+    fn from(collection: Collection<CollectionT>) -> Self {
+        Reference::Object(Box::new(collection))
+    }
+}
+
+impl<AttributedToT, ExtensionT> From<ObjectBuilder<AttributedToT, ExtensionT>>
+    for Reference<Object<AttributedToT, ExtensionT>>
+where
+    AttributedToT: Serde + Clone,
+    ExtensionT: Clone,
+{
+    fn from(object: ObjectBuilder<AttributedToT, ExtensionT>) -> Self {
+        Reference::Object(Box::new(object.build()))
+    }
+}
+
+/// The value of `url`: the IRI of a representation of an object, or a [Link]
+/// to it. Unlike a [Reference], it is never an embedded object.
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum LinkReference {
+    Iri(Iri),
+    Link(Box<Link>),
+}
+
+impl LinkReference {
+    pub fn as_iri(&self) -> Option<&Iri> {
+        match self {
+            LinkReference::Iri(iri) => Some(iri),
+            LinkReference::Link(_) => None,
+        }
+    }
+
+    pub fn as_link(&self) -> Option<&Link> {
+        match self {
+            LinkReference::Link(link) => Some(link),
+            LinkReference::Iri(_) => None,
+        }
+    }
+
+    /// The IRI linked to: the IRI itself or the `href` of the link.
+    pub fn href(&self) -> &Iri {
+        match self {
+            LinkReference::Iri(iri) => iri,
+            LinkReference::Link(link) => &link.href.href,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LinkReference {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(iri) => Iri::try_from(iri)
+                .map(LinkReference::Iri)
+                .map_err(crate::error::raise),
+            value => Link::deserialize(value)
+                .map(|link| LinkReference::Link(Box::new(link)))
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl From<Iri> for LinkReference {
+    fn from(iri: Iri) -> Self {
+        LinkReference::Iri(iri)
+    }
+}
+
+impl From<http::Uri> for LinkReference {
+    fn from(uri: http::Uri) -> Self {
+        LinkReference::Iri(uri.into())
+    }
+}
+
+impl From<Link> for LinkReference {
+    fn from(link: Link) -> Self {
+        LinkReference::Link(Box::new(link))
+    }
+}
+
+impl From<LinkBuilder> for LinkReference {
+    fn from(link: LinkBuilder) -> Self {
+        LinkReference::Link(Box::new(link.build()))
+    }
+}
+
+/// The value of a non-functional property, which may be given either as a
+/// single value or as an array of values. Either form is accepted on input;
+/// on output a single value is always bare, whichever variant holds it, and
+/// several values are an array, as in compacted JSON-LD.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T: Serialize> Serialize for OneOrMany<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.as_slice() {
+            [value] => value.serialize(serializer),
+            values => values.serialize(serializer),
+        }
+    }
+}
+
+/// Unlike `#[serde(untagged)]`, reports why the values didn't parse rather
+/// than that they matched neither form.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OneOrMany<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            values @ Value::Array(_) => Vec::deserialize(values).map(OneOrMany::Many),
+            value => T::deserialize(value).map(OneOrMany::One),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+impl<T> OneOrMany<T> {
+    /// Returns the first value, if any.
+    pub fn first(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    /// Returns the values as a slice, regardless of the form they were given
+    /// in.
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            OneOrMany::Many(values) => values,
+            OneOrMany::One(value) => std::slice::from_ref(value),
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match self {
+            OneOrMany::Many(values) => values,
+            OneOrMany::One(value) => std::slice::from_mut(value),
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Consumes the value, returning every value as a [Vec].
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::Many(values) => values,
+            OneOrMany::One(value) => vec![value],
+        }
+    }
+
+    /// Appends a value, turning a single value into an array.
+    pub fn push(&mut self, value: T) {
+        match self {
+            OneOrMany::Many(values) => values.push(value),
+            OneOrMany::One(_) => {
+                let one = std::mem::replace(self, OneOrMany::Many(Vec::with_capacity(2)));
+                if let (OneOrMany::One(first), OneOrMany::Many(values)) = (one, self) {
+                    values.push(first);
+                    values.push(value);
+                }
+            }
+        }
+    }
+}
+
+/// Deserializes a property holding a single value or an array of values into
+/// a [Vec], as JSON-LD makes no difference between a value and an array of
+/// that one value.
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    OneOrMany::deserialize(deserializer).map(OneOrMany::into_vec)
+}
+
+impl<T> Serde for OneOrMany<T>
+where
+    T: Serde,
+{
+    fn extend_context(context: &mut Context) {
+        T::extend_context(context);
+    }
+}
+
+/// Appends `value` to a non-functional property, setting it to the single
+/// value if it is not set yet.
+pub(crate) fn push_value<T>(property: &mut Option<OneOrMany<T>>, value: T) {
+    match property {
+        Some(values) => values.push(value),
+        None => *property = Some(OneOrMany::One(value)),
+    }
+}
+
+impl<T> From<T> for OneOrMany<T> {
+    fn from(value: T) -> Self {
+        OneOrMany::One(value)
+    }
+}
+
+impl<T> From<Vec<T>> for OneOrMany<T> {
+    fn from(values: Vec<T>) -> Self {
+        OneOrMany::Many(values)
+    }
+}
+
+impl<T> FromIterator<T> for OneOrMany<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        OneOrMany::Many(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for OneOrMany<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a OneOrMany<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut OneOrMany<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
 
 /// Builder for [Object].
 #[derive(Clone)]
-pub struct ObjectBuilder<AttributedToT> {
-    object_type: Option<String>,
-    // TODO: actually an IRI: consider https://docs.rs/iref/latest/iref/
-    id: Option<http::Uri>,
+pub struct ObjectBuilder<AttributedToT, ExtensionT = Null> {
+    object_type: Option<ObjectType>,
+    id: Option<Iri>,
     name: Option<String>,
-    url: Option<http::Uri>,
+    url: Option<OneOrMany<LinkReference>>,
     published: Option<DateTime<Utc>>,
-    image: Option<LinkBuilder>,
-    attributed_to: Vec<AttributedToT>,
-    audience: Option<Box<ObjectBuilder<Null>>>,
+    image: Option<OneOrMany<Reference<Object<Null>>>>,
+    attributed_to: Option<OneOrMany<Reference<AttributedToT>>>,
+    audience: Option<OneOrMany<Reference<Object<Null>>>>,
     content: Option<String>,
     summary: Option<String>,
-    // TODO: more fields
+    attachment: Option<OneOrMany<Reference<Object<Null>>>>,
+    context: Option<OneOrMany<Reference<Object<Null>>>>,
+    content_map: Option<LanguageMap>,
+    name_map: Option<LanguageMap>,
+    summary_map: Option<LanguageMap>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+    duration: Option<String>,
+    generator: Option<OneOrMany<Reference<Object<Null>>>>,
+    icon: Option<OneOrMany<Reference<Object<Null>>>>,
+    in_reply_to: Option<OneOrMany<Reference<Object<Null>>>>,
+    location: Option<OneOrMany<Reference<Object<Null>>>>,
+    preview: Option<OneOrMany<Reference<Object<Null>>>>,
+    replies: Option<Reference<Collection<Reference<Object<Null>>>>>,
+    tag: Option<OneOrMany<Reference<Object<Null>>>>,
+    updated: Option<DateTime<Utc>>,
+    to: Option<OneOrMany<Reference<Object<Null>>>>,
+    bto: Option<OneOrMany<Reference<Object<Null>>>>,
+    cc: Option<OneOrMany<Reference<Object<Null>>>>,
+    bcc: Option<OneOrMany<Reference<Object<Null>>>>,
+    media_type: Option<String>,
+    extension: ExtensionT,
+    extensions: serde_json::Map<String, serde_json::Value>,
 }
 
 impl<AttributedToT: Serde + Clone> ObjectBuilder<AttributedToT> {
@@ -161,51 +1089,80 @@ impl<AttributedToT: Serde + Clone> ObjectBuilder<AttributedToT> {
             url: None,
             published: None,
             image: None,
-            attributed_to: vec![],
+            attributed_to: None,
             audience: None,
             content: None,
             summary: None,
+            attachment: None,
+            context: None,
+            content_map: None,
+            name_map: None,
+            summary_map: None,
+            start_time: None,
+            end_time: None,
+            duration: None,
+            generator: None,
+            icon: None,
+            in_reply_to: None,
+            location: None,
+            preview: None,
+            replies: None,
+            tag: None,
+            updated: None,
+            to: None,
+            bto: None,
+            cc: None,
+            bcc: None,
+            media_type: None,
+            extension: Null {},
+            extensions: serde_json::Map::new(),
         }
     }
+}
 
-    pub fn object_type(mut self, object_type: String) -> Self {
-        self.object_type = Some(object_type);
+impl<AttributedToT, ExtensionT> ObjectBuilder<AttributedToT, ExtensionT>
+where
+    AttributedToT: Serde + Clone,
+    ExtensionT: Clone,
+{
+    pub fn object_type(mut self, object_type: impl Into<ObjectType>) -> Self {
+        self.object_type = Some(object_type.into());
         self
     }
 
-    pub fn id(&mut self, id: http::Uri) -> Self {
+    pub fn id(mut self, id: Iri) -> Self {
         self.id = Some(id);
-        self.clone()
+        self
     }
 
-    pub fn name(&mut self, name: String) -> Self {
+    pub fn name(mut self, name: String) -> Self {
         self.name = Some(name);
-        self.clone()
+        self
     }
 
-    pub fn url(&mut self, url: http::Uri) -> Self {
-        self.url = Some(url);
-        self.clone()
+    pub fn url(mut self, url: impl Into<LinkReference>) -> Self {
+        self.url = Some(OneOrMany::One(url.into()));
+        self
     }
 
-    pub fn published(&mut self, datetime: DateTime<Utc>) -> Self {
+    pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
         self.published = Some(datetime);
-        self.clone()
+        self
     }
 
-    pub fn image(&mut self, image: LinkBuilder) -> Self {
-        self.image = Some(image);
-        self.clone()
+    pub fn image(mut self, image: impl Into<Reference<Object<Null>>>) -> Self {
+        self.image = Some(OneOrMany::One(image.into()));
+        self
     }
 
-    pub fn add_attributed_to(mut self, attribution: AttributedToT) -> Self {
-        self.attributed_to.push(attribution);
+    pub fn add_attributed_to(mut self, attribution: impl Into<Reference<AttributedToT>>) -> Self {
+        push_value(&mut self.attributed_to, attribution.into());
         self
     }
 
-    pub fn audience(&mut self, audience: ObjectBuilder<Null>) -> Self {
-        self.audience = Some(Box::new(audience));
-        self.clone()
+    pub fn audience(mut self, audience: impl Into<Reference<Object<Null>>>) -> Self {
+        self.audience = Some(OneOrMany::One(audience.into()));
+        self
     }
 
     pub fn content(mut self, content: String) -> Self {
@@ -213,23 +1170,227 @@ impl<AttributedToT: Serde + Clone> ObjectBuilder<AttributedToT> {
         self
     }
 
-    pub fn summary(&mut self, summary: String) -> Self {
+    pub fn summary(mut self, summary: String) -> Self {
         self.summary = Some(summary);
-        self.clone()
+        self
+    }
+
+    pub fn add_attachment(mut self, attachment: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.attachment, attachment.into());
+        self
+    }
+
+    pub fn context(mut self, context: impl Into<Reference<Object<Null>>>) -> Self {
+        self.context = Some(OneOrMany::One(context.into()));
+        self
+    }
+
+    pub fn content_map(mut self, content_map: LanguageMap) -> Self {
+        self.content_map = Some(content_map);
+        self
+    }
+
+    /// Adds the content in a single language to `contentMap`.
+    pub fn add_content_map(mut self, language: LanguageTag, content: String) -> Self {
+        self.content_map
+            .get_or_insert_with(LanguageMap::new)
+            .insert(language, content);
+        self
+    }
+
+    pub fn name_map(mut self, name_map: LanguageMap) -> Self {
+        self.name_map = Some(name_map);
+        self
+    }
+
+    /// Adds the name in a single language to `nameMap`.
+    pub fn add_name_map(mut self, language: LanguageTag, name: String) -> Self {
+        self.name_map
+            .get_or_insert_with(LanguageMap::new)
+            .insert(language, name);
+        self
+    }
+
+    pub fn summary_map(mut self, summary_map: LanguageMap) -> Self {
+        self.summary_map = Some(summary_map);
+        self
+    }
+
+    /// Adds the summary in a single language to `summaryMap`.
+    pub fn add_summary_map(mut self, language: LanguageTag, summary: String) -> Self {
+        self.summary_map
+            .get_or_insert_with(LanguageMap::new)
+            .insert(language, summary);
+        self
+    }
+
+    pub fn start_time(mut self, datetime: DateTime<Utc>) -> Self {
+        self.start_time = Some(datetime);
+        self
+    }
+
+    pub fn end_time(mut self, datetime: DateTime<Utc>) -> Self {
+        self.end_time = Some(datetime);
+        self
+    }
+
+    /// Sets the duration as an `xsd:duration`, e.g. "PT2H".
+    pub fn duration(mut self, duration: String) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn generator(mut self, generator: impl Into<Reference<Object<Null>>>) -> Self {
+        self.generator = Some(OneOrMany::One(generator.into()));
+        self
+    }
+
+    pub fn icon(mut self, icon: impl Into<Reference<Object<Null>>>) -> Self {
+        self.icon = Some(OneOrMany::One(icon.into()));
+        self
+    }
+
+    pub fn in_reply_to(mut self, in_reply_to: impl Into<Reference<Object<Null>>>) -> Self {
+        self.in_reply_to = Some(OneOrMany::One(in_reply_to.into()));
+        self
+    }
+
+    pub fn location(mut self, location: impl Into<Reference<Object<Null>>>) -> Self {
+        self.location = Some(OneOrMany::One(location.into()));
+        self
+    }
+
+    pub fn preview(mut self, preview: impl Into<Reference<Object<Null>>>) -> Self {
+        self.preview = Some(OneOrMany::One(preview.into()));
+        self
+    }
+
+    pub fn replies(
+        mut self,
+        replies: impl Into<Reference<Collection<Reference<Object<Null>>>>>,
+    ) -> Self {
+        self.replies = Some(replies.into());
+        self
+    }
+
+    pub fn add_tag(mut self, tag: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.tag, tag.into());
+        self
+    }
+
+    pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+        self.updated = Some(datetime);
+        self
+    }
+
+    pub fn add_to(mut self, to: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.to, to.into());
+        self
+    }
+
+    pub fn add_bto(mut self, bto: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.bto, bto.into());
+        self
+    }
+
+    pub fn add_cc(mut self, cc: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.cc, cc.into());
+        self
+    }
+
+    pub fn add_bcc(mut self, bcc: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.bcc, bcc.into());
+        self
+    }
+
+    pub fn media_type(mut self, media_type: String) -> Self {
+        self.media_type = Some(media_type);
+        self
+    }
+
+    /// Sets a member not defined by Activity Streams, e.g. `sensitive`.
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.extensions.insert(name, value);
+        self
+    }
+
+    /// Attaches the typed extension `extension`, replacing any previous one.
+    pub fn with_extension<OtherT: Extension>(
+        self,
+        extension: OtherT,
+    ) -> ObjectBuilder<AttributedToT, OtherT> {
+        ObjectBuilder {
+            id: self.id,
+            object_type: self.object_type,
+            name: self.name,
+            url: self.url,
+            published: self.published,
+            image: self.image,
+            attributed_to: self.attributed_to,
+            audience: self.audience,
+            content: self.content,
+            summary: self.summary,
+            attachment: self.attachment,
+            context: self.context,
+            content_map: self.content_map,
+            name_map: self.name_map,
+            summary_map: self.summary_map,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            duration: self.duration,
+            generator: self.generator,
+            icon: self.icon,
+            in_reply_to: self.in_reply_to,
+            location: self.location,
+            preview: self.preview,
+            replies: self.replies,
+            tag: self.tag,
+            updated: self.updated,
+            to: self.to,
+            bto: self.bto,
+            cc: self.cc,
+            bcc: self.bcc,
+            media_type: self.media_type,
+            extension,
+            extensions: self.extensions,
+        }
     }
 
-    pub fn build(self) -> Object<AttributedToT> {
+    pub fn build(self) -> Object<AttributedToT, ExtensionT> {
         Object {
             object_type: self.object_type,
-            id: self.id.map(|uri| uri.to_string()),
+            id: self.id,
             name: self.name,
-            url: self.url.map(|uri| uri.to_string()),
+            url: self.url,
             published: self.published,
-            image: self.image.map(|i| Box::new(i.build())),
+            image: self.image,
             attributed_to: self.attributed_to,
-            audience: self.audience.map(|a| Box::new(a.build())),
+            audience: self.audience,
             content: self.content,
             summary: self.summary,
+            attachment: self.attachment,
+            context: self.context,
+            content_map: self.content_map,
+            name_map: self.name_map,
+            summary_map: self.summary_map,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            duration: self.duration,
+            generator: self.generator,
+            icon: self.icon,
+            in_reply_to: self.in_reply_to,
+            location: self.location,
+            preview: self.preview,
+            replies: self.replies,
+            tag: self.tag,
+            updated: self.updated,
+            to: self.to,
+            bto: self.bto,
+            cc: self.cc,
+            bcc: self.bcc,
+            media_type: self.media_type,
+            extension: self.extension,
+            extensions: self.extensions,
         }
     }
 }
@@ -243,7 +1404,7 @@ impl<AttributedToT: Serde + Clone> Default for ObjectBuilder<AttributedToT> {
 /// A utility struct to describe a URI.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Uri {
-    pub href: String,
+    pub href: Iri,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "mediaType")]
@@ -255,12 +1416,12 @@ impl Serde for Uri {}
 /// Builder struct for [Uri].
 #[derive(Clone)]
 pub struct UriBuilder {
-    href: http::Uri,
+    href: Iri,
     media_type: Option<String>,
 }
 
 impl UriBuilder {
-    pub fn new(href: http::Uri) -> Self {
+    pub fn new(href: Iri) -> Self {
         UriBuilder {
             href,
             media_type: None,
@@ -274,7 +1435,7 @@ impl UriBuilder {
 
     pub fn build(self) -> Uri {
         Uri {
-            href: self.href.to_string(),
+            href: self.href,
             media_type: self.media_type,
         }
     }
@@ -286,9 +1447,6 @@ pub struct Preview {
     #[serde(flatten)]
     pub base: Object<Null>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub duration: Option<String>,
-
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<Uri>,
 }
@@ -298,7 +1456,6 @@ impl Serde for Preview {}
 /// Builder for [Preview].
 pub struct PreviewBuilder {
     base: ObjectBuilder<Null>,
-    duration: Option<String>,
     url: Option<Uri>,
 }
 
@@ -306,13 +1463,12 @@ impl PreviewBuilder {
     pub fn new(preview_type: String, name: String) -> Self {
         PreviewBuilder {
             base: ObjectBuilder::new().object_type(preview_type).name(name),
-            duration: None,
             url: None,
         }
     }
 
     pub fn duration(mut self, dur: String) -> Self {
-        self.duration = Some(dur);
+        self.base = self.base.duration(dur);
         self
     }
 
@@ -321,10 +1477,14 @@ impl PreviewBuilder {
         self
     }
 
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
+    }
+
     pub fn build(self) -> Preview {
         Preview {
             base: self.base.build(),
-            duration: self.duration,
             url: self.url,
         }
     }
@@ -346,14 +1506,14 @@ pub struct Link {
     #[serde(flatten)]
     pub href: Uri,
 
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
-    pub rel: Vec<String>, // TODO: RFC5988 validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel: Option<OneOrMany<String>>, // TODO: RFC5988 validation
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub hreflang: Option<String>, // TODO: BCP47 language tag
+    pub hreflang: Option<LanguageTag>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<u32>,
@@ -363,6 +1523,11 @@ pub struct Link {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preview: Option<Preview>,
+
+    /// Members not defined by Activity Streams, kept so they are re-emitted
+    /// on serialization.
+    #[serde(flatten)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Link {
@@ -374,30 +1539,40 @@ impl Serde for Link {}
 /// Builder for a [Link] struct.
 #[derive(Clone)]
 pub struct LinkBuilder {
+    link_type: String,
     href: UriBuilder,
-    rel: Vec<String>, // TODO: RFC5988 validation
+    rel: Option<OneOrMany<String>>, // TODO: RFC5988 validation
     name: Option<String>,
-    hreflang: Option<String>, // TODO: BCP47 language tag
+    hreflang: Option<LanguageTag>,
     height: Option<u32>,
     width: Option<u32>,
     preview: Option<Preview>,
+    extensions: serde_json::Map<String, serde_json::Value>,
 }
 
 impl LinkBuilder {
     pub fn new(href: UriBuilder) -> Self {
         LinkBuilder {
+            link_type: Link::TYPE.to_string(),
             href,
-            rel: Vec::new(),
+            rel: None,
             name: None,
             hreflang: None,
             height: None,
             width: None,
             preview: None,
+            extensions: serde_json::Map::new(),
         }
     }
 
+    /// Sets the `type` of the link, `Link` unless given, e.g. `Mention`.
+    pub fn link_type(mut self, link_type: String) -> Self {
+        self.link_type = link_type;
+        self
+    }
+
     pub fn add_rel(mut self, rel: String) -> Self {
-        self.rel.push(rel);
+        push_value(&mut self.rel, rel);
         self
     }
 
@@ -406,7 +1581,7 @@ impl LinkBuilder {
         self
     }
 
-    pub fn hreflang(mut self, hreflang: String) -> Self {
+    pub fn hreflang(mut self, hreflang: LanguageTag) -> Self {
         self.hreflang = Some(hreflang);
         self
     }
@@ -426,9 +1601,15 @@ impl LinkBuilder {
         self
     }
 
+    /// Sets a member not defined by Activity Streams.
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.extensions.insert(name, value);
+        self
+    }
+
     pub fn build(self) -> Link {
         Link {
-            link_type: Link::TYPE.to_string(),
+            link_type: self.link_type,
             href: self.href.build(),
             rel: self.rel,
             name: self.name,
@@ -436,6 +1617,7 @@ impl LinkBuilder {
             height: self.height,
             width: self.width,
             preview: self.preview,
+            extensions: self.extensions,
         }
     }
 }
@@ -451,17 +1633,17 @@ pub struct Activity {
     base: Object<Null>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub actor: Option<Actor>,
+    pub actor: Option<OneOrMany<Reference<Actor>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub object: Option<Object<Null>>,
+    pub object: Option<OneOrMany<Reference<Object<Null>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub target: Option<Object<Null>>,
+    pub target: Option<OneOrMany<Reference<Object<Null>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub result: Option<String>,
+    pub result: Option<OneOrMany<Reference<Object<Null>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub origin: Option<String>, // TODO: Origin
+    pub origin: Option<OneOrMany<Reference<Object<Null>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub instrument: Option<String>, // TODO: Instrument
+    pub instrument: Option<OneOrMany<Reference<Object<Null>>>>,
 }
 
 impl Serde for Activity {}
@@ -474,16 +1656,22 @@ impl std::ops::Deref for Activity {
     }
 }
 
+impl std::ops::DerefMut for Activity {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
 /// Builder for an [Activity].
 #[derive(Clone)]
 pub struct ActivityBuilder {
     base: ObjectBuilder<Null>,
-    actor: Option<ActorBuilder>,
-    object: Option<ObjectBuilder<Null>>,
-    target: Option<ObjectBuilder<Null>>,
-    result: Option<String>,
-    origin: Option<String>,
-    instrument: Option<String>,
+    actor: Option<OneOrMany<Reference<Actor>>>,
+    object: Option<OneOrMany<Reference<Object<Null>>>>,
+    target: Option<OneOrMany<Reference<Object<Null>>>>,
+    result: Option<OneOrMany<Reference<Object<Null>>>>,
+    origin: Option<OneOrMany<Reference<Object<Null>>>>,
+    instrument: Option<OneOrMany<Reference<Object<Null>>>>,
 }
 
 impl ActivityBuilder {
@@ -501,47 +1689,92 @@ impl ActivityBuilder {
         }
     }
 
-    pub fn published(&mut self, datetime: DateTime<Utc>) -> Self {
-        self.base.published(datetime);
-        self.clone()
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
+        self
+    }
+
+    pub fn name(mut self, name: String) -> Self {
+        self.base = self.base.name(name);
+        self
+    }
+
+    pub fn content(mut self, content: String) -> Self {
+        self.base = self.base.content(content);
+        self
+    }
+
+    pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
+        self.base = self.base.published(datetime);
+        self
+    }
+
+    pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+        self.base = self.base.updated(datetime);
+        self
+    }
+
+    pub fn add_to(mut self, to: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_to(to);
+        self
+    }
+
+    pub fn add_bto(mut self, bto: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_bto(bto);
+        self
+    }
+
+    pub fn add_cc(mut self, cc: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_cc(cc);
+        self
+    }
+
+    pub fn add_bcc(mut self, bcc: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_bcc(bcc);
+        self
+    }
+
+    pub fn actor(mut self, actor: impl Into<Reference<Actor>>) -> Self {
+        self.actor = Some(OneOrMany::One(actor.into()));
+        self
     }
 
-    pub fn actor(&mut self, actor: ActorBuilder) -> Self {
-        self.actor = Some(actor);
-        self.clone()
+    pub fn object(mut self, object: impl Into<Reference<Object<Null>>>) -> Self {
+        self.object = Some(OneOrMany::One(object.into()));
+        self
     }
 
-    pub fn object(&mut self, object: ObjectBuilder<Null>) -> Self {
-        self.object = Some(object);
-        self.clone()
+    pub fn target(mut self, target: impl Into<Reference<Object<Null>>>) -> Self {
+        self.target = Some(OneOrMany::One(target.into()));
+        self
     }
 
-    pub fn target(&mut self, target: ObjectBuilder<Null>) -> Self {
-        self.target = Some(target);
-        self.clone()
+    pub fn result(mut self, result: impl Into<Reference<Object<Null>>>) -> Self {
+        self.result = Some(OneOrMany::One(result.into()));
+        self
     }
 
-    pub fn result(&mut self, result: String) -> Self {
-        self.result = Some(result);
-        self.clone()
+    pub fn origin(mut self, origin: impl Into<Reference<Object<Null>>>) -> Self {
+        self.origin = Some(OneOrMany::One(origin.into()));
+        self
     }
 
-    pub fn origin(&mut self, origin: String) -> Self {
-        self.origin = Some(origin);
-        self.clone()
+    pub fn instrument(mut self, instrument: impl Into<Reference<Object<Null>>>) -> Self {
+        self.instrument = Some(OneOrMany::One(instrument.into()));
+        self
     }
 
-    pub fn instrument(&mut self, instrument: String) -> Self {
-        self.instrument = Some(instrument);
-        self.clone()
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
     }
 
     pub fn build(self) -> Activity {
         Activity {
             base: self.base.build(),
-            actor: self.actor.map(|a| a.build()),
-            object: self.object.map(|o| o.build()),
-            target: self.target.map(|t| t.build()),
+            actor: self.actor,
+            object: self.object,
+            target: self.target,
             result: self.result,
             origin: self.origin,
             instrument: self.instrument,
@@ -581,33 +1814,78 @@ impl IntransitiveActivityBuilder {
         }
     }
 
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
+        self
+    }
+
+    pub fn name(mut self, name: String) -> Self {
+        self.base = self.base.name(name);
+        self
+    }
+
+    pub fn content(mut self, content: String) -> Self {
+        self.base = self.base.content(content);
+        self
+    }
+
     pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
-        self.base.published(datetime);
+        self.base = self.base.published(datetime);
+        self
+    }
+
+    pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+        self.base = self.base.updated(datetime);
+        self
+    }
+
+    pub fn add_to(mut self, to: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_to(to);
+        self
+    }
+
+    pub fn add_bto(mut self, bto: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_bto(bto);
+        self
+    }
+
+    pub fn add_cc(mut self, cc: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_cc(cc);
+        self
+    }
+
+    pub fn add_bcc(mut self, bcc: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_bcc(bcc);
+        self
+    }
+
+    pub fn actor(mut self, actor: impl Into<Reference<Actor>>) -> Self {
+        self.base = self.base.actor(actor);
         self
     }
 
-    pub fn actor(mut self, actor: ActorBuilder) -> Self {
-        self.base.actor(actor);
+    pub fn target(mut self, target: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.target(target);
         self
     }
 
-    pub fn target(mut self, target: ObjectBuilder<Null>) -> Self {
-        self.base.target(target);
+    pub fn result(mut self, result: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.result(result);
         self
     }
 
-    pub fn result(mut self, result: String) -> Self {
-        self.base.result(result);
+    pub fn origin(mut self, origin: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.origin(origin);
         self
     }
 
-    pub fn origin(mut self, origin: String) -> Self {
-        self.base.origin(origin);
+    pub fn instrument(mut self, instrument: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.instrument(instrument);
         self
     }
 
-    pub fn instrument(mut self, instrument: String) -> Self {
-        self.base.instrument(instrument);
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
         self
     }
 
@@ -621,7 +1899,8 @@ impl IntransitiveActivityBuilder {
 /// A [Collection] is a subtype of [Object] that represents ordered or unordered
 /// sets of [Object] or [Link] instances. Refer to the Activity Streams 2.0 Core
 /// specification for a complete description of the [Collection] type.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(bound(deserialize = "CollectionT: Deserialize<'de>"))]
 pub struct Collection<CollectionT> {
     #[serde(flatten)]
     base: Object<Null>,
@@ -629,7 +1908,24 @@ pub struct Collection<CollectionT> {
     #[serde(rename = "totalItems", skip_serializing_if = "Option::is_none")]
     pub total_items: Option<usize>,
 
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// The page containing the most recently updated members, in a paged
+    /// collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<Reference<Object<Null>>>,
+
+    /// The furthest preceding page of a paged collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first: Option<Reference<Object<Null>>>,
+
+    /// The furthest proceeding page of a paged collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<Reference<Object<Null>>>,
+
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        default = "Vec::new",
+        deserialize_with = "one_or_many"
+    )]
     pub items: Vec<CollectionT>,
 }
 
@@ -652,6 +1948,10 @@ where
     CollectionT: Serde,
 {
     base: ObjectBuilder<Null>,
+    total_items: Option<usize>,
+    current: Option<Reference<Object<Null>>>,
+    first: Option<Reference<Object<Null>>>,
+    last: Option<Reference<Object<Null>>>,
     items: Vec<CollectionT>,
 }
 
@@ -662,17 +1962,55 @@ where
     pub fn new(collection_type: String, items: Vec<CollectionT>) -> Self {
         CollectionBuilder {
             base: ObjectBuilder::new().object_type(collection_type),
+            total_items: None,
+            current: None,
+            first: None,
+            last: None,
             items,
         }
     }
 
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
+        self
+    }
+
+    /// Sets `totalItems`, for collections whose items are left out or paged.
+    /// Defaults to the number of items, if any.
+    pub fn total_items(mut self, total_items: usize) -> Self {
+        self.total_items = Some(total_items);
+        self
+    }
+
+    pub fn current(mut self, current: impl Into<Reference<Object<Null>>>) -> Self {
+        self.current = Some(current.into());
+        self
+    }
+
+    pub fn first(mut self, first: impl Into<Reference<Object<Null>>>) -> Self {
+        self.first = Some(first.into());
+        self
+    }
+
+    pub fn last(mut self, last: impl Into<Reference<Object<Null>>>) -> Self {
+        self.last = Some(last.into());
+        self
+    }
+
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
+    }
+
     pub fn build(self) -> Collection<CollectionT> {
         Collection {
             base: self.base.build(),
-            total_items: match self.items.is_empty() {
-                true => None,
-                false => Some(self.items.len()),
-            },
+            total_items: self
+                .total_items
+                .or((!self.items.is_empty()).then_some(self.items.len())),
+            current: self.current,
+            first: self.first,
+            last: self.last,
             items: self.items,
         }
     }
@@ -681,6 +2019,7 @@ where
 /// A subtype of [Collection] in which members of the logical collection are
 /// assumed to always be strictly ordered.
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(bound(deserialize = "CollectionT: Deserialize<'de>"))]
 pub struct OrderedCollection<CollectionT> {
     #[serde(flatten)]
     base: Object<Null>,
@@ -688,7 +2027,24 @@ pub struct OrderedCollection<CollectionT> {
     #[serde(rename = "totalItems", skip_serializing_if = "Option::is_none")]
     pub total_items: Option<usize>,
 
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// The page containing the most recently updated members, in a paged
+    /// collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<Reference<Object<Null>>>,
+
+    /// The furthest preceding page of a paged collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first: Option<Reference<Object<Null>>>,
+
+    /// The furthest proceeding page of a paged collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<Reference<Object<Null>>>,
+
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        default = "Vec::new",
+        deserialize_with = "one_or_many"
+    )]
     #[serde(rename = "orderedItems")]
     pub ordered_items: Vec<CollectionT>,
 }
@@ -712,6 +2068,10 @@ where
     CollectionT: Serde,
 {
     base: ObjectBuilder<Null>,
+    total_items: Option<usize>,
+    current: Option<Reference<Object<Null>>>,
+    first: Option<Reference<Object<Null>>>,
+    last: Option<Reference<Object<Null>>>,
     ordered_items: Vec<CollectionT>,
 }
 
@@ -722,17 +2082,55 @@ where
     pub fn new(collection_type: String, ordered_items: Vec<CollectionT>) -> Self {
         OrderedCollectionBuilder {
             base: ObjectBuilder::new().object_type(collection_type),
+            total_items: None,
+            current: None,
+            first: None,
+            last: None,
             ordered_items,
         }
     }
 
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
+        self
+    }
+
+    /// Sets `totalItems`, for collections whose items are left out or paged.
+    /// Defaults to the number of items, if any.
+    pub fn total_items(mut self, total_items: usize) -> Self {
+        self.total_items = Some(total_items);
+        self
+    }
+
+    pub fn current(mut self, current: impl Into<Reference<Object<Null>>>) -> Self {
+        self.current = Some(current.into());
+        self
+    }
+
+    pub fn first(mut self, first: impl Into<Reference<Object<Null>>>) -> Self {
+        self.first = Some(first.into());
+        self
+    }
+
+    pub fn last(mut self, last: impl Into<Reference<Object<Null>>>) -> Self {
+        self.last = Some(last.into());
+        self
+    }
+
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
+    }
+
     pub fn build(self) -> OrderedCollection<CollectionT> {
         OrderedCollection {
             base: self.base.build(),
-            total_items: match self.ordered_items.is_empty() {
-                true => None,
-                false => Some(self.ordered_items.len()),
-            },
+            total_items: self
+                .total_items
+                .or((!self.ordered_items.is_empty()).then_some(self.ordered_items.len())),
+            current: self.current,
+            first: self.first,
+            last: self.last,
             ordered_items: self.ordered_items,
         }
     }
@@ -747,11 +2145,13 @@ pub struct CollectionPage<CollectionT> {
     base: Collection<CollectionT>,
 
     #[serde(rename = "partOf")]
-    pub part_of: String,
+    pub part_of: Iri,
 
-    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<Iri>,
 
-    pub prev: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<Iri>,
 }
 
 impl<CollectionT> Serde for CollectionPage<CollectionT> where CollectionT: Serde {}
@@ -773,16 +2173,16 @@ where
     CollectionT: Serde,
 {
     base: CollectionBuilder<CollectionT>,
-    part_of: http::Uri,
-    next: Option<http::Uri>,
-    prev: Option<http::Uri>,
+    part_of: Iri,
+    next: Option<Iri>,
+    prev: Option<Iri>,
 }
 
 impl<CollectionT> CollectionPageBuilder<CollectionT>
 where
     CollectionT: Serde,
 {
-    pub fn new(collection_type: String, items: Vec<CollectionT>, part_of: http::Uri) -> Self {
+    pub fn new(collection_type: String, items: Vec<CollectionT>, part_of: Iri) -> Self {
         CollectionPageBuilder {
             base: CollectionBuilder::new(collection_type, items),
             part_of,
@@ -791,22 +2191,39 @@ where
         }
     }
 
-    pub fn next(mut self, next: http::Uri) -> Self {
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
+        self
+    }
+
+    /// Sets `totalItems` to the size of the whole collection. Defaults to
+    /// the number of items in the page.
+    pub fn total_items(mut self, total_items: usize) -> Self {
+        self.base = self.base.total_items(total_items);
+        self
+    }
+
+    pub fn next(mut self, next: Iri) -> Self {
         self.next = Some(next);
         self
     }
 
-    pub fn prev(mut self, prev: http::Uri) -> Self {
+    pub fn prev(mut self, prev: Iri) -> Self {
         self.prev = Some(prev);
         self
     }
 
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
+    }
+
     pub fn build(self) -> CollectionPage<CollectionT> {
         CollectionPage {
             base: self.base.build(),
-            part_of: self.part_of.to_string(),
-            next: self.next.map(|n| n.to_string()),
-            prev: self.prev.map(|p| p.to_string()),
+            part_of: self.part_of,
+            next: self.next,
+            prev: self.prev,
         }
     }
 }
@@ -820,11 +2237,13 @@ pub struct OrderedCollectionPage<CollectionT> {
     base: OrderedCollection<CollectionT>,
 
     #[serde(rename = "partOf")]
-    pub part_of: String,
+    pub part_of: Iri,
 
-    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<Iri>,
 
-    pub prev: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<Iri>,
 }
 
 impl<CollectionT> Serde for OrderedCollectionPage<CollectionT> where CollectionT: Serde {}
@@ -846,16 +2265,16 @@ where
     CollectionT: Serde,
 {
     base: OrderedCollectionBuilder<CollectionT>,
-    part_of: http::Uri,
-    next: Option<http::Uri>,
-    prev: Option<http::Uri>,
+    part_of: Iri,
+    next: Option<Iri>,
+    prev: Option<Iri>,
 }
 
 impl<CollectionT> OrderedCollectionPageBuilder<CollectionT>
 where
     CollectionT: Serde,
 {
-    pub fn new(collection_type: String, items: Vec<CollectionT>, part_of: http::Uri) -> Self {
+    pub fn new(collection_type: String, items: Vec<CollectionT>, part_of: Iri) -> Self {
         OrderedCollectionPageBuilder {
             base: OrderedCollectionBuilder::new(collection_type, items),
             part_of,
@@ -864,79 +2283,226 @@ where
         }
     }
 
-    pub fn next(mut self, next: http::Uri) -> Self {
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
+        self
+    }
+
+    /// Sets `totalItems` to the size of the whole collection. Defaults to
+    /// the number of items in the page.
+    pub fn total_items(mut self, total_items: usize) -> Self {
+        self.base = self.base.total_items(total_items);
+        self
+    }
+
+    pub fn next(mut self, next: Iri) -> Self {
         self.next = Some(next);
         self
     }
 
-    pub fn prev(mut self, prev: http::Uri) -> Self {
+    pub fn prev(mut self, prev: Iri) -> Self {
         self.prev = Some(prev);
         self
     }
 
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
+    }
+
     pub fn build(self) -> OrderedCollectionPage<CollectionT> {
         OrderedCollectionPage {
             base: self.base.build(),
-            part_of: self.part_of.to_string(),
-            next: self.next.map(|n| n.to_string()),
-            prev: self.prev.map(|p| p.to_string()),
+            part_of: self.part_of,
+            next: self.next,
+            prev: self.prev,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn serialize_object() {
+        let object: Object<Null> = ObjectBuilder::new().name(String::from("name")).build();
+        let actual = Document::new(
+            ContextBuilder::new().language(String::from("en")).build(),
+            object,
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams",
+    "@language": "en"
+  },
+  "name": "name"
+}"#;
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn deserialize_object() {
+        let actual = String::from(
+            r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams",
+    "@language": "en"
+  },
+  "name": "name"
+}"#,
+        );
+        let document: Document<Object<Null>> = Document::from_json(actual).unwrap();
+        assert_eq!(document.context.language(), Some("en"));
+        let object = document.object as Object<Null>;
+        assert_eq!(object.name, Some(String::from("name")));
+    }
+
+    #[test]
+    fn deserialize_object_malformed() {
+        let actual = String::from(
+            r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams",
+    "@language": "en"
+  },
+}"#,
+        );
+        let result: Result<Document<Object<Null>>> = Document::from_json(actual);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn deserialize_object_error_path() {
+        let actual = String::from(
+            r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Note",
+  "attachment": [
+    {
+      "type": "Image",
+      "published": "yesterday"
+    }
+  ]
+}"#,
+        );
+        let result: Result<Document<Object<Null>>> = Document::from_json(actual);
+        match result {
+            Err(error @ crate::Error::Parse { .. }) => {
+                assert_eq!(error.path(), Some("$.attachment[0].published"))
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid_iri() {
+        let actual = String::from(
+            r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Note",
+  "id": "https://example.com/notes/a b"
+}"#,
+        );
+        let result: Result<Document<Object<Null>>> = Document::from_json(actual);
+        match result {
+            Err(crate::Error::InvalidIri { path, iri }) => {
+                assert_eq!(path, "$.id");
+                assert_eq!(iri, "https://example.com/notes/a b");
+            }
+            other => panic!("expected an invalid IRI error, got {:?}", other),
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use pretty_assertions::assert_eq;
-    use serde_json::Result;
+        let actual = String::from(
+            r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Note",
+  "to": ["https://example.com/alice", "https://example.com/<bob>"]
+}"#,
+        );
+        let result: Result<Document<Object<Null>>> = Document::from_json(actual);
+        match result {
+            Err(crate::Error::InvalidIri { path, iri }) => {
+                assert_eq!(path, "$.to[1]");
+                assert_eq!(iri, "https://example.com/<bob>");
+            }
+            other => panic!("expected an invalid IRI error, got {:?}", other),
+        }
 
-    #[test]
-    fn serialize_object() {
-        let object: Object<Null> = ObjectBuilder::new().name(String::from("name")).build();
-        let actual = Document::new(
-            ContextBuilder::new().language(String::from("en")).build(),
-            object,
+        let actual = String::from(
+            r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Note",
+  "inReplyTo": {
+    "type": "Note",
+    "id": "https://example.com/notes/a b"
+  }
+}"#,
         );
-        let expected = r#"{
-  "@context": {
-    "@vocab": "https://www.w3.org/ns/activitystreams",
-    "@language": "en"
-  },
-  "name": "name"
-}"#;
-        assert!(actual.to_json_pretty().is_ok());
-        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+        let result: Result<Document<Object<Null>>> = Document::from_json(actual);
+        match result {
+            Err(crate::Error::InvalidIri { path, iri }) => {
+                assert_eq!(path, "$.inReplyTo.id");
+                assert_eq!(iri, "https://example.com/notes/a b");
+            }
+            other => panic!("expected an invalid IRI error, got {:?}", other),
+        }
     }
 
     #[test]
-    fn deserialize_object() {
+    fn deserialize_unknown_context() {
         let actual = String::from(
             r#"{
-  "@context": {
-    "@vocab": "https://www.w3.org/ns/activitystreams",
-    "@language": "en"
-  },
-  "name": "name"
+  "@context": "https://example.com/unknown",
+  "type": "Note"
 }"#,
         );
-        let document: Document<Object<Null>> = Document::from_json(actual).unwrap();
-        assert_eq!(document.context.language, Some(String::from("en")));
-        let object = document.object as Object<Null>;
-        assert_eq!(object.name, Some(String::from("name")));
+        let result: Result<Document<Object<Null>>> = Document::from_json_ld(actual);
+        assert!(matches!(
+            result,
+            Err(crate::Error::Context(
+                jsonld::ContextError::LoadingContextFailed(_)
+            ))
+        ));
     }
 
     #[test]
-    fn deserialize_object_malformed() {
+    fn deserialize_object_multiple_types() {
         let actual = String::from(
             r#"{
   "@context": {
-    "@vocab": "https://www.w3.org/ns/activitystreams",
-    "@language": "en"
+    "@vocab": "https://www.w3.org/ns/activitystreams"
   },
+  "type": [
+    "Note",
+    "toot:Custom"
+  ],
+  "name": "name"
 }"#,
         );
-        let result: Result<Document<Object<Null>>> = Document::from_json(actual);
-        assert!(result.is_err());
+        let document: Document<Object<Null>> = Document::from_json(actual.clone()).unwrap();
+        assert_eq!(
+            document.object.object_type,
+            Some(ObjectType::Multiple(vec![
+                String::from("Note"),
+                String::from("toot:Custom")
+            ]))
+        );
+        assert!(document.object.has_type("Note"));
+        assert!(document.object.has_type("toot:Custom"));
+        assert!(!document.object.has_type("Article"));
+        assert_eq!(document.to_json_pretty().unwrap(), actual);
+    }
+
+    #[test]
+    fn serialize_object_single_type() {
+        let object: Object<Null> = ObjectBuilder::new().object_type("Note").build();
+        assert!(object.has_type("Note"));
+        assert_eq!(object.to_json().unwrap(), r#"{"type":"Note"}"#);
     }
 
     #[test]
@@ -944,10 +2510,10 @@ mod tests {
         let actual = Document::new(
             ContextBuilder::new().build(),
             LinkBuilder::new(UriBuilder::new(
-                "http://example.org/abc".parse::<http::Uri>().unwrap(),
+                "http://example.org/abc".parse::<Iri>().unwrap(),
             ))
             .name(String::from("An example link"))
-            .hreflang(String::from("en"))
+            .hreflang("en".parse().unwrap())
             .build(),
         );
         let expected = String::from(
@@ -983,7 +2549,7 @@ mod tests {
         assert_eq!(link.link_type, "Link");
         assert_eq!(link.href.href, "http://example.org/abc");
         assert_eq!(link.name, Some(String::from("An example link")));
-        assert_eq!(link.hreflang, Some(String::from("en")));
+        assert_eq!(link.hreflang.as_ref().map(LanguageTag::as_str), Some("en"));
     }
 
     #[test]
@@ -993,13 +2559,9 @@ mod tests {
             PreviewBuilder::new(String::from("Video"), String::from("Trailer"))
                 .duration(String::from("PT1M"))
                 .url(
-                    UriBuilder::new(
-                        "http://example.org/trailer.mkv"
-                            .parse::<http::Uri>()
-                            .unwrap(),
-                    )
-                    .media_type(String::from("video/mkv"))
-                    .build(),
+                    UriBuilder::new("http://example.org/trailer.mkv".parse::<Iri>().unwrap())
+                        .media_type(String::from("video/mkv"))
+                        .build(),
                 )
                 .build(),
         );
@@ -1039,13 +2601,13 @@ mod tests {
         );
         let document: Document<Preview> = Document::from_json(actual).unwrap();
         let preview = document.object as Preview;
-        assert_eq!(preview.base.object_type, Some(String::from("Video")));
+        assert_eq!(preview.base.object_type, Some(ObjectType::from("Video")));
         assert_eq!(preview.base.name, Some(String::from("Trailer")));
-        assert_eq!(preview.duration, Some(String::from("PT1M")));
+        assert_eq!(preview.base.duration, Some(String::from("PT1M")));
         assert!(preview.url.is_some());
         assert_eq!(
             preview.url.as_ref().unwrap().href,
-            "http://example.org/trailer.mkv".to_string()
+            "http://example.org/trailer.mkv"
         );
         assert_eq!(
             preview.url.as_ref().unwrap().media_type,
@@ -1053,6 +2615,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserialize_url() {
+        let actual = String::from(
+            r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Image",
+  "url": [
+    "http://example.org/cat.jpeg",
+    {
+      "type": "Link",
+      "href": "http://example.org/cat.png",
+      "mediaType": "image/png"
+    }
+  ]
+}"#,
+        );
+        let document: Document<Object<Null>> = Document::from_json(actual).unwrap();
+        let urls = document.object.url.unwrap();
+        let hrefs: Vec<&str> = urls.iter().map(|url| url.href().as_str()).collect();
+        assert_eq!(
+            hrefs,
+            vec!["http://example.org/cat.jpeg", "http://example.org/cat.png"]
+        );
+        assert!(urls.as_slice()[1].as_link().is_some());
+
+        let actual = String::from(
+            r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Image",
+  "url": {
+    "type": "Image",
+    "name": "A cat"
+  }
+}"#,
+        );
+        let result: Result<Document<Object<Null>>> = Document::from_json(actual);
+        assert!(matches!(result, Err(crate::Error::Parse { .. })));
+    }
+
     #[test]
     fn serialize_activity() {
         let actual = Document::new(
@@ -1061,7 +2662,10 @@ mod tests {
                 String::from("Activity"),
                 String::from("Sally did something to a note"),
             )
-            .actor(ActorBuilder::new(String::from("Person")).name(String::from("Sally")))
+            .actor(
+                crate::extended::ActorBuilder::new(String::from("Person"))
+                    .name(String::from("Sally")),
+            )
             .object(
                 ObjectBuilder::new()
                     .object_type(String::from("Note"))
@@ -1112,20 +2716,589 @@ mod tests {
         );
         let document: Document<Activity> = Document::from_json(actual).unwrap();
         let activity = document.object as Activity;
-        assert_eq!(activity.object_type, Some(String::from("Activity")));
+        assert_eq!(activity.object_type, Some(ObjectType::from("Activity")));
         assert_eq!(
             activity.summary,
             Some(String::from("Sally did something to a note"))
         );
 
         assert!(activity.actor.is_some());
-        let actor = activity.actor.as_ref().unwrap();
-        assert_eq!(actor.object_type, Some(String::from("Person")));
+        let actor = activity.actor.as_ref().unwrap().first().unwrap();
+        let actor = actor.as_object().unwrap();
+        assert_eq!(actor.object_type, Some(ObjectType::from("Person")));
         assert_eq!(actor.name, Some(String::from("Sally")));
 
         assert!(activity.object.is_some());
-        let object = activity.object.as_ref().unwrap();
-        assert_eq!(object.object_type, Some(String::from("Note")));
+        let object = activity.object.as_ref().unwrap().first().unwrap();
+        let object = object.as_object().unwrap();
+        assert_eq!(object.object_type, Some(ObjectType::from("Note")));
         assert_eq!(object.name, Some(String::from("A Note")));
     }
+
+    #[test]
+    fn deserialize_activity_references() {
+        let actual = String::from(
+            r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Announce",
+  "actor": [
+    "https://example.com/alice",
+    {
+      "type": "Person",
+      "name": "Bob"
+    }
+  ],
+  "object": "https://example.com/notes/1",
+  "target": {
+    "type": "Link",
+    "href": "https://example.com/timeline"
+  }
+}"#,
+        );
+        let document: Document<Activity> = Document::from_json(actual.clone()).unwrap();
+        let activity = &document.object;
+
+        let actors = match activity.actor.as_ref().unwrap() {
+            OneOrMany::Many(actors) => actors,
+            other => panic!("expected an array of actors, got {:?}", other),
+        };
+        assert_eq!(
+            actors[0].as_iri().map(Iri::as_str),
+            Some("https://example.com/alice")
+        );
+        assert_eq!(
+            actors[1].as_object().unwrap().name,
+            Some(String::from("Bob"))
+        );
+
+        let object = activity.object.as_ref().unwrap().first().unwrap();
+        assert_eq!(
+            object.as_iri().map(Iri::as_str),
+            Some("https://example.com/notes/1")
+        );
+
+        let target = activity.target.as_ref().unwrap().first().unwrap();
+        assert_eq!(
+            target.as_link().unwrap().href.href,
+            "https://example.com/timeline"
+        );
+
+        assert_eq!(document.to_json_pretty().unwrap(), actual);
+    }
+
+    #[test]
+    fn serialize_activity_iri_references() {
+        let actual = ActivityBuilder::new(String::from("Like"), String::from("Alice liked a note"))
+            .actor("https://example.com/alice".parse::<Iri>().unwrap())
+            .object("https://example.com/notes/1".parse::<Iri>().unwrap())
+            .build();
+        let expected = r#"{"type":"Like","summary":"Alice liked a note","actor":"https://example.com/alice","object":"https://example.com/notes/1"}"#;
+        assert_eq!(actual.to_json().unwrap(), expected);
+    }
+
+    #[test]
+    fn deserialize_object_one_or_many() {
+        let actual = String::from(
+            r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Note",
+  "attributedTo": {
+    "type": "Person",
+    "name": "Sally"
+  },
+  "audience": [
+    "https://example.com/followers",
+    "https://example.com/friends"
+  ]
+}"#,
+        );
+        let document: Document<Object<Object<Null>>> = Document::from_json(actual.clone()).unwrap();
+        let object = &document.object;
+
+        let attributed_to = object.attributed_to.as_ref().unwrap();
+        assert_eq!(attributed_to.len(), 1);
+        assert_eq!(
+            attributed_to
+                .first()
+                .and_then(Reference::as_object)
+                .unwrap()
+                .name,
+            Some(String::from("Sally"))
+        );
+
+        let audience = object.audience.as_ref().unwrap();
+        let iris: Vec<&str> = audience
+            .iter()
+            .filter_map(Reference::as_iri)
+            .map(Iri::as_str)
+            .collect();
+        assert_eq!(
+            iris,
+            vec![
+                "https://example.com/followers",
+                "https://example.com/friends"
+            ]
+        );
+
+        assert_eq!(document.to_json_pretty().unwrap(), actual);
+    }
+
+    #[test]
+    fn one_or_many() {
+        let mut values = OneOrMany::One(1);
+        assert_eq!(values.as_slice(), &[1]);
+        values.push(2);
+        assert_eq!(values, OneOrMany::Many(vec![1, 2]));
+        for value in &mut values {
+            *value *= 10;
+        }
+        assert_eq!(values.iter().sum::<i32>(), 30);
+        assert_eq!(values.into_vec(), vec![10, 20]);
+
+        let values: OneOrMany<String> = serde_json::from_str(r#""canonical""#).unwrap();
+        assert_eq!(values.len(), 1);
+        assert_eq!(serde_json::to_string(&values).unwrap(), r#""canonical""#);
+        let values: OneOrMany<String> = serde_json::from_str(r#"["a","b"]"#).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(serde_json::to_string(&values).unwrap(), r#"["a","b"]"#);
+        let values = OneOrMany::Many(vec![String::from("canonical")]);
+        assert_eq!(serde_json::to_string(&values).unwrap(), r#""canonical""#);
+        let mut values = None;
+        push_value(&mut values, 1);
+        assert_eq!(values, Some(OneOrMany::One(1)));
+    }
+
+    #[test]
+    fn deserialize_object_properties() {
+        let actual = String::from(
+            r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Note",
+  "id": "http://example.org/notes/2",
+  "content": "I am fine.",
+  "attachment": [
+    {
+      "type": "Image",
+      "content": "This is what he looks like.",
+      "url": "http://example.org/cat.jpeg"
+    }
+  ],
+  "contentMap": {
+    "en": "I am fine.",
+    "fr": "Je vais bien."
+  },
+  "generator": {
+    "type": "Application",
+    "name": "Exampletron 3000"
+  },
+  "icon": {
+    "type": "Image",
+    "name": "Note icon",
+    "url": "http://example.org/note.png"
+  },
+  "inReplyTo": "http://example.org/notes/1",
+  "replies": {
+    "type": "Collection",
+    "totalItems": 1,
+    "items": [
+      {
+        "type": "Note",
+        "summary": "A response to the note",
+        "inReplyTo": "http://example.org/notes/2"
+      }
+    ]
+  },
+  "tag": [
+    {
+      "type": "Person",
+      "id": "http://example.org/people/sally",
+      "name": "Sally"
+    }
+  ],
+  "updated": "2014-12-12T12:12:12Z",
+  "to": [
+    "http://example.org/people/sally"
+  ],
+  "cc": [
+    "https://www.w3.org/ns/activitystreams#Public"
+  ],
+  "mediaType": "text/plain"
+}"#,
+        );
+        let document: Document<Object<Null>> = Document::from_json(actual.clone()).unwrap();
+        let object = &document.object;
+
+        assert_eq!(object.attachment.as_ref().unwrap().len(), 1);
+        assert_eq!(
+            object.content_map.as_ref().unwrap().get("fr"),
+            Some("Je vais bien.")
+        );
+        let generator = object.generator.as_ref().unwrap().first().unwrap();
+        assert_eq!(
+            generator.as_object().unwrap().name,
+            Some(String::from("Exampletron 3000"))
+        );
+        assert!(object.icon.is_some());
+        assert_eq!(
+            object
+                .in_reply_to
+                .as_ref()
+                .and_then(OneOrMany::first)
+                .and_then(Reference::as_iri)
+                .map(Iri::as_str),
+            Some("http://example.org/notes/1")
+        );
+        let replies = object.replies.as_ref().unwrap().as_object().unwrap();
+        assert_eq!(replies.total_items, Some(1));
+        assert_eq!(object.tag.as_ref().unwrap().len(), 1);
+        assert!(object.updated.is_some());
+        assert_eq!(object.to.as_ref().unwrap().len(), 1);
+        assert_eq!(object.cc.as_ref().unwrap().len(), 1);
+        assert_eq!(object.media_type, Some(String::from("text/plain")));
+
+        // Normalizing leaves single values, such as the one `to` address,
+        // outside of arrays, so compare the documents as JSON-LD.
+        let expected = jsonld::normalize(&serde_json::from_str(&actual).unwrap()).unwrap();
+        assert_eq!(expected["to"], "http://example.org/people/sally");
+        let actual = serde_json::to_value(&document).unwrap();
+        assert_eq!(jsonld::normalize(&actual).unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_object_properties() {
+        let object: Object<Null> = ObjectBuilder::new()
+            .object_type("Event")
+            .name(String::from("Going-Away Party for Jim"))
+            .start_time(Utc.with_ymd_and_hms(2015, 1, 1, 7, 0, 0).unwrap())
+            .end_time(Utc.with_ymd_and_hms(2015, 1, 1, 14, 0, 0).unwrap())
+            .location(
+                ObjectBuilder::<Null>::new()
+                    .object_type("Place")
+                    .name(String::from("Work")),
+            )
+            .add_to("http://example.org/people/jim".parse::<Iri>().unwrap())
+            .add_cc("http://example.org/people/sally".parse::<Iri>().unwrap())
+            .build();
+        let expected = r#"{
+  "type": "Event",
+  "name": "Going-Away Party for Jim",
+  "startTime": "2015-01-01T07:00:00Z",
+  "endTime": "2015-01-01T14:00:00Z",
+  "location": {
+    "type": "Place",
+    "name": "Work"
+  },
+  "to": "http://example.org/people/jim",
+  "cc": "http://example.org/people/sally"
+}"#;
+        assert_eq!(object.to_json_pretty().unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_object_language_maps() {
+        let object: Object<Null> = ObjectBuilder::new()
+            .object_type("Note")
+            .add_name_map("en".parse().unwrap(), String::from("A note"))
+            .add_content_map("en".parse().unwrap(), String::from("Hello"))
+            .add_content_map("fi".parse().unwrap(), String::from("Hei"))
+            .build();
+        let expected = r#"{
+  "type": "Note",
+  "contentMap": {
+    "en": "Hello",
+    "fi": "Hei"
+  },
+  "nameMap": {
+    "en": "A note"
+  }
+}"#;
+        assert_eq!(object.to_json_pretty().unwrap(), expected);
+    }
+
+    #[test]
+    fn resolve_object_language() {
+        let document: Document<Object<Null>> = Document::from_json(String::from(
+            r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams",
+    "@language": "en"
+  },
+  "type": "Note",
+  "summary": "A short note",
+  "content": "Hello",
+  "contentMap": {
+    "fi": "Hei",
+    "sv-FI": "Hej"
+  }
+}"#,
+        ))
+        .unwrap();
+        let object = &document.object;
+
+        assert_eq!(object.content_for(&["fi", "en"]), Some("Hei"));
+        assert_eq!(object.content_for(&["sv"]), Some("Hej"));
+        assert_eq!(object.content_for(&["de"]), Some("Hello"));
+        assert_eq!(
+            object.content_in(&document.context, &["en", "fi"]),
+            Some("Hello")
+        );
+        assert_eq!(object.content_for(&["en", "fi"]), Some("Hei"));
+        assert_eq!(
+            object.summary_in(&document.context, &["fi"]),
+            Some("A short note")
+        );
+        assert_eq!(object.name_for(&["fi"]), None);
+
+        let result =
+            Object::<Null>::from_json(String::from(r#"{"contentMap": {"not_a_tag": "x"}}"#));
+        assert_eq!(result.unwrap_err().path(), Some("$.contentMap.not_a_tag"));
+    }
+
+    #[test]
+    fn deserialize_context_iri() {
+        let document: Document<Object<Null>> = Document::from_json(String::from(
+            r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Note"
+}"#,
+        ))
+        .unwrap();
+        assert!(document.context.includes(Context::ACTIVITY_STREAMS));
+        assert_eq!(document.context.vocab(), None);
+        assert_eq!(
+            document.to_json().unwrap(),
+            r#"{"@context":"https://www.w3.org/ns/activitystreams","type":"Note"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_context_array() {
+        let actual = String::from(
+            r#"{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    "https://w3id.org/security/v1",
+    {
+      "@language": "en",
+      "toot": "http://joinmastodon.org/ns#",
+      "sensitive": "as:sensitive",
+      "featured": {
+        "@id": "toot:featured",
+        "@type": "@id"
+      },
+      "focalPoint": {
+        "@container": "@list",
+        "@id": "toot:focalPoint"
+      }
+    }
+  ],
+  "type": "Note"
+}"#,
+        );
+        let document: Document<Object<Null>> = Document::from_json(actual.clone()).unwrap();
+        let context = &document.context;
+        assert_eq!(
+            context.iris().collect::<Vec<_>>(),
+            vec![
+                "https://www.w3.org/ns/activitystreams",
+                "https://w3id.org/security/v1"
+            ]
+        );
+        assert_eq!(context.language(), Some("en"));
+        assert_eq!(
+            context.term("toot"),
+            Some(&TermDefinition::from("http://joinmastodon.org/ns#"))
+        );
+        assert_eq!(
+            context.term("featured").and_then(TermDefinition::id),
+            Some("toot:featured")
+        );
+        assert_eq!(context.term("missing"), None);
+
+        let expected: serde_json::Value = serde_json::from_str(&actual).unwrap();
+        let actual: serde_json::Value = serde_json::from_str(&document.to_json().unwrap()).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn deserialize_context_array_of_one() {
+        let document: Document<Object<Null>> = Document::from_json(String::from(
+            r#"{
+  "@context": ["https://www.w3.org/ns/activitystreams"],
+  "type": "Note"
+}"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            document.context.iris().collect::<Vec<_>>(),
+            vec![Context::ACTIVITY_STREAMS]
+        );
+        let json = document.to_json().unwrap();
+        assert_eq!(
+            json,
+            r#"{"@context":"https://www.w3.org/ns/activitystreams","type":"Note"}"#
+        );
+        let document: Document<Object<Null>> = Document::from_json(json).unwrap();
+        assert_eq!(
+            document.context.iris().collect::<Vec<_>>(),
+            vec![Context::ACTIVITY_STREAMS]
+        );
+    }
+
+    #[test]
+    fn deserialize_context_keywords() {
+        let actual = String::from(
+            r#"{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    {
+      "@version": 1.1,
+      "@protected": true,
+      "@propagate": false,
+      "toot": "http://joinmastodon.org/ns#",
+      "featured": null,
+      "names": {
+        "@id": "toot:names",
+        "@container": ["@set", "@language"]
+      }
+    }
+  ],
+  "type": "Note"
+}"#,
+        );
+        let document: Document<Object<Null>> = Document::from_json(actual.clone()).unwrap();
+        let context = &document.context;
+        let definition = context.definitions().next().unwrap();
+        assert_eq!(definition.protected, Some(true));
+        assert_eq!(definition.propagate, Some(false));
+        assert_eq!(
+            definition.terms.keys().collect::<Vec<_>>(),
+            vec!["featured", "names", "toot"]
+        );
+        assert_eq!(context.term("featured"), Some(&TermDefinition::Null));
+        let names = ExpandedTermDefinition::new(String::from("toot:names"))
+            .container(vec![String::from("@set"), String::from("@language")]);
+        assert_eq!(context.term("names"), Some(&TermDefinition::from(names)));
+
+        let expected: serde_json::Value = serde_json::from_str(&actual).unwrap();
+        let actual = serde_json::to_value(&document).unwrap();
+        assert_eq!(actual["@context"], expected["@context"]);
+    }
+
+    #[test]
+    fn serialize_context_terms() {
+        let context = ContextBuilder::activity_streams()
+            .add_context("https://w3id.org/security/v1".parse().unwrap())
+            .add_term(String::from("toot"), "http://joinmastodon.org/ns#")
+            .add_term(String::from("sensitive"), "as:sensitive")
+            .add_term(
+                String::from("featured"),
+                ExpandedTermDefinition::new(String::from("toot:featured"))
+                    .term_type(String::from("@id")),
+            )
+            .build();
+        let expected = r#"[
+  "https://www.w3.org/ns/activitystreams",
+  "https://w3id.org/security/v1",
+  {
+    "featured": {
+      "@id": "toot:featured",
+      "@type": "@id"
+    },
+    "sensitive": "as:sensitive",
+    "toot": "http://joinmastodon.org/ns#"
+  }
+]"#;
+        assert_eq!(serde_json::to_string_pretty(&context).unwrap(), expected);
+
+        let context = ContextBuilder::activity_streams().build();
+        assert_eq!(
+            serde_json::to_string(&context).unwrap(),
+            r#""https://www.w3.org/ns/activitystreams""#
+        );
+    }
+
+    #[test]
+    fn round_trip_unknown_properties() {
+        let actual = String::from(
+            r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Create",
+  "actor": "http://example.org/alice",
+  "object": {
+    "type": "Note",
+    "content": "Hello",
+    "sensitive": true,
+    "votersCount": 3,
+    "tag": {
+      "type": "Link",
+      "href": "http://example.org/tags/hello",
+      "toot:custom": {
+        "nested": [1, 2]
+      }
+    }
+  },
+  "signature": {
+    "type": "RsaSignature2017",
+    "signatureValue": "abc"
+  }
+}"#,
+        );
+        let document: Document<Activity> = Document::from_json(actual.clone()).unwrap();
+        let activity = &document.object;
+        assert_eq!(
+            activity.extensions.keys().collect::<Vec<_>>(),
+            vec!["signature"]
+        );
+        let note = activity
+            .object
+            .as_ref()
+            .and_then(OneOrMany::first)
+            .and_then(Reference::as_object)
+            .unwrap();
+        assert_eq!(
+            note.extensions.get("sensitive"),
+            Some(&serde_json::json!(true))
+        );
+        assert_eq!(
+            note.extensions.get("votersCount"),
+            Some(&serde_json::json!(3))
+        );
+
+        let serialized = document.to_json().unwrap();
+        assert_eq!(serialized.matches(r#""actor""#).count(), 1);
+        let expected: serde_json::Value = serde_json::from_str(&actual).unwrap();
+        let actual: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn serialize_extensions() {
+        let object: Object<Null> = ObjectBuilder::new()
+            .object_type("Note")
+            .extension(String::from("sensitive"), serde_json::json!(true))
+            .build();
+        assert_eq!(
+            object.to_json().unwrap(),
+            r#"{"type":"Note","sensitive":true}"#
+        );
+
+        let link = LinkBuilder::new(UriBuilder::new(
+            "http://example.org/abc".parse::<Iri>().unwrap(),
+        ))
+        .extension(String::from("rel:me"), serde_json::json!(true))
+        .build();
+        assert_eq!(
+            link.to_json().unwrap(),
+            r#"{"type":"Link","href":"http://example.org/abc","rel:me":true}"#
+        );
+    }
 }
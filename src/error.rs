@@ -0,0 +1,296 @@
+use crate::extended::UnexpectedType;
+use crate::iri::{InvalidIri, Iri};
+use crate::jsonld::ContextError;
+use crate::validate::{validate_json, Mode, Violation, ViolationKind};
+use serde::de::{DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use serde_json::Value;
+use serde_path_to_error::Segment;
+use std::cell::RefCell;
+use std::fmt;
+
+/// Errors raised by the crate. Where the problem can be pinned to a value in
+/// a document, the error carries its JSON path, e.g. `$.object.actor`.
+#[derive(Debug)]
+pub enum Error {
+    /// The input is not valid JSON, or a value doesn't have the shape its
+    /// property takes.
+    Parse { path: String, message: String },
+    /// A value could not be serialized to JSON.
+    Serialize(serde_json::Error),
+    /// The document violates the Activity Streams or ActivityPub
+    /// specifications, see [crate::validate].
+    Validation(Vec<Violation>),
+    /// The `type` of an object is not one the type parsed into accepts, e.g.
+    /// a `Group` parsed as a [crate::extended::Person].
+    UnexpectedType {
+        path: String,
+        expected: String,
+        found: Option<String>,
+    },
+    /// An IRI is malformed, or relative where an absolute one is required.
+    InvalidIri { path: String, iri: String },
+    /// Processing a JSON-LD `@context` failed.
+    Context(ContextError),
+    /// An activity's actor may not do what it attempts, e.g. update an
+    /// object of another server.
+    Unauthorized(String),
+    /// An object an activity acts on is not in the store, e.g. the object of
+    /// an `Update`.
+    NotFound(Iri),
+    /// The storage backend failed, see [crate::store].
+    Store(Box<dyn std::error::Error + Send + Sync>),
+    /// A request's HTTP signature is missing, malformed or doesn't verify, or
+    /// a key can't be used for signatures.
+    Signature(String),
+}
+
+impl Error {
+    /// The JSON path of the offending value, if known. For validation errors
+    /// this is the path of the first violation.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Error::Parse { path, .. }
+            | Error::UnexpectedType { path, .. }
+            | Error::InvalidIri { path, .. } => Some(path),
+            Error::Validation(violations) => violations.first().map(|v| v.path.as_str()),
+            Error::Serialize(_)
+            | Error::Context(_)
+            | Error::Unauthorized(_)
+            | Error::NotFound(_)
+            | Error::Store(_)
+            | Error::Signature(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { path, message } => write!(f, "{}: {}", path, message),
+            Error::Serialize(error) => write!(f, "failed to serialize: {}", error),
+            Error::Validation(violations) => {
+                let violations: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                write!(f, "invalid document: {}", violations.join("; "))
+            }
+            Error::UnexpectedType {
+                path,
+                expected,
+                found: Some(found),
+            } => write!(f, "{}: expected type {}, found {}", path, expected, found),
+            Error::UnexpectedType {
+                path,
+                expected,
+                found: None,
+            } => write!(f, "{}: expected type {}, found none", path, expected),
+            Error::InvalidIri { path, iri } => write!(f, "{}: invalid IRI {:?}", path, iri),
+            Error::Context(error) => write!(f, "{}", error),
+            Error::Unauthorized(reason) => write!(f, "unauthorized: {}", reason),
+            Error::NotFound(id) => write!(f, "{} not found", id),
+            Error::Store(error) => write!(f, "storage failed: {}", error),
+            Error::Signature(reason) => write!(f, "invalid signature: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Serialize(error) => Some(error),
+            Error::Context(error) => Some(error),
+            Error::Store(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<ContextError> for Error {
+    fn from(error: ContextError) -> Self {
+        Error::Context(error)
+    }
+}
+
+impl From<InvalidIri> for Error {
+    fn from(error: InvalidIri) -> Self {
+        Error::InvalidIri {
+            path: String::from("$"),
+            iri: error.0,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Parse {
+            path: String::from("$"),
+            message: error.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Error::Store(Box::new(error))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An error raised while deserializing that [locate] reports as its own
+/// [Error] variant. Serde only keeps the message of custom errors, so these are
+/// handed over through [RAISED] instead.
+pub(crate) enum Raised {
+    UnexpectedType(UnexpectedType),
+    InvalidIri(InvalidIri),
+}
+
+impl From<UnexpectedType> for Raised {
+    fn from(error: UnexpectedType) -> Self {
+        Raised::UnexpectedType(error)
+    }
+}
+
+impl From<InvalidIri> for Raised {
+    fn from(error: InvalidIri) -> Self {
+        Raised::InvalidIri(error)
+    }
+}
+
+thread_local! {
+    /// The error last [raise]d on this thread.
+    static RAISED: RefCell<Option<Raised>> = const { RefCell::new(None) };
+}
+
+/// Fails deserialization with `error`, keeping it for [locate].
+pub(crate) fn raise<E, R>(error: R) -> E
+where
+    E: serde::de::Error,
+    R: Into<Raised> + fmt::Display,
+{
+    let custom = E::custom(&error);
+    RAISED.with(|raised| *raised.borrow_mut() = Some(error.into()));
+    custom
+}
+
+/// Forgets the error last [raise]d, for deserializers that recover from it.
+pub(crate) fn forget_raised() {
+    RAISED.with(|raised| raised.borrow_mut().take());
+}
+
+/// Deserializes a `U` and converts it, like `#[serde(try_from = "U")]` does,
+/// but [raise]s the conversion error.
+pub(crate) fn try_from<'de, D, U, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    U: Deserialize<'de>,
+    T: TryFrom<U>,
+    T::Error: Into<Raised> + fmt::Display,
+{
+    T::try_from(U::deserialize(deserializer)?).map_err(raise)
+}
+
+/// Parses `json`, reporting the path of the value that failed to parse.
+pub(crate) fn from_str<T: DeserializeOwned>(json: &str) -> Result<T> {
+    from_value(serde_json::from_str(json)?)
+}
+
+/// Like [from_str], from an already parsed JSON value.
+pub(crate) fn from_value<T: DeserializeOwned>(value: Value) -> Result<T> {
+    forget_raised();
+    serde_path_to_error::deserialize(&value).map_err(|error| {
+        let raised = RAISED.with(|raised| raised.borrow_mut().take());
+        locate(error, raised, &value)
+    })
+}
+
+/// Turns a failed parse of `document` into an [Error] naming the offending
+/// value.
+fn locate(
+    error: serde_path_to_error::Error<serde_json::Error>,
+    raised: Option<Raised>,
+    document: &Value,
+) -> Error {
+    let mut path = String::from("$");
+    let mut value = Some(document);
+    for segment in error.path().iter() {
+        match segment {
+            Segment::Seq { index } => {
+                path += &format!("[{}]", index);
+                value = value.and_then(|value| value.get(index));
+            }
+            Segment::Map { key } => {
+                path += &format!(".{}", key);
+                value = value.and_then(|value| value.get(key));
+            }
+            Segment::Enum { .. } | Segment::Unknown => {}
+        }
+    }
+
+    // Serde loses track of the path inside flattened types, which most of
+    // the crate's types are, so the path is often that of an enclosing
+    // object. Narrow it down to the offending value below it.
+    match raised {
+        Some(Raised::UnexpectedType(UnexpectedType { expected, found })) => {
+            let typed = |value: &Value| {
+                let types = value.get("type").map(|types| match types {
+                    Value::Array(types) => types
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    types => types.as_str().unwrap_or_default().to_string(),
+                });
+                value.is_object() && types == found
+            };
+            if let Some(below) = value.and_then(|value| find(value, &typed)) {
+                path += &below;
+            }
+            return Error::UnexpectedType {
+                path,
+                expected: expected.to_string(),
+                found,
+            };
+        }
+        Some(Raised::InvalidIri(InvalidIri(iri))) => {
+            let invalid = |value: &Value| value.as_str() == Some(iri.as_str());
+            if let Some(below) = value.and_then(|value| find(value, &invalid)) {
+                path += &below;
+            }
+            return Error::InvalidIri { path, iri };
+        }
+        None => {}
+    }
+
+    let message = error.into_inner().to_string();
+    let nested = validate_json(document, Mode::Lenient)
+        .into_iter()
+        .filter(|violation| {
+            violation
+                .path
+                .strip_prefix(path.as_str())
+                .is_some_and(|rest| rest.starts_with(['.', '[']))
+        })
+        .find(|violation| matches!(violation.kind, ViolationKind::WrongValueType { .. }));
+    if let Some(Violation { path: nested, .. }) = nested {
+        path = nested;
+    }
+    Error::Parse { path, message }
+}
+
+/// The path, relative to `value`, of the first value depth first that
+/// `matches`.
+fn find(value: &Value, matches: &dyn Fn(&Value) -> bool) -> Option<String> {
+    if matches(value) {
+        return Some(String::new());
+    }
+    match value {
+        Value::Array(values) => values.iter().enumerate().find_map(|(index, value)| {
+            find(value, matches).map(|path| format!("[{}]{}", index, path))
+        }),
+        Value::Object(properties) => properties
+            .iter()
+            .find_map(|(key, value)| find(value, matches).map(|path| format!(".{}{}", key, path))),
+        _ => None,
+    }
+}
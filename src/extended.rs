@@ -1,62 +1,604 @@
-use crate::core::{LinkBuilder, Null, Object, ObjectBuilder};
+use crate::core::{
+    push_value, Activity, ActivityBuilder, Collection, CollectionBuilder, Context,
+    ExpandedTermDefinition, Extension, IntransitiveActivity, IntransitiveActivityBuilder, Link,
+    LinkBuilder, LinkReference, Null, Object, ObjectBuilder, ObjectType, OneOrMany, Reference,
+    TermDefinition, UriBuilder,
+};
+use crate::iri::Iri;
+use crate::language::LanguageTag;
 use crate::Serde;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 
-/// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-note
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Note {
-    #[serde(flatten)]
-    base: Object<Null>,
+/// Generates a typed wrapper and builder for one of the Activity Streams object
+/// types. The wrapper derefs to [Object] and carries any type-specific
+/// properties listed for it; the builder fixes the `type` value.
+macro_rules! object_type {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $builder:ident {
+            $($(#[$field_meta:meta])* $field:ident: $field_type:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Serialize, Debug, Clone)]
+        #[serde(rename_all = "camelCase")]
+        pub struct $name<ExtensionT = Null> {
+            #[serde(flatten)]
+            base: Object<Null, ExtensionT>,
+
+            $(
+                $(#[$field_meta])*
+                #[serde(skip_serializing_if = "Option::is_none")]
+                pub $field: Option<$field_type>,
+            )*
+        }
+
+        impl $name {
+            pub const TYPE: &'static str = stringify!($name);
+        }
+
+        // Checks the type like the other typed wrappers' `TryFrom`, which
+        // would need an unchecked type declared outside the macro.
+        impl<'de, ExtensionT: Deserialize<'de>> Deserialize<'de> for $name<ExtensionT> {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct Unchecked<ExtensionT> {
+                    #[serde(flatten)]
+                    base: Object<Null, ExtensionT>,
+                    $($(#[$field_meta])* $field: Option<$field_type>,)*
+                }
+
+                let unchecked = Unchecked::<ExtensionT>::deserialize(deserializer)?;
+                if !unchecked.base.has_type($name::TYPE) {
+                    return Err(crate::error::raise(UnexpectedType {
+                        expected: $name::TYPE,
+                        found: unchecked.base.object_type.as_ref().map(|t| t.iter().collect::<Vec<_>>().join(", ")),
+                    }));
+                }
+                Ok($name {
+                    base: unchecked.base,
+                    $($field: unchecked.$field,)*
+                })
+            }
+        }
+
+        impl<ExtensionT: Extension> Serde for $name<ExtensionT> {
+            fn extend_context(context: &mut Context) {
+                context.extend::<ExtensionT>();
+            }
+        }
+
+        impl<ExtensionT> std::ops::Deref for $name<ExtensionT> {
+            type Target = Object<Null, ExtensionT>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.base
+            }
+        }
+
+        #[doc = concat!("Builder for a [", stringify!($name), "].")]
+        #[derive(Clone)]
+        pub struct $builder<ExtensionT = Null> {
+            base: ObjectBuilder<Null, ExtensionT>,
+            $($field: Option<$field_type>,)*
+        }
+
+        impl $builder {
+            pub fn new() -> Self {
+                $builder {
+                    base: ObjectBuilder::new().object_type($name::TYPE.to_string()),
+                    $($field: None,)*
+                }
+            }
+        }
+
+        impl<ExtensionT: Clone> $builder<ExtensionT> {
+            #[doc = concat!("Attaches a typed extension, see [crate::core::Extension].")]
+            pub fn with_extension<OtherT: Extension>(self, extension: OtherT) -> $builder<OtherT> {
+                $builder {
+                    base: self.base.with_extension(extension),
+                    $($field: self.$field,)*
+                }
+            }
+
+            pub fn id(mut self, id: Iri) -> Self {
+                self.base = self.base.id(id);
+                self
+            }
+
+            pub fn name(mut self, name: String) -> Self {
+                self.base = self.base.name(name);
+                self
+            }
+
+            pub fn url(mut self, url: impl Into<LinkReference>) -> Self {
+                self.base = self.base.url(url);
+                self
+            }
+
+            pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.published(datetime);
+                self
+            }
+
+            pub fn image(mut self, image: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.image(image);
+                self
+            }
+
+            pub fn audience(mut self, audience: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.audience(audience);
+                self
+            }
+
+            pub fn content(mut self, content: String) -> Self {
+                self.base = self.base.content(content);
+                self
+            }
+
+            pub fn summary(mut self, summary: String) -> Self {
+                self.base = self.base.summary(summary);
+                self
+            }
+
+            pub fn add_attachment(mut self, attachment: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_attachment(attachment);
+                self
+            }
+
+            pub fn context(mut self, context: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.context(context);
+                self
+            }
+
+            pub fn start_time(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.start_time(datetime);
+                self
+            }
+
+            pub fn end_time(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.end_time(datetime);
+                self
+            }
+
+            pub fn duration(mut self, duration: String) -> Self {
+                self.base = self.base.duration(duration);
+                self
+            }
+
+            pub fn generator(mut self, generator: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.generator(generator);
+                self
+            }
+
+            pub fn icon(mut self, icon: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.icon(icon);
+                self
+            }
+
+            pub fn in_reply_to(mut self, in_reply_to: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.in_reply_to(in_reply_to);
+                self
+            }
+
+            pub fn location(mut self, location: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.location(location);
+                self
+            }
+
+            pub fn preview(mut self, preview: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.preview(preview);
+                self
+            }
+
+            pub fn replies(mut self, replies: impl Into<Reference<Collection<Reference<Object<Null>>>>>) -> Self {
+                self.base = self.base.replies(replies);
+                self
+            }
+
+            pub fn add_tag(mut self, tag: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_tag(tag);
+                self
+            }
+
+            pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.updated(datetime);
+                self
+            }
+
+            pub fn add_to(mut self, to: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_to(to);
+                self
+            }
+
+            pub fn add_bto(mut self, bto: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_bto(bto);
+                self
+            }
+
+            pub fn add_cc(mut self, cc: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_cc(cc);
+                self
+            }
+
+            pub fn add_bcc(mut self, bcc: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_bcc(bcc);
+                self
+            }
+
+            pub fn media_type(mut self, media_type: String) -> Self {
+                self.base = self.base.media_type(media_type);
+                self
+            }
+
+            pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+                self.base = self.base.extension(name, value);
+                self
+            }
+
+            $(
+                pub fn $field(mut self, $field: $field_type) -> Self {
+                    self.$field = Some($field);
+                    self
+                }
+            )*
+
+            pub fn build(self) -> $name<ExtensionT> {
+                $name {
+                    base: self.base.build(),
+                    $($field: self.$field,)*
+                }
+            }
+        }
+
+        impl Default for $builder {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+object_type! {
+    /// Represents any kind of multi-paragraph written work.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-article>
+    Article, ArticleBuilder {}
+}
+
+object_type! {
+    /// Represents an audio document of any kind.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-audio>
+    Audio, AudioBuilder {}
+}
+
+object_type! {
+    /// Represents a document of any kind. Not to be confused with
+    /// [crate::core::Document], the outer wrapper used for serialization.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-document>
+    Document, DocumentBuilder {}
+}
+
+object_type! {
+    /// Represents any kind of event.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-event>
+    Event, EventBuilder {}
+}
+
+object_type! {
+    /// An image document of any kind.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-image>
+    Image, ImageBuilder {}
+}
+
+object_type! {
+    /// Represents a short written work typically less than a single paragraph
+    /// in length.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-note>
+    Note, NoteBuilder {}
 }
 
 impl Note {
     pub fn new(name: String, content: String) -> Self {
-        Note {
-            base: ObjectBuilder::new()
-                .object_type("Note".to_string())
-                .name(name)
-                .content(content)
-                .build(),
-        }
+        NoteBuilder::new().name(name).content(content).build()
     }
 }
 
-impl Serde for Note {}
+object_type! {
+    /// Represents a Web Page.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-page>
+    Page, PageBuilder {}
+}
 
-impl std::ops::Deref for Note {
-    type Target = Object<Null>;
+object_type! {
+    /// Represents a logical or physical location.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-place>
+    Place, PlaceBuilder {
+        /// The accuracy of the position coordinates, as a percentage (0-100).
+        accuracy: f64,
+        /// The altitude of the place, measured in `units`.
+        altitude: f64,
+        /// The latitude of the place.
+        latitude: f64,
+        /// The longitude of the place.
+        longitude: f64,
+        /// The radius from the given latitude and longitude, measured in
+        /// `units`.
+        radius: f64,
+        /// One of "cm", "feet", "inches", "km", "m", "miles", or an absolute
+        /// IRI. Defaults to "m" when not specified.
+        units: String,
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.base
+object_type! {
+    /// A [Profile] is a content object that describes another [Object],
+    /// typically used to describe Actor Type objects.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-profile>
+    Profile, ProfileBuilder {
+        /// The object this profile describes.
+        describes: Reference<Object<Null>>,
+    }
+}
+
+object_type! {
+    /// Describes a relationship between two individuals. The subject and
+    /// object properties are used to identify the connected individuals.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-relationship>
+    Relationship, RelationshipBuilder {
+        /// The subject of the relationship.
+        subject: Reference<Object<Null>>,
+        /// The entity the subject is related to.
+        object: OneOrMany<Reference<Object<Null>>>,
+        /// The kind of relationship, typically an IRI into a relationship
+        /// vocabulary.
+        relationship: OneOrMany<Reference<Object<Null>>>,
+    }
+}
+
+object_type! {
+    /// A [Tombstone] represents a content object that has been deleted. It can
+    /// be used in [crate::core::Collection]s to signify that there used to be
+    /// an object at this position, but it has been deleted.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tombstone>
+    Tombstone, TombstoneBuilder {
+        /// The type of the object that was deleted.
+        former_type: String,
+        /// The date and time at which the object was deleted.
+        deleted: DateTime<Utc>,
     }
 }
 
-// TODO: expand to actor types: https://www.w3.org/TR/activitystreams-vocabulary/#actor-types
+object_type! {
+    /// Represents a video document of any kind.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-video>
+    Video, VideoBuilder {}
+}
+
+/// Generates a typed wrapper and builder for a [Link] subtype. The wrapper
+/// derefs to [Link] and refuses to deserialize from a link carrying any other
+/// `type`.
+macro_rules! link_type {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $builder:ident
+    ) => {
+        $(#[$meta])*
+        #[derive(Serialize, Debug, Clone)]
+        pub struct $name {
+            #[serde(flatten)]
+            base: Link,
+        }
+
+        impl $name {
+            pub const TYPE: &'static str = stringify!($name);
+        }
+
+        impl Serde for $name {}
+
+        impl std::ops::Deref for $name {
+            type Target = Link;
+
+            fn deref(&self) -> &Self::Target {
+                &self.base
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                crate::error::try_from::<_, Link, _>(deserializer)
+            }
+        }
+
+        impl TryFrom<Link> for $name {
+            type Error = UnexpectedType;
+
+            fn try_from(link: Link) -> Result<Self, Self::Error> {
+                if link.link_type == $name::TYPE {
+                    return Ok($name { base: link });
+                }
+                Err(UnexpectedType {
+                    expected: $name::TYPE,
+                    found: Some(link.link_type),
+                })
+            }
+        }
+
+        impl From<$name> for Link {
+            fn from(link: $name) -> Self {
+                link.base
+            }
+        }
+
+        impl<T> From<$name> for Reference<T> {
+            fn from(link: $name) -> Self {
+                Reference::Link(Box::new(link.base))
+            }
+        }
+
+        #[doc = concat!("Builder for a [", stringify!($name), "].")]
+        #[derive(Clone)]
+        pub struct $builder {
+            base: LinkBuilder,
+        }
+
+        impl $builder {
+            pub fn new(href: Iri) -> Self {
+                $builder {
+                    base: LinkBuilder::new(UriBuilder::new(href)).link_type($name::TYPE.to_string()),
+                }
+            }
+
+            pub fn add_rel(mut self, rel: String) -> Self {
+                self.base = self.base.add_rel(rel);
+                self
+            }
+
+            pub fn name(mut self, name: String) -> Self {
+                self.base = self.base.name(name);
+                self
+            }
+
+            pub fn hreflang(mut self, hreflang: LanguageTag) -> Self {
+                self.base = self.base.hreflang(hreflang);
+                self
+            }
+
+            pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+                self.base = self.base.extension(name, value);
+                self
+            }
+
+            pub fn build(self) -> $name {
+                $name {
+                    base: self.base.build(),
+                }
+            }
+        }
+
+        impl<T> From<$builder> for Reference<T> {
+            fn from(link: $builder) -> Self {
+                Reference::Link(Box::new(link.base.build()))
+            }
+        }
+    };
+}
+
+link_type! {
+    /// A specialized [Link] that represents an @mention.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-mention>
+    Mention, MentionBuilder
+}
+
+link_type! {
+    /// A [Link] to a hashtag, as used by Mastodon and others. Not part of the
+    /// Activity Streams vocabulary, but proposed as `as:Hashtag`.
+    /// <https://docs.joinmastodon.org/spec/activitypub/#Hashtag>
+    Hashtag, HashtagBuilder
+}
+
+/// Additional endpoints of an actor which may be useful either to the actor
+/// or to others interacting with it.
+/// <https://www.w3.org/TR/activitypub/#endpoints>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoints {
+    /// Endpoint through which a client may fetch objects it cannot access
+    /// directly, with the server's credentials.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_url: Option<Iri>,
+    /// Endpoint where a client obtains authorization to act for the actor
+    /// through OAuth 2.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth_authorization_endpoint: Option<Iri>,
+    /// Endpoint where a client obtains an OAuth 2.0 access token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth_token_endpoint: Option<Iri>,
+    /// Endpoint where a client's public key can be authorized for the actor.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provide_client_key: Option<Iri>,
+    /// Endpoint where a client key can be signed by the actor's key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sign_client_key: Option<Iri>,
+    /// An inbox shared by all actors of the server, used for delivery to
+    /// many recipients at once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_inbox: Option<Iri>,
+}
+
+impl Serde for Endpoints {}
+
+/// The key an actor signs its requests with. Not part of ActivityPub itself
+/// but defined by the security vocabulary, `https://w3id.org/security/v1`.
+/// <https://docs.joinmastodon.org/spec/activitypub/#publicKey>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKey {
+    pub id: Iri,
+    pub owner: Iri,
+    pub public_key_pem: String,
+}
+
+impl Serde for PublicKey {}
+
+/// Actor types are [Object] types that are capable of performing activities.
+/// [Actor] is the untyped base shared by [Application], [Group],
+/// [Organization], [Person] and [Service]; use [ActorKind] to tell them apart.
+/// <https://www.w3.org/TR/activitystreams-vocabulary/#actor-types>
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Actor {
+pub struct Actor<ExtensionT = Null> {
     #[serde(flatten)]
-    base: Object<Null>,
+    base: Object<Null, ExtensionT>,
 
     #[serde(rename = "preferredUsername")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preferred_username: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub inbox: Option<String>,
+    pub inbox: Option<Iri>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outbox: Option<Iri>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub outbox: Option<String>,
+    pub followers: Option<Iri>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub followers: Option<String>,
+    pub following: Option<Iri>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub following: Option<String>,
+    pub liked: Option<Iri>,
+    /// Supplementary collections which may be of interest.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub liked: Option<String>,
+    pub streams: Option<OneOrMany<Iri>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoints: Option<Endpoints>,
+    #[serde(rename = "publicKey", skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<PublicKey>,
+}
+
+impl Actor {
+    /// Resolves this actor into its [ActorKind].
+    pub fn into_kind(self) -> ActorKind {
+        ActorKind::from(self)
+    }
 }
 
-impl Serde for Actor {}
+impl<ExtensionT> Actor<ExtensionT> {
+    /// The shared inbox, if the actor's server has one.
+    pub fn shared_inbox(&self) -> Option<&Iri> {
+        self.endpoints
+            .as_ref()
+            .and_then(|endpoints| endpoints.shared_inbox.as_ref())
+    }
+}
+
+impl<ExtensionT: Extension> Serde for Actor<ExtensionT> {
+    fn extend_context(context: &mut Context) {
+        context.extend::<ExtensionT>();
+    }
+}
 
-impl std::ops::Deref for Actor {
-    type Target = Object<Null>;
+impl<ExtensionT> std::ops::Deref for Actor<ExtensionT> {
+    type Target = Object<Null, ExtensionT>;
 
     fn deref(&self) -> &Self::Target {
         &self.base
@@ -65,15 +607,18 @@ impl std::ops::Deref for Actor {
 
 /// Builder for an [Actor].
 #[derive(Clone)]
-pub struct ActorBuilder {
-    base: ObjectBuilder<Null>,
+pub struct ActorBuilder<ExtensionT = Null> {
+    base: ObjectBuilder<Null, ExtensionT>,
 
     preferred_username: Option<String>,
-    inbox: Option<String>,
-    outbox: Option<String>,
-    followers: Option<String>,
-    following: Option<String>,
-    liked: Option<String>,
+    inbox: Option<Iri>,
+    outbox: Option<Iri>,
+    followers: Option<Iri>,
+    following: Option<Iri>,
+    liked: Option<Iri>,
+    streams: Option<OneOrMany<Iri>>,
+    endpoints: Option<Endpoints>,
+    public_key: Option<PublicKey>,
 }
 
 impl ActorBuilder {
@@ -86,36 +631,72 @@ impl ActorBuilder {
             followers: None,
             following: None,
             liked: None,
+            streams: None,
+            endpoints: None,
+            public_key: None,
+        }
+    }
+}
+
+impl<ExtensionT: Clone> ActorBuilder<ExtensionT> {
+    /// Attaches a typed extension, see [crate::core::Extension].
+    pub fn with_extension<OtherT: Extension>(self, extension: OtherT) -> ActorBuilder<OtherT> {
+        ActorBuilder {
+            base: self.base.with_extension(extension),
+            preferred_username: self.preferred_username,
+            inbox: self.inbox,
+            outbox: self.outbox,
+            followers: self.followers,
+            following: self.following,
+            liked: self.liked,
+            streams: self.streams,
+            endpoints: self.endpoints,
+            public_key: self.public_key,
         }
     }
 
-    pub fn id(mut self, id: http::Uri) -> Self {
-        self.base.id(id);
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
         self
     }
 
     pub fn name(mut self, name: String) -> Self {
-        self.base.name(name);
+        self.base = self.base.name(name);
         self
     }
 
-    pub fn url(mut self, url: http::Uri) -> Self {
-        self.base.url(url);
+    pub fn url(mut self, url: impl Into<LinkReference>) -> Self {
+        self.base = self.base.url(url);
         self
     }
 
     pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
-        self.base.published(datetime);
+        self.base = self.base.published(datetime);
         self
     }
 
-    pub fn image(mut self, image: LinkBuilder) -> Self {
-        self.base.image(image);
+    pub fn image(mut self, image: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.image(image);
         self
     }
 
     pub fn summary(mut self, summary: String) -> Self {
-        self.base.summary(summary);
+        self.base = self.base.summary(summary);
+        self
+    }
+
+    pub fn icon(mut self, icon: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.icon(icon);
+        self
+    }
+
+    pub fn add_attachment(mut self, attachment: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_attachment(attachment);
+        self
+    }
+
+    pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+        self.base = self.base.updated(datetime);
         self
     }
 
@@ -124,32 +705,97 @@ impl ActorBuilder {
         self
     }
 
-    pub fn inbox(mut self, inbox: String) -> Self {
+    pub fn inbox(mut self, inbox: Iri) -> Self {
         self.inbox = Some(inbox);
         self
     }
 
-    pub fn outbox(mut self, outbox: String) -> Self {
+    pub fn outbox(mut self, outbox: Iri) -> Self {
         self.outbox = Some(outbox);
         self
     }
 
-    pub fn followers(mut self, followers: String) -> Self {
+    pub fn followers(mut self, followers: Iri) -> Self {
         self.followers = Some(followers);
         self
     }
 
-    pub fn following(mut self, following: String) -> Self {
+    pub fn following(mut self, following: Iri) -> Self {
         self.following = Some(following);
         self
     }
 
-    pub fn liked(mut self, liked: String) -> Self {
+    pub fn liked(mut self, liked: Iri) -> Self {
         self.liked = Some(liked);
         self
     }
 
-    pub fn build(self) -> Actor {
+    pub fn add_stream(mut self, stream: Iri) -> Self {
+        push_value(&mut self.streams, stream);
+        self
+    }
+
+    pub fn proxy_url(mut self, proxy_url: Iri) -> Self {
+        self.endpoints
+            .get_or_insert_with(Endpoints::default)
+            .proxy_url = Some(proxy_url);
+        self
+    }
+
+    pub fn oauth_authorization_endpoint(mut self, endpoint: Iri) -> Self {
+        self.endpoints
+            .get_or_insert_with(Endpoints::default)
+            .oauth_authorization_endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn oauth_token_endpoint(mut self, endpoint: Iri) -> Self {
+        self.endpoints
+            .get_or_insert_with(Endpoints::default)
+            .oauth_token_endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn provide_client_key(mut self, endpoint: Iri) -> Self {
+        self.endpoints
+            .get_or_insert_with(Endpoints::default)
+            .provide_client_key = Some(endpoint);
+        self
+    }
+
+    pub fn sign_client_key(mut self, endpoint: Iri) -> Self {
+        self.endpoints
+            .get_or_insert_with(Endpoints::default)
+            .sign_client_key = Some(endpoint);
+        self
+    }
+
+    pub fn shared_inbox(mut self, shared_inbox: Iri) -> Self {
+        self.endpoints
+            .get_or_insert_with(Endpoints::default)
+            .shared_inbox = Some(shared_inbox);
+        self
+    }
+
+    /// Sets the key the actor signs its requests with; `owner` is usually the
+    /// actor's own id.
+    pub fn public_key(mut self, id: Iri, owner: Iri, public_key_pem: String) -> Self {
+        self.public_key = Some(PublicKey {
+            id,
+            owner,
+            public_key_pem,
+        });
+        self
+    }
+
+    /// Sets a member not defined by Activity Streams, e.g.
+    /// `manuallyApprovesFollowers`.
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
+    }
+
+    pub fn build(self) -> Actor<ExtensionT> {
         Actor {
             base: self.base.build(),
 
@@ -159,95 +805,2426 @@ impl ActorBuilder {
             followers: self.followers,
             following: self.following,
             liked: self.liked,
+            streams: self.streams,
+            endpoints: self.endpoints,
+            public_key: self.public_key,
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::core::{ContextBuilder, Document};
-    use pretty_assertions::assert_eq;
+impl<ExtensionT> Reference<Actor<ExtensionT>> {
+    /// The IRI referred to: the IRI itself, the `id` of an embedded actor or
+    /// the `href` of a link.
+    pub fn id(&self) -> Option<&Iri> {
+        match self {
+            Reference::Iri(iri) => Some(iri),
+            Reference::Link(link) => Some(&link.href.href),
+            Reference::Object(actor) => actor.id.as_ref(),
+        }
+    }
+}
 
-    #[test]
-    fn serialize_actor() {
-        let actual = Document::new(
-            ContextBuilder::new().build(),
-            ActorBuilder::new(String::from("Person"))
-                .id("https://example.com/person/1234"
-                    .parse::<http::Uri>()
-                    .unwrap())
-                .name(String::from("name"))
-                .preferred_username(String::from("dma"))
-                .build(),
-        );
-        let expected = r#"{
-  "@context": {
-    "@vocab": "https://www.w3.org/ns/activitystreams"
-  },
-  "type": "Person",
-  "id": "https://example.com/person/1234",
-  "name": "name",
-  "preferredUsername": "dma"
-}"#;
-        assert!(actual.to_json_pretty().is_ok());
-        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+impl<ExtensionT> From<Actor<ExtensionT>> for Reference<Actor<ExtensionT>> {
+    fn from(actor: Actor<ExtensionT>) -> Self {
+        Reference::Object(Box::new(actor))
     }
+}
 
-    #[test]
-    fn deserialize_actor() {
-        let actual = r#"{
-  "@context": {
-    "@vocab": "https://www.w3.org/ns/activitystreams"
-  },
-  "type": "Person",
-  "id": "https://example.com/person/1234",
-  "name": "name",
-  "preferredUsername": "dma"
-}"#;
-        let document: Document<Actor> = Document::from_json(String::from(actual)).unwrap();
-        let actor = document.object as Actor;
-        assert_eq!(actor.object_type, Some(String::from("Person")));
-        assert_eq!(
-            actor.id,
-            Some(String::from("https://example.com/person/1234"))
-        );
-        assert_eq!(actor.name, Some(String::from("name")));
-        assert_eq!(actor.preferred_username, Some(String::from("dma")));
+impl<ExtensionT: Clone> From<ActorBuilder<ExtensionT>> for Reference<Actor<ExtensionT>> {
+    fn from(actor: ActorBuilder<ExtensionT>) -> Self {
+        Reference::Object(Box::new(actor.build()))
     }
+}
 
-    #[test]
-    fn serialize_note() {
-        let actual = Document::new(
-            ContextBuilder::new().build(),
-            Note::new(String::from("Name"), String::from("Content")),
-        );
-        let expected = r#"{
-  "@context": {
-    "@vocab": "https://www.w3.org/ns/activitystreams"
-  },
-  "type": "Note",
-  "name": "Name",
-  "content": "Content"
-}"#;
-        assert!(actual.to_json_pretty().is_ok());
-        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+/// Error returned when a value is converted into a type whose fixed `type`
+/// value it doesn't carry, e.g. deserializing a `Group` into a [Person].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnexpectedType {
+    pub expected: &'static str,
+    pub found: Option<String>,
+}
+
+impl fmt::Display for UnexpectedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.found {
+            Some(found) => write!(f, "expected type {}, found {}", self.expected, found),
+            None => write!(f, "expected type {}, found none", self.expected),
+        }
     }
+}
 
-    #[test]
-    fn deserialize_note() {
-        let actual = r#"{
-  "@context": {
-    "@vocab": "https://www.w3.org/ns/activitystreams"
-  },
-  "type": "Note",
-  "name": "Name",
-  "content": "Content"
+impl std::error::Error for UnexpectedType {}
+
+/// Generates a typed wrapper and builder for one of the Activity Streams actor
+/// types. The wrapper derefs to [Actor] and refuses to deserialize from a
+/// document carrying any other `type`.
+macro_rules! actor_type {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $builder:ident
+    ) => {
+        $(#[$meta])*
+        #[derive(Serialize, Debug, Clone)]
+        pub struct $name<ExtensionT = Null> {
+            #[serde(flatten)]
+            base: Actor<ExtensionT>,
+        }
+
+        impl $name {
+            pub const TYPE: &'static str = stringify!($name);
+
+            pub fn new(id: Iri, name: String) -> Self {
+                $builder::new().id(id).name(name).build()
+            }
+        }
+
+        impl<ExtensionT: Extension> Serde for $name<ExtensionT> {
+            fn extend_context(context: &mut Context) {
+                context.extend::<ExtensionT>();
+            }
+        }
+
+        impl<ExtensionT> std::ops::Deref for $name<ExtensionT> {
+            type Target = Actor<ExtensionT>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.base
+            }
+        }
+
+        impl<'de, ExtensionT: Deserialize<'de>> Deserialize<'de> for $name<ExtensionT> {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                crate::error::try_from::<_, Actor<ExtensionT>, _>(deserializer)
+            }
+        }
+
+        impl<ExtensionT> TryFrom<Actor<ExtensionT>> for $name<ExtensionT> {
+            type Error = UnexpectedType;
+
+            fn try_from(actor: Actor<ExtensionT>) -> Result<Self, Self::Error> {
+                if actor.has_type($name::TYPE) {
+                    return Ok($name { base: actor });
+                }
+                Err(UnexpectedType {
+                    expected: $name::TYPE,
+                    found: actor.object_type.as_ref().map(|t| t.iter().collect::<Vec<_>>().join(", ")),
+                })
+            }
+        }
+
+        impl<ExtensionT> From<$name<ExtensionT>> for Actor<ExtensionT> {
+            fn from(actor: $name<ExtensionT>) -> Self {
+                actor.base
+            }
+        }
+
+        #[doc = concat!("Builder for a [", stringify!($name), "].")]
+        #[derive(Clone)]
+        pub struct $builder<ExtensionT = Null> {
+            base: ActorBuilder<ExtensionT>,
+        }
+
+        impl $builder {
+            pub fn new() -> Self {
+                $builder {
+                    base: ActorBuilder::new($name::TYPE.to_string()),
+                }
+            }
+        }
+
+        impl<ExtensionT: Clone> $builder<ExtensionT> {
+            #[doc = concat!("Attaches a typed extension, see [crate::core::Extension].")]
+            pub fn with_extension<OtherT: Extension>(self, extension: OtherT) -> $builder<OtherT> {
+                $builder {
+                    base: self.base.with_extension(extension),
+                }
+            }
+
+            pub fn id(mut self, id: Iri) -> Self {
+                self.base = self.base.id(id);
+                self
+            }
+
+            pub fn name(mut self, name: String) -> Self {
+                self.base = self.base.name(name);
+                self
+            }
+
+            pub fn url(mut self, url: impl Into<LinkReference>) -> Self {
+                self.base = self.base.url(url);
+                self
+            }
+
+            pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.published(datetime);
+                self
+            }
+
+            pub fn image(mut self, image: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.image(image);
+                self
+            }
+
+            pub fn summary(mut self, summary: String) -> Self {
+                self.base = self.base.summary(summary);
+                self
+            }
+
+            pub fn icon(mut self, icon: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.icon(icon);
+                self
+            }
+
+            pub fn add_attachment(mut self, attachment: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_attachment(attachment);
+                self
+            }
+
+            pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.updated(datetime);
+                self
+            }
+
+            pub fn preferred_username(mut self, username: String) -> Self {
+                self.base = self.base.preferred_username(username);
+                self
+            }
+
+            pub fn inbox(mut self, inbox: Iri) -> Self {
+                self.base = self.base.inbox(inbox);
+                self
+            }
+
+            pub fn outbox(mut self, outbox: Iri) -> Self {
+                self.base = self.base.outbox(outbox);
+                self
+            }
+
+            pub fn followers(mut self, followers: Iri) -> Self {
+                self.base = self.base.followers(followers);
+                self
+            }
+
+            pub fn following(mut self, following: Iri) -> Self {
+                self.base = self.base.following(following);
+                self
+            }
+
+            pub fn liked(mut self, liked: Iri) -> Self {
+                self.base = self.base.liked(liked);
+                self
+            }
+
+            pub fn add_stream(mut self, stream: Iri) -> Self {
+                self.base = self.base.add_stream(stream);
+                self
+            }
+
+            pub fn proxy_url(mut self, proxy_url: Iri) -> Self {
+                self.base = self.base.proxy_url(proxy_url);
+                self
+            }
+
+            pub fn oauth_authorization_endpoint(mut self, endpoint: Iri) -> Self {
+                self.base = self.base.oauth_authorization_endpoint(endpoint);
+                self
+            }
+
+            pub fn oauth_token_endpoint(mut self, endpoint: Iri) -> Self {
+                self.base = self.base.oauth_token_endpoint(endpoint);
+                self
+            }
+
+            pub fn provide_client_key(mut self, endpoint: Iri) -> Self {
+                self.base = self.base.provide_client_key(endpoint);
+                self
+            }
+
+            pub fn sign_client_key(mut self, endpoint: Iri) -> Self {
+                self.base = self.base.sign_client_key(endpoint);
+                self
+            }
+
+            pub fn shared_inbox(mut self, shared_inbox: Iri) -> Self {
+                self.base = self.base.shared_inbox(shared_inbox);
+                self
+            }
+
+            pub fn public_key(mut self, id: Iri, owner: Iri, public_key_pem: String) -> Self {
+                self.base = self.base.public_key(id, owner, public_key_pem);
+                self
+            }
+
+            pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+                self.base = self.base.extension(name, value);
+                self
+            }
+
+            pub fn build(self) -> $name<ExtensionT> {
+                $name {
+                    base: self.base.build(),
+                }
+            }
+        }
+
+        impl Default for $builder {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<ExtensionT> From<$builder<ExtensionT>> for ActorBuilder<ExtensionT> {
+            fn from(builder: $builder<ExtensionT>) -> Self {
+                builder.base
+            }
+        }
+
+        impl From<$builder> for Reference<Actor> {
+            fn from(builder: $builder) -> Self {
+                Reference::Object(Box::new(builder.base.build()))
+            }
+        }
+    };
+}
+
+actor_type! {
+    /// Describes a software application.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-application>
+    Application, ApplicationBuilder
+}
+
+actor_type! {
+    /// Represents a formal or informal collective of Actors.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-group>
+    Group, GroupBuilder
+}
+
+actor_type! {
+    /// Represents an organization.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-organization>
+    Organization, OrganizationBuilder
+}
+
+actor_type! {
+    /// Represents an individual person.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-person>
+    Person, PersonBuilder
+}
+
+actor_type! {
+    /// Represents a service of any kind.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-service>
+    Service, ServiceBuilder
+}
+
+/// An [Actor] resolved to one of the Activity Streams actor types, so callers
+/// can match on the kind of actor rather than comparing `type` strings.
+/// Actors with any other `type` are kept as [ActorKind::Other].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged, from = "Actor")]
+pub enum ActorKind {
+    Application(Application),
+    Group(Group),
+    Organization(Organization),
+    Person(Person),
+    Service(Service),
+    Other(Actor),
+}
+
+impl Serde for ActorKind {}
+
+impl From<Actor> for ActorKind {
+    fn from(actor: Actor) -> Self {
+        let object_type = actor.object_type.clone();
+        for t in object_type.iter().flat_map(ObjectType::iter) {
+            match t {
+                Application::TYPE => return ActorKind::Application(Application { base: actor }),
+                Group::TYPE => return ActorKind::Group(Group { base: actor }),
+                Organization::TYPE => return ActorKind::Organization(Organization { base: actor }),
+                Person::TYPE => return ActorKind::Person(Person { base: actor }),
+                Service::TYPE => return ActorKind::Service(Service { base: actor }),
+                _ => {}
+            }
+        }
+        ActorKind::Other(actor)
+    }
+}
+
+impl std::ops::Deref for ActorKind {
+    type Target = Actor;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            ActorKind::Application(actor) => actor,
+            ActorKind::Group(actor) => actor,
+            ActorKind::Organization(actor) => actor,
+            ActorKind::Person(actor) => actor,
+            ActorKind::Service(actor) => actor,
+            ActorKind::Other(actor) => actor,
+        }
+    }
+}
+
+/// Generates a typed wrapper and builder for one of the Activity Streams
+/// activity types. The wrapper derefs to its base (either [Activity] or
+/// [IntransitiveActivity]) and the builder fixes the `type` value, exposing
+/// only the setters listed for that activity.
+macro_rules! activity_type {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $builder:ident, $base:ty, $base_builder:ty {
+            $($setter:ident: $arg:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Serialize, Debug)]
+        pub struct $name {
+            #[serde(flatten)]
+            base: $base,
+        }
+
+        impl $name {
+            pub const TYPE: &'static str = stringify!($name);
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                crate::error::try_from::<_, $base, _>(deserializer)
+            }
+        }
+
+        impl TryFrom<$base> for $name {
+            type Error = UnexpectedType;
+
+            fn try_from(activity: $base) -> Result<Self, Self::Error> {
+                if activity.has_type($name::TYPE) {
+                    return Ok($name { base: activity });
+                }
+                Err(UnexpectedType {
+                    expected: $name::TYPE,
+                    found: activity.object_type.as_ref().map(|t| t.iter().collect::<Vec<_>>().join(", ")),
+                })
+            }
+        }
+
+        impl From<$name> for $base {
+            fn from(activity: $name) -> Self {
+                activity.base
+            }
+        }
+
+        impl Serde for $name {}
+
+        impl std::ops::Deref for $name {
+            type Target = $base;
+
+            fn deref(&self) -> &Self::Target {
+                &self.base
+            }
+        }
+
+        #[doc = concat!("Builder for a [", stringify!($name), "].")]
+        #[derive(Clone)]
+        pub struct $builder {
+            base: $base_builder,
+        }
+
+        impl $builder {
+            pub fn new(summary: String) -> Self {
+                $builder {
+                    base: <$base_builder>::new($name::TYPE.to_string(), summary),
+                }
+            }
+
+            pub fn id(mut self, id: Iri) -> Self {
+                self.base = self.base.id(id);
+                self
+            }
+
+            pub fn name(mut self, name: String) -> Self {
+                self.base = self.base.name(name);
+                self
+            }
+
+            pub fn content(mut self, content: String) -> Self {
+                self.base = self.base.content(content);
+                self
+            }
+
+            pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.published(datetime);
+                self
+            }
+
+            pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+                self.base = self.base.updated(datetime);
+                self
+            }
+
+            pub fn add_to(mut self, to: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_to(to);
+                self
+            }
+
+            pub fn add_bto(mut self, bto: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_bto(bto);
+                self
+            }
+
+            pub fn add_cc(mut self, cc: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_cc(cc);
+                self
+            }
+
+            pub fn add_bcc(mut self, bcc: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.add_bcc(bcc);
+                self
+            }
+
+            pub fn actor(mut self, actor: impl Into<Reference<Actor>>) -> Self {
+                self.base = self.base.actor(actor);
+                self
+            }
+
+            pub fn result(mut self, result: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.result(result);
+                self
+            }
+
+            pub fn instrument(mut self, instrument: impl Into<Reference<Object<Null>>>) -> Self {
+                self.base = self.base.instrument(instrument);
+                self
+            }
+
+            pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+                self.base = self.base.extension(name, value);
+                self
+            }
+
+            $(
+                pub fn $setter(mut self, $setter: $arg) -> Self {
+                    self.base = self.base.$setter($setter);
+                    self
+                }
+            )*
+
+            pub fn build(self) -> $name {
+                $name {
+                    base: self.base.build(),
+                }
+            }
+        }
+    };
+}
+
+activity_type! {
+    /// Indicates that the actor accepts the object. The target property can be
+    /// used in certain circumstances to indicate the context into which the
+    /// object has been accepted.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-accept>
+    Accept, AcceptBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has added the object to the target. If the
+    /// target property is not explicitly specified, the target would need to
+    /// be determined implicitly by context. The origin can be used to identify
+    /// the context from which the object originated.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-add>
+    Add, AddBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+        origin: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is calling the target's attention the object.
+    /// The origin typically has no defined meaning.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-announce>
+    Announce, AnnounceBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// An [IntransitiveActivity] that indicates that the actor has arrived at
+    /// the location. The origin can be used to identify the context from which
+    /// the actor originated. The target typically has no defined meaning.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-arrive>
+    Arrive, ArriveBuilder, IntransitiveActivity, IntransitiveActivityBuilder {
+        origin: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is blocking the object. Blocking is a stronger
+    /// form of [Ignore].
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-block>
+    Block, BlockBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has created the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-create>
+    Create, CreateBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has deleted the object. If specified, the
+    /// origin indicates the context from which the object was deleted.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-delete>
+    Delete, DeleteBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        origin: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor dislikes the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-dislike>
+    Dislike, DislikeBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is "flagging" the object. Flagging is defined
+    /// in the sense common to many social platforms as reporting content as
+    /// being inappropriate for any number of reasons.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-flag>
+    Flag, FlagBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is "following" the object. Following is
+    /// defined in the sense typically used within Social systems in which the
+    /// actor is interested in any activity performed by or on the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-follow>
+    Follow, FollowBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is ignoring the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-ignore>
+    Ignore, IgnoreBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// A specialization of [Offer] in which the actor is extending an
+    /// invitation for the object to the target.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-invite>
+    Invite, InviteBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has joined the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-join>
+    Join, JoinBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has left the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-leave>
+    Leave, LeaveBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor likes, recommends or endorses the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-like>
+    Like, LikeBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has listened to the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-listen>
+    Listen, ListenBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has moved object from origin to target.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-move>
+    Move, MoveBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+        origin: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is offering the object. If specified, the
+    /// target indicates the entity to which the object is being offered.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-offer>
+    Offer, OfferBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+/// Represents a question being asked. [Question] objects are an extension
+/// of [IntransitiveActivity]. That is, the [Question] object is an Activity,
+/// but the direct object is the question itself and therefore it would not
+/// contain an object property. Either of the `one_of` and `any_of` properties
+/// may be used to express possible answers, but a [Question] object must not
+/// have both.
+/// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-question>
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Question {
+    #[serde(flatten)]
+    base: IntransitiveActivity,
+
+    /// The options of a single choice question.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub one_of: Option<OneOrMany<Reference<Object<Null>>>>,
+    /// The options of a multiple choice question.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub any_of: Option<OneOrMany<Reference<Object<Null>>>>,
+    /// Whether, or since when, the question is closed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed: Option<Closed>,
+    /// The number of actors who answered, as used by Mastodon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voters_count: Option<u64>,
+}
+
+impl Question {
+    pub const TYPE: &'static str = "Question";
+
+    /// Whether several options may be chosen, i.e. whether the options are
+    /// given as `anyOf`.
+    pub fn is_multiple_choice(&self) -> bool {
+        self.any_of.is_some()
+    }
+
+    /// The names of the options, in the order they are listed.
+    pub fn options(&self) -> Vec<&str> {
+        self.one_of
+            .iter()
+            .chain(self.any_of.iter())
+            .flatten()
+            .filter_map(|option| match option {
+                Reference::Object(object) => object.name.as_deref(),
+                Reference::Link(link) => link.name.as_deref(),
+                Reference::Iri(_) => None,
+            })
+            .collect()
+    }
+
+    /// Whether the question no longer accepts answers at `now`.
+    pub fn is_closed(&self, now: DateTime<Utc>) -> bool {
+        match self.closed {
+            Some(Closed::Bool(closed)) => closed,
+            Some(Closed::DateTime(closed)) => closed <= now,
+            None => false,
+        }
+    }
+
+    /// Counts the votes cast by `replies`. A vote is a [Create] of a [Note]
+    /// that is `inReplyTo` this question and whose `name` is the chosen
+    /// option. Each actor counts once per option, and for a single choice
+    /// question only their first vote counts; votes published after the
+    /// question closed are ignored.
+    pub fn tally<'a>(&self, replies: impl IntoIterator<Item = &'a Create>) -> Tally {
+        let options = self.options();
+        let mut votes = vec![0; options.len()];
+        let mut voters: BTreeMap<&Iri, BTreeSet<usize>> = BTreeMap::new();
+        for create in replies {
+            if let (Some(Closed::DateTime(closed)), Some(published)) =
+                (self.closed, create.published)
+            {
+                if published > closed {
+                    continue;
+                }
+            }
+            let Some(voter) = create
+                .actor
+                .as_ref()
+                .and_then(OneOrMany::first)
+                .and_then(|actor| actor.id())
+            else {
+                continue;
+            };
+            let notes = create
+                .object
+                .iter()
+                .flatten()
+                .filter_map(Reference::as_object)
+                .filter(|note| note.has_type(Note::TYPE) && self.is_replied_to_by(note));
+            for note in notes {
+                let Some(index) = note
+                    .name
+                    .as_deref()
+                    .and_then(|name| options.iter().position(|option| *option == name))
+                else {
+                    continue;
+                };
+                let chosen = voters.entry(voter).or_default();
+                if !self.is_multiple_choice() && !chosen.is_empty() {
+                    continue;
+                }
+                if chosen.insert(index) {
+                    votes[index] += 1;
+                }
+            }
+        }
+        Tally {
+            votes: options.into_iter().map(String::from).zip(votes).collect(),
+            voters_count: voters.len() as u64,
+        }
+    }
+
+    /// Publishes `tally` on the question: each option's `replies` carries its
+    /// vote count as `totalItems`, the way Mastodon renders polls, and
+    /// `votersCount` the number of voters.
+    pub fn apply_tally(&mut self, tally: &Tally) {
+        let options = self
+            .one_of
+            .iter_mut()
+            .chain(self.any_of.iter_mut())
+            .flatten();
+        for option in options {
+            let Reference::Object(object) = option else {
+                continue;
+            };
+            let count = tally
+                .votes
+                .iter()
+                .find(|(name, _)| object.name.as_ref() == Some(name))
+                .map_or(0, |(_, count)| *count);
+            let mut replies =
+                CollectionBuilder::new(String::from("Collection"), Vec::new()).build();
+            replies.total_items = Some(count as usize);
+            object.replies = Some(Reference::from(replies));
+        }
+        self.voters_count = Some(tally.voters_count);
+    }
+
+    fn is_replied_to_by(&self, note: &Object<Null>) -> bool {
+        let Some(id) = self.id.as_ref() else {
+            return false;
+        };
+        note.in_reply_to
+            .iter()
+            .flatten()
+            .any(|reference| reference.id() == Some(id))
+    }
+}
+
+impl Serde for Question {}
+
+/// A [Question] whose `type` is yet to be checked.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UncheckedQuestion {
+    #[serde(flatten)]
+    base: IntransitiveActivity,
+    one_of: Option<OneOrMany<Reference<Object<Null>>>>,
+    any_of: Option<OneOrMany<Reference<Object<Null>>>>,
+    closed: Option<Closed>,
+    voters_count: Option<u64>,
+}
+
+impl<'de> Deserialize<'de> for Question {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::error::try_from::<_, UncheckedQuestion, _>(deserializer)
+    }
+}
+
+impl TryFrom<UncheckedQuestion> for Question {
+    type Error = UnexpectedType;
+
+    fn try_from(question: UncheckedQuestion) -> Result<Self, Self::Error> {
+        if !question.base.has_type(Question::TYPE) {
+            return Err(UnexpectedType {
+                expected: Question::TYPE,
+                found: question
+                    .base
+                    .object_type
+                    .as_ref()
+                    .map(|t| t.iter().collect::<Vec<_>>().join(", ")),
+            });
+        }
+        Ok(Question {
+            base: question.base,
+            one_of: question.one_of,
+            any_of: question.any_of,
+            closed: question.closed,
+            voters_count: question.voters_count,
+        })
+    }
+}
+
+impl std::ops::Deref for Question {
+    type Target = IntransitiveActivity;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+/// The `closed` property of a [Question]: either the time it closed at, or
+/// simply whether it is closed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Closed {
+    Bool(bool),
+    DateTime(DateTime<Utc>),
+}
+
+impl From<bool> for Closed {
+    fn from(closed: bool) -> Self {
+        Closed::Bool(closed)
+    }
+}
+
+impl From<DateTime<Utc>> for Closed {
+    fn from(closed: DateTime<Utc>) -> Self {
+        Closed::DateTime(closed)
+    }
+}
+
+/// The outcome of [Question::tally].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    /// The number of votes per option, in the order the options are listed.
+    pub votes: Vec<(String, u64)>,
+    /// The number of distinct actors who voted.
+    pub voters_count: u64,
+}
+
+/// Builder for a [Question].
+#[derive(Clone)]
+pub struct QuestionBuilder {
+    base: IntransitiveActivityBuilder,
+    one_of: Option<OneOrMany<Reference<Object<Null>>>>,
+    any_of: Option<OneOrMany<Reference<Object<Null>>>>,
+    closed: Option<Closed>,
+    voters_count: Option<u64>,
+}
+
+impl QuestionBuilder {
+    pub fn new(summary: String) -> Self {
+        QuestionBuilder {
+            base: IntransitiveActivityBuilder::new(Question::TYPE.to_string(), summary),
+            one_of: None,
+            any_of: None,
+            closed: None,
+            voters_count: None,
+        }
+    }
+
+    pub fn id(mut self, id: Iri) -> Self {
+        self.base = self.base.id(id);
+        self
+    }
+
+    pub fn name(mut self, name: String) -> Self {
+        self.base = self.base.name(name);
+        self
+    }
+
+    pub fn content(mut self, content: String) -> Self {
+        self.base = self.base.content(content);
+        self
+    }
+
+    pub fn published(mut self, datetime: DateTime<Utc>) -> Self {
+        self.base = self.base.published(datetime);
+        self
+    }
+
+    pub fn updated(mut self, datetime: DateTime<Utc>) -> Self {
+        self.base = self.base.updated(datetime);
+        self
+    }
+
+    pub fn add_to(mut self, to: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_to(to);
+        self
+    }
+
+    pub fn add_bto(mut self, bto: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_bto(bto);
+        self
+    }
+
+    pub fn add_cc(mut self, cc: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_cc(cc);
+        self
+    }
+
+    pub fn add_bcc(mut self, bcc: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.add_bcc(bcc);
+        self
+    }
+
+    pub fn actor(mut self, actor: impl Into<Reference<Actor>>) -> Self {
+        self.base = self.base.actor(actor);
+        self
+    }
+
+    pub fn result(mut self, result: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.result(result);
+        self
+    }
+
+    pub fn instrument(mut self, instrument: impl Into<Reference<Object<Null>>>) -> Self {
+        self.base = self.base.instrument(instrument);
+        self
+    }
+
+    pub fn extension(mut self, name: String, value: serde_json::Value) -> Self {
+        self.base = self.base.extension(name, value);
+        self
+    }
+
+    /// Adds an option to a single choice question.
+    pub fn add_one_of(mut self, option: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.one_of, option.into());
+        self
+    }
+
+    /// Adds an option to a multiple choice question.
+    pub fn add_any_of(mut self, option: impl Into<Reference<Object<Null>>>) -> Self {
+        push_value(&mut self.any_of, option.into());
+        self
+    }
+
+    pub fn closed(mut self, closed: impl Into<Closed>) -> Self {
+        self.closed = Some(closed.into());
+        self
+    }
+
+    pub fn voters_count(mut self, voters_count: u64) -> Self {
+        self.voters_count = Some(voters_count);
+        self
+    }
+
+    pub fn build(self) -> Question {
+        Question {
+            base: self.base.build(),
+            one_of: self.one_of,
+            any_of: self.any_of,
+            closed: self.closed,
+            voters_count: self.voters_count,
+        }
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is rejecting the object. The target and origin
+    /// typically have no defined meaning.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-reject>
+    Reject, RejectBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has read the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-read>
+    Read, ReadBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is removing the object. If specified, the
+    /// origin indicates the context from which the object is being removed.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-remove>
+    Remove, RemoveBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+        origin: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// A specialization of [Reject] in which the rejection is considered
+    /// tentative.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tentativereject>
+    TentativeReject, TentativeRejectBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// A specialization of [Accept] indicating that the acceptance is
+    /// tentative.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tentativeaccept>
+    TentativeAccept, TentativeAcceptBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+        target: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is traveling to target from origin. [Travel]
+    /// is an [IntransitiveActivity] whose actor specifies the direct object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-travel>
+    Travel, TravelBuilder, IntransitiveActivity, IntransitiveActivityBuilder {
+        target: impl Into<Reference<Object<Null>>>,
+        origin: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor is undoing the object. In most cases, the
+    /// object will be an [Activity] describing some previously performed
+    /// action.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-undo>
+    Undo, UndoBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has updated the object. Note, however, that
+    /// this vocabulary does not define a mechanism for describing the actual
+    /// set of modifications made to object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-update>
+    Update, UpdateBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+activity_type! {
+    /// Indicates that the actor has viewed the object.
+    /// <https://www.w3.org/TR/activitystreams-vocabulary/#dfn-view>
+    View, ViewBuilder, Activity, ActivityBuilder {
+        object: impl Into<Reference<Object<Null>>>,
+    }
+}
+
+// Mastodon compatibility profile. Mastodon, and most of the software
+// federating with it, exchanges actors and statuses carrying properties from
+// its own `toot:` namespace, the security vocabulary and schema.org on top of
+// Activity Streams. They are modelled as [Extension]s, so they can be attached
+// to any of the types above.
+// <https://docs.joinmastodon.org/spec/activitypub/>
+
+const MASTODON_NAMESPACE: &str = "http://joinmastodon.org/ns#";
+const SECURITY_CONTEXT: &str = "https://w3id.org/security/v1";
+
+object_type! {
+    /// A name/value pair shown as a profile field, listed in a Mastodon
+    /// actor's `attachment`. Defined by schema.org rather than Activity
+    /// Streams.
+    /// <https://docs.joinmastodon.org/spec/activitypub/#PropertyValue>
+    PropertyValue, PropertyValueBuilder {
+        /// The value of the field, usually HTML.
+        value: String,
+    }
+}
+
+object_type! {
+    /// A custom emoji, used as a tag whose `name` (e.g. `:blobcat:`) is
+    /// replaced in the content by the image given as its `icon`.
+    /// <https://docs.joinmastodon.org/spec/activitypub/#emoji>
+    Emoji, EmojiBuilder {}
+}
+
+impl From<Emoji> for Reference<Object<Null>> {
+    fn from(emoji: Emoji) -> Self {
+        Reference::Object(Box::new(emoji.base))
+    }
+}
+
+impl From<EmojiBuilder> for Reference<Object<Null>> {
+    fn from(emoji: EmojiBuilder) -> Self {
+        Reference::from(emoji.build())
+    }
+}
+
+/// The properties Mastodon adds to actors.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MastodonActorExtension {
+    /// Collection of the actor's pinned statuses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub featured: Option<Iri>,
+    /// Collection of the hashtags featured on the actor's profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub featured_tags: Option<Iri>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manually_approves_followers: Option<bool>,
+    /// Whether the actor may be listed in directories.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discoverable: Option<bool>,
+    /// Whether the actor's public statuses may be indexed for search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memorial: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspended: Option<bool>,
+    /// Previous identities of the actor, checked when it moves accounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub also_known_as: Option<OneOrMany<Iri>>,
+    /// The actor this account has moved to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<Iri>,
+}
+
+impl Serde for MastodonActorExtension {}
+
+impl Extension for MastodonActorExtension {
+    fn terms() -> Vec<(String, TermDefinition)> {
+        vec![
+            mastodon_term("manuallyApprovesFollowers", "as:manuallyApprovesFollowers"),
+            mastodon_term("toot", MASTODON_NAMESPACE),
+            mastodon_id_term("featured", "toot:featured"),
+            mastodon_id_term("featuredTags", "toot:featuredTags"),
+            mastodon_id_term("alsoKnownAs", "as:alsoKnownAs"),
+            mastodon_id_term("movedTo", "as:movedTo"),
+            mastodon_term("schema", "http://schema.org#"),
+            mastodon_term("PropertyValue", "schema:PropertyValue"),
+            mastodon_term("value", "schema:value"),
+            mastodon_term("discoverable", "toot:discoverable"),
+            mastodon_term("suspended", "toot:suspended"),
+            mastodon_term("memorial", "toot:memorial"),
+            mastodon_term("indexable", "toot:indexable"),
+        ]
+    }
+
+    fn contexts() -> Vec<String> {
+        vec![SECURITY_CONTEXT.to_string()]
+    }
+}
+
+/// The properties Mastodon adds to statuses.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MastodonStatusExtension {
+    /// Whether the content is hidden behind the `summary` as a content
+    /// warning.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub atom_uri: Option<Iri>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_reply_to_atom_uri: Option<Iri>,
+    /// Identifies the thread the status belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation: Option<Iri>,
+}
+
+impl Serde for MastodonStatusExtension {}
+
+impl Extension for MastodonStatusExtension {
+    fn terms() -> Vec<(String, TermDefinition)> {
+        vec![
+            mastodon_term("ostatus", "http://ostatus.org#"),
+            mastodon_term("atomUri", "ostatus:atomUri"),
+            mastodon_term("inReplyToAtomUri", "ostatus:inReplyToAtomUri"),
+            mastodon_term("conversation", "ostatus:conversation"),
+            mastodon_term("sensitive", "as:sensitive"),
+            mastodon_term("toot", MASTODON_NAMESPACE),
+            mastodon_term("blurhash", "toot:blurhash"),
+            (
+                String::from("focalPoint"),
+                ExpandedTermDefinition::new(String::from("toot:focalPoint"))
+                    .container(String::from("@list"))
+                    .into(),
+            ),
+            mastodon_term("Hashtag", "as:Hashtag"),
+            mastodon_term("Emoji", "toot:Emoji"),
+            mastodon_term("votersCount", "toot:votersCount"),
+        ]
+    }
+}
+
+fn mastodon_term(term: &str, iri: &str) -> (String, TermDefinition) {
+    (term.to_string(), TermDefinition::from(iri))
+}
+
+fn mastodon_id_term(term: &str, iri: &str) -> (String, TermDefinition) {
+    (
+        term.to_string(),
+        ExpandedTermDefinition::new(iri.to_string())
+            .term_type(String::from("@id"))
+            .into(),
+    )
+}
+
+/// An actor as served by Mastodon.
+pub type MastodonActor = Actor<MastodonActorExtension>;
+
+/// A status as served by Mastodon.
+pub type MastodonStatus = Note<MastodonStatusExtension>;
+
+impl<ExtensionT> Actor<ExtensionT> {
+    /// The profile fields listed in `attachment`.
+    pub fn property_values(&self) -> Vec<PropertyValue> {
+        self.attachment
+            .iter()
+            .flatten()
+            .filter_map(Reference::as_object)
+            .filter(|object| object.has_type(PropertyValue::TYPE))
+            .filter_map(|object| {
+                serde_json::to_value(object)
+                    .and_then(serde_json::from_value)
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+/// An entry of an object's `tag` list, resolved to the kind of tag it is.
+/// Tags of any other `type` are kept as [Tag::Link] or [Tag::Object].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged, from = "Reference<Object<Null>>")]
+pub enum Tag {
+    Mention(Mention),
+    Hashtag(Hashtag),
+    Emoji(Emoji),
+    Link(Link),
+    Object(Object<Null>),
+    Iri(Iri),
+}
+
+impl Serde for Tag {}
+
+impl From<Reference<Object<Null>>> for Tag {
+    fn from(tag: Reference<Object<Null>>) -> Self {
+        match tag {
+            Reference::Iri(iri) => Tag::Iri(iri),
+            Reference::Link(link) => match link.link_type.as_str() {
+                Mention::TYPE => Tag::Mention(Mention { base: *link }),
+                Hashtag::TYPE => Tag::Hashtag(Hashtag { base: *link }),
+                _ => Tag::Link(*link),
+            },
+            Reference::Object(object) if object.has_type(Emoji::TYPE) => {
+                Tag::Emoji(Emoji { base: *object })
+            }
+            Reference::Object(object) => Tag::Object(*object),
+        }
+    }
+}
+
+impl From<Tag> for Reference<Object<Null>> {
+    fn from(tag: Tag) -> Self {
+        match tag {
+            Tag::Mention(mention) => Reference::from(mention),
+            Tag::Hashtag(hashtag) => Reference::from(hashtag),
+            Tag::Emoji(emoji) => Reference::from(emoji),
+            Tag::Link(link) => Reference::from(link),
+            Tag::Object(object) => Reference::from(object),
+            Tag::Iri(iri) => Reference::Iri(iri),
+        }
+    }
+}
+
+impl<AttributedToT, ExtensionT> Object<AttributedToT, ExtensionT> {
+    /// The `tag` list, each entry resolved to its kind of [Tag].
+    pub fn tags(&self) -> Vec<Tag> {
+        self.tag.iter().flatten().cloned().map(Tag::from).collect()
+    }
+
+    /// The IRIs of the actors mentioned in the `tag` list, e.g. to address
+    /// them when delivering the object.
+    pub fn mentioned_actors(&self) -> Vec<&Iri> {
+        self.tag
+            .iter()
+            .flatten()
+            .filter_map(Reference::as_link)
+            .filter(|link| link.link_type == Mention::TYPE)
+            .map(|link| &link.href.href)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Context, ContextBuilder, Document};
+    use crate::jsonld::BundledContextLoader;
+    use crate::validate::{Mode, Validate};
+    use crate::Error;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn serialize_actor() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            ActorBuilder::new(String::from("Person"))
+                .id("https://example.com/person/1234".parse::<Iri>().unwrap())
+                .name(String::from("name"))
+                .preferred_username(String::from("dma"))
+                .build(),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Person",
+  "id": "https://example.com/person/1234",
+  "name": "name",
+  "preferredUsername": "dma"
+}"#;
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn deserialize_actor() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Person",
+  "id": "https://example.com/person/1234",
+  "name": "name",
+  "preferredUsername": "dma"
+}"#;
+        let document: Document<Actor> = Document::from_json(String::from(actual)).unwrap();
+        let actor = document.object as Actor;
+        assert_eq!(actor.object_type, Some(ObjectType::from("Person")));
+        assert_eq!(
+            actor.id,
+            Some("https://example.com/person/1234".parse::<Iri>().unwrap())
+        );
+        assert_eq!(actor.name, Some(String::from("name")));
+        assert_eq!(actor.preferred_username, Some(String::from("dma")));
+    }
+
+    #[test]
+    fn serialize_note() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            Note::new(String::from("Name"), String::from("Content")),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Note",
+  "name": "Name",
+  "content": "Content"
+}"#;
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn deserialize_note() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Note",
+  "name": "Name",
+  "content": "Content"
+}"#;
+        let document: Document<Note> = Document::from_json(String::from(actual)).unwrap();
+        let actor = document.object as Note;
+        assert_eq!(actor.object_type, Some(ObjectType::from("Note")));
+        assert_eq!(actor.name, Some(String::from("Name")));
+        assert_eq!(actor.content, Some(String::from("Content")));
+    }
+
+    #[test]
+    fn serialize_follow() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            FollowBuilder::new(String::from("Sally followed John"))
+                .actor(ActorBuilder::new(String::from("Person")).name(String::from("Sally")))
+                .object(
+                    ObjectBuilder::new()
+                        .object_type(String::from("Person"))
+                        .name(String::from("John")),
+                )
+                .build(),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Follow",
+  "summary": "Sally followed John",
+  "actor": {
+    "type": "Person",
+    "name": "Sally"
+  },
+  "object": {
+    "type": "Person",
+    "name": "John"
+  }
+}"#;
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn deserialize_create() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Create",
+  "summary": "Sally created a note",
+  "actor": {
+    "type": "Person",
+    "name": "Sally"
+  },
+  "object": {
+    "type": "Note",
+    "name": "A Simple Note",
+    "content": "This is a simple note"
+  }
+}"#;
+        let document: Document<Create> = Document::from_json(String::from(actual)).unwrap();
+        let create = document.object as Create;
+        assert_eq!(create.object_type, Some(ObjectType::from(Create::TYPE)));
+        assert_eq!(create.summary, Some(String::from("Sally created a note")));
+        assert_eq!(
+            create
+                .actor
+                .as_ref()
+                .and_then(OneOrMany::first)
+                .and_then(Reference::as_object)
+                .unwrap()
+                .name,
+            Some(String::from("Sally"))
+        );
+        let object = create.object.as_ref().unwrap().first().unwrap();
+        let object = object.as_object().unwrap();
+        assert_eq!(object.object_type, Some(ObjectType::from("Note")));
+        assert_eq!(object.content, Some(String::from("This is a simple note")));
+    }
+
+    #[test]
+    fn serialize_travel() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            TravelBuilder::new(String::from("Sally went home from work"))
+                .actor(ActorBuilder::new(String::from("Person")).name(String::from("Sally")))
+                .target(
+                    ObjectBuilder::new()
+                        .object_type(String::from("Place"))
+                        .name(String::from("Home")),
+                )
+                .origin("http://example.org/places/work".parse::<Iri>().unwrap())
+                .build(),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Travel",
+  "summary": "Sally went home from work",
+  "actor": {
+    "type": "Person",
+    "name": "Sally"
+  },
+  "target": {
+    "type": "Place",
+    "name": "Home"
+  },
+  "origin": "http://example.org/places/work"
+}"#;
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn activity_types() {
+        assert_eq!(
+            AcceptBuilder::new(String::new()).build().object_type,
+            Some(ObjectType::from("Accept"))
+        );
+        assert_eq!(
+            TentativeRejectBuilder::new(String::new())
+                .build()
+                .object_type,
+            Some(ObjectType::from("TentativeReject"))
+        );
+        assert_eq!(
+            QuestionBuilder::new(String::new()).build().object_type,
+            Some(ObjectType::from("Question"))
+        );
+    }
+
+    #[test]
+    fn serialize_person() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            Person::new(
+                "https://example.com/person/1234".parse::<Iri>().unwrap(),
+                String::from("Sally"),
+            ),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Person",
+  "id": "https://example.com/person/1234",
+  "name": "Sally"
 }"#;
-        let document: Document<Note> = Document::from_json(String::from(actual)).unwrap();
-        let actor = document.object as Note;
-        assert_eq!(actor.object_type, Some(String::from("Note")));
-        assert_eq!(actor.name, Some(String::from("Name")));
-        assert_eq!(actor.content, Some(String::from("Content")));
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn deserialize_group() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Group",
+  "name": "Big Beards of Austin",
+  "inbox": "https://example.com/groups/beards/inbox"
+}"#;
+        let document: Document<Group> = Document::from_json(String::from(actual)).unwrap();
+        let group = document.object as Group;
+        assert_eq!(group.object_type, Some(ObjectType::from("Group")));
+        assert_eq!(group.name, Some(String::from("Big Beards of Austin")));
+        assert_eq!(
+            group.inbox,
+            Some(
+                "https://example.com/groups/beards/inbox"
+                    .parse::<Iri>()
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn deserialize_person_multiple_types() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": ["Person", "http://example.org/Bot"],
+  "name": "Sally"
+}"#;
+        let document: Document<Person> = Document::from_json(String::from(actual)).unwrap();
+        assert!(document.object.has_type("http://example.org/Bot"));
+        assert_eq!(document.object.name, Some(String::from("Sally")));
+    }
+
+    #[test]
+    fn round_trip_person_extensions() {
+        let person = PersonBuilder::new()
+            .id("http://example.org/sally".parse::<Iri>().unwrap())
+            .name(String::from("Sally"))
+            .extension(
+                String::from("manuallyApprovesFollowers"),
+                serde_json::json!(true),
+            )
+            .build();
+        let actual = Document::new(ContextBuilder::new().build(), person)
+            .to_json()
+            .unwrap();
+
+        let document: Document<Person> = Document::from_json(actual.clone()).unwrap();
+        assert_eq!(
+            document.object.extensions.get("manuallyApprovesFollowers"),
+            Some(&serde_json::json!(true))
+        );
+        assert_eq!(document.to_json().unwrap(), actual);
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Toot {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sensitive: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        featured: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        manually_approves_followers: Option<bool>,
+    }
+
+    impl Serde for Toot {}
+
+    impl Extension for Toot {
+        fn terms() -> Vec<(String, TermDefinition)> {
+            vec![
+                (
+                    String::from("toot"),
+                    TermDefinition::from("http://joinmastodon.org/ns#"),
+                ),
+                (
+                    String::from("sensitive"),
+                    TermDefinition::from("as:sensitive"),
+                ),
+                (
+                    String::from("featured"),
+                    ExpandedTermDefinition::new(String::from("toot:featured"))
+                        .term_type(String::from("@id"))
+                        .into(),
+                ),
+                (
+                    String::from("manuallyApprovesFollowers"),
+                    TermDefinition::from("as:manuallyApprovesFollowers"),
+                ),
+            ]
+        }
+    }
+
+    #[test]
+    fn serialize_note_typed_extension() {
+        let actual = Document::new(
+            ContextBuilder::activity_streams()
+                .extension::<Toot>()
+                .build(),
+            NoteBuilder::new()
+                .content(String::from("Spoilers ahead"))
+                .with_extension(Toot {
+                    sensitive: Some(true),
+                    ..Default::default()
+                })
+                .build(),
+        );
+        let expected = r#"{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    {
+      "featured": {
+        "@id": "toot:featured",
+        "@type": "@id"
+      },
+      "manuallyApprovesFollowers": "as:manuallyApprovesFollowers",
+      "sensitive": "as:sensitive",
+      "toot": "http://joinmastodon.org/ns#"
+    }
+  ],
+  "type": "Note",
+  "content": "Spoilers ahead",
+  "sensitive": true
+}"#;
+        assert_eq!(actual.to_json_pretty().unwrap(), expected);
+    }
+
+    #[test]
+    fn deserialize_person_typed_extension() {
+        let actual = r#"{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    {
+      "toot": "http://joinmastodon.org/ns#",
+      "featured": {
+        "@id": "toot:featured",
+        "@type": "@id"
+      },
+      "manuallyApprovesFollowers": "as:manuallyApprovesFollowers"
+    }
+  ],
+  "type": "Person",
+  "id": "https://mastodon.example/users/sally",
+  "name": "Sally",
+  "inbox": "https://mastodon.example/users/sally/inbox",
+  "featured": "https://mastodon.example/users/sally/collections/featured",
+  "manuallyApprovesFollowers": false,
+  "discoverable": true
+}"#;
+        let document: Document<Person<Toot>> = Document::from_json(String::from(actual)).unwrap();
+        let person = &document.object;
+        assert_eq!(person.name, Some(String::from("Sally")));
+        assert_eq!(
+            person.extension,
+            Toot {
+                sensitive: None,
+                featured: Some(String::from(
+                    "https://mastodon.example/users/sally/collections/featured"
+                )),
+                manually_approves_followers: Some(false),
+            }
+        );
+        // Typed members are not duplicated into the untyped extensions.
+        assert_eq!(
+            person.extensions.keys().collect::<Vec<_>>(),
+            vec!["discoverable"]
+        );
+
+        let round_trip: Document<Person<Toot>> =
+            Document::from_json(document.to_json().unwrap()).unwrap();
+        assert_eq!(round_trip.to_json().unwrap(), document.to_json().unwrap());
+    }
+
+    #[test]
+    fn with_extension_keeps_properties() {
+        let person = PersonBuilder::new()
+            .id("http://example.org/sally".parse::<Iri>().unwrap())
+            .inbox("http://example.org/sally/inbox".parse::<Iri>().unwrap())
+            .with_extension(Toot {
+                manually_approves_followers: Some(true),
+                ..Default::default()
+            })
+            .name(String::from("Sally"))
+            .build();
+        assert_eq!(
+            person.id,
+            Some("http://example.org/sally".parse::<Iri>().unwrap())
+        );
+        assert_eq!(person.name, Some(String::from("Sally")));
+        assert_eq!(
+            person.inbox,
+            Some("http://example.org/sally/inbox".parse::<Iri>().unwrap())
+        );
+        assert_eq!(person.extension.manually_approves_followers, Some(true));
+    }
+
+    #[test]
+    fn document_defines_extension_terms() {
+        let person = PersonBuilder::new()
+            .id("https://mastodon.example/users/sally"
+                .parse::<Iri>()
+                .unwrap())
+            .with_extension(MastodonActorExtension {
+                discoverable: Some(true),
+                ..Default::default()
+            })
+            .build();
+        let context = ContextBuilder::new()
+            .add_term(
+                String::from("discoverable"),
+                "http://example.org/ns#discoverable",
+            )
+            .build();
+        let document = Document::new(context, person);
+        let context = &document.context;
+        assert!(context.includes(SECURITY_CONTEXT));
+        assert_eq!(
+            context.term("featured").and_then(TermDefinition::id),
+            Some("toot:featured")
+        );
+        // Terms the context defines itself are kept.
+        assert_eq!(
+            context.term("discoverable").and_then(TermDefinition::id),
+            Some("http://example.org/ns#discoverable")
+        );
+        assert_eq!(
+            context.vocab(),
+            Some("https://www.w3.org/ns/activitystreams")
+        );
+
+        // Documents of objects without a typed extension are unchanged.
+        let note = NoteBuilder::new().build();
+        let document = Document::new(ContextBuilder::new().build(), note);
+        assert_eq!(document.context, ContextBuilder::new().build());
+    }
+
+    #[test]
+    fn round_trip_mastodon_actor() {
+        let fixture = include_str!("../tests/fixtures/mastodon/actor.json");
+        let document: Document<MastodonActor> = Document::from_json(String::from(fixture)).unwrap();
+        let actor = &document.object;
+        assert_eq!(actor.preferred_username, Some(String::from("Gargron")));
+        assert_eq!(
+            actor.public_key.as_ref().map(|key| key.id.as_str()),
+            Some("https://mastodon.social/users/Gargron#main-key")
+        );
+        assert_eq!(
+            actor.shared_inbox().map(Iri::as_str),
+            Some("https://mastodon.social/inbox")
+        );
+        assert_eq!(
+            actor.extension.featured_tags,
+            Some(
+                "https://mastodon.social/users/Gargron/collections/tags"
+                    .parse::<Iri>()
+                    .unwrap()
+            )
+        );
+        assert_eq!(actor.extension.manually_approves_followers, Some(false));
+        assert_eq!(actor.extension.discoverable, Some(true));
+        assert_eq!(
+            actor.extension.also_known_as,
+            Some(OneOrMany::One(
+                "https://tooting.ai/users/Gargron".parse::<Iri>().unwrap()
+            ))
+        );
+        let fields = actor.property_values();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name, Some(String::from("Patreon")));
+        assert!(fields[1]
+            .value
+            .as_ref()
+            .is_some_and(|value| value.contains("zeonfederated.com")));
+        assert!(actor
+            .icon
+            .as_ref()
+            .and_then(OneOrMany::first)
+            .and_then(Reference::as_object)
+            .is_some_and(|icon| icon.has_type(Image::TYPE)));
+        // Members outside the profile are still carried along.
+        assert!(actor.extensions.contains_key("devices"));
+        assert_eq!(document.validate(Mode::Strict).unwrap(), Vec::new());
+
+        let expected = crate::jsonld::normalize_for::<MastodonActor>(
+            &serde_json::from_str(fixture).unwrap(),
+            &BundledContextLoader::new(),
+        )
+        .unwrap();
+        assert_eq!(serde_json::to_value(&document).unwrap(), expected);
+    }
+
+    #[test]
+    fn round_trip_mastodon_status() {
+        let fixture = include_str!("../tests/fixtures/mastodon/status.json");
+        let document: Document<MastodonStatus> =
+            Document::from_json(String::from(fixture)).unwrap();
+        let status = &document.object;
+        assert_eq!(status.extension.sensitive, Some(false));
+        assert_eq!(
+            status.extension.conversation,
+            Some(
+                "tag:mastodon.social,2024-10-25:objectId=829015823:objectType=Conversation"
+                    .parse::<Iri>()
+                    .unwrap()
+            )
+        );
+        assert_eq!(status.extension.in_reply_to_atom_uri, None);
+        assert_eq!(status.summary, None);
+        assert!(status.content_for(&["en"]).is_some());
+        assert_eq!(document.validate(Mode::Strict).unwrap(), Vec::new());
+        assert!(matches!(status.tags()[1], Tag::Hashtag(_)));
+        assert_eq!(
+            status.mentioned_actors(),
+            vec!["https://mastodon.social/users/Mastodon"]
+        );
+        let replies = status.replies.as_ref().and_then(Reference::as_object);
+        let first = replies.and_then(|replies| replies.first.as_ref());
+        assert!(first
+            .and_then(Reference::as_object)
+            .is_some_and(|page| page.has_type("CollectionPage")));
+
+        // Normalizing also drops the `null` members, which JSON-LD treats as
+        // absent, and defines the terms of the extension missing from the
+        // context, such as `Emoji`.
+        let expected = crate::jsonld::normalize_for::<MastodonStatus>(
+            &serde_json::from_str(fixture).unwrap(),
+            &BundledContextLoader::new(),
+        )
+        .unwrap();
+        assert_eq!(serde_json::to_value(&document).unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_mastodon_context() {
+        let context = ContextBuilder::activity_streams()
+            .extension::<MastodonActorExtension>()
+            .build();
+        assert_eq!(
+            context.iris().collect::<Vec<_>>(),
+            vec![Context::ACTIVITY_STREAMS, "https://w3id.org/security/v1"]
+        );
+        assert_eq!(
+            context.term("featured").and_then(TermDefinition::id),
+            Some("toot:featured")
+        );
+
+        let fixture = include_str!("../tests/fixtures/mastodon/actor.json");
+        let document: Document<MastodonActor> = Document::from_json(String::from(fixture)).unwrap();
+        let actor = Document::new(context, document.object);
+        let expanded = crate::jsonld::expand(&serde_json::to_value(&actor).unwrap()).unwrap();
+        assert_eq!(
+            expanded[0]["http://joinmastodon.org/ns#featured"][0]["@id"],
+            "https://mastodon.social/users/Gargron/collections/featured"
+        );
+    }
+
+    #[test]
+    fn serialize_actor_endpoints() {
+        let actual = Document::new(
+            ContextBuilder::activity_streams()
+                .add_context("https://w3id.org/security/v1".parse::<Iri>().unwrap())
+                .build(),
+            PersonBuilder::new()
+                .id("https://example.com/sally".parse::<Iri>().unwrap())
+                .inbox("https://example.com/sally/inbox".parse::<Iri>().unwrap())
+                .outbox("https://example.com/sally/outbox".parse::<Iri>().unwrap())
+                .add_stream("https://example.com/sally/photos".parse::<Iri>().unwrap())
+                .shared_inbox("https://example.com/inbox".parse::<Iri>().unwrap())
+                .oauth_token_endpoint("https://example.com/oauth/token".parse::<Iri>().unwrap())
+                .public_key(
+                    "https://example.com/sally#main-key".parse::<Iri>().unwrap(),
+                    "https://example.com/sally".parse::<Iri>().unwrap(),
+                    String::from("-----BEGIN PUBLIC KEY-----\n...\n-----END PUBLIC KEY-----\n"),
+                )
+                .build(),
+        );
+        let expected = r#"{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    "https://w3id.org/security/v1"
+  ],
+  "type": "Person",
+  "id": "https://example.com/sally",
+  "inbox": "https://example.com/sally/inbox",
+  "outbox": "https://example.com/sally/outbox",
+  "streams": "https://example.com/sally/photos",
+  "endpoints": {
+    "oauthTokenEndpoint": "https://example.com/oauth/token",
+    "sharedInbox": "https://example.com/inbox"
+  },
+  "publicKey": {
+    "id": "https://example.com/sally#main-key",
+    "owner": "https://example.com/sally",
+    "publicKeyPem": "-----BEGIN PUBLIC KEY-----\n...\n-----END PUBLIC KEY-----\n"
+  }
+}"#;
+        assert_eq!(actual.to_json_pretty().unwrap(), expected);
+        assert_eq!(
+            actual.object.shared_inbox().map(Iri::as_str),
+            Some("https://example.com/inbox")
+        );
+        assert!(actual.validate(Mode::Strict).unwrap().is_empty());
+    }
+
+    #[test]
+    fn round_trip_tags() {
+        let note = NoteBuilder::new()
+            .content(String::from("Hi @sally, #rust :ferris:"))
+            .add_tag(
+                MentionBuilder::new("https://example.com/sally".parse::<Iri>().unwrap())
+                    .name(String::from("@sally")),
+            )
+            .add_tag(
+                HashtagBuilder::new("https://example.com/tags/rust".parse::<Iri>().unwrap())
+                    .name(String::from("#rust")),
+            )
+            .add_tag(
+                EmojiBuilder::new()
+                    .id("https://example.com/emojis/1".parse::<Iri>().unwrap())
+                    .name(String::from(":ferris:"))
+                    .icon(
+                        ImageBuilder::new()
+                            .media_type(String::from("image/png"))
+                            .url("https://example.com/ferris.png".parse::<Iri>().unwrap())
+                            .build()
+                            .base,
+                    ),
+            )
+            .build();
+        let actual = Document::new(ContextBuilder::new().build(), note);
+        let expected = r##"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Note",
+  "content": "Hi @sally, #rust :ferris:",
+  "tag": [
+    {
+      "type": "Mention",
+      "href": "https://example.com/sally",
+      "name": "@sally"
+    },
+    {
+      "type": "Hashtag",
+      "href": "https://example.com/tags/rust",
+      "name": "#rust"
+    },
+    {
+      "type": "Emoji",
+      "id": "https://example.com/emojis/1",
+      "name": ":ferris:",
+      "icon": {
+        "type": "Image",
+        "url": "https://example.com/ferris.png",
+        "mediaType": "image/png"
+      }
+    }
+  ]
+}"##;
+        assert_eq!(actual.to_json_pretty().unwrap(), expected);
+
+        let document: Document<Note> = Document::from_json(String::from(expected)).unwrap();
+        let tags = document.object.tags();
+        assert!(
+            matches!(&tags[0], Tag::Mention(mention) if mention.href.href == "https://example.com/sally")
+        );
+        assert!(
+            matches!(&tags[1], Tag::Hashtag(hashtag) if hashtag.name.as_deref() == Some("#rust"))
+        );
+        assert!(matches!(&tags[2], Tag::Emoji(emoji) if emoji.icon.is_some()));
+        assert_eq!(
+            document.object.mentioned_actors(),
+            vec!["https://example.com/sally"]
+        );
+    }
+
+    #[test]
+    fn deserialize_tag_kinds() {
+        let tags: Vec<Tag> = serde_json::from_str(
+            r#"[
+  "https://example.com/tags/1",
+  {"type": "Link", "href": "https://example.com/"},
+  {"type": "Mention", "href": "https://example.com/sally"},
+  {"type": "Place", "name": "Fresno Area"}
+]"#,
+        )
+        .unwrap();
+        assert!(matches!(&tags[0], Tag::Iri(iri) if iri == "https://example.com/tags/1"));
+        assert!(matches!(&tags[1], Tag::Link(_)));
+        assert!(matches!(&tags[2], Tag::Mention(_)));
+        assert!(matches!(&tags[3], Tag::Object(object) if object.has_type(Place::TYPE)));
+
+        let link = Link::from(
+            MentionBuilder::new("https://example.com/sally".parse::<Iri>().unwrap()).build(),
+        );
+        assert!(Hashtag::try_from(link).is_err());
+    }
+
+    #[test]
+    fn serialize_question() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            QuestionBuilder::new(String::from("A poll"))
+                .name(String::from("What is the answer?"))
+                .add_one_of(
+                    ObjectBuilder::new()
+                        .object_type(String::from("Note"))
+                        .name(String::from("Option A")),
+                )
+                .add_one_of(
+                    ObjectBuilder::new()
+                        .object_type(String::from("Note"))
+                        .name(String::from("Option B")),
+                )
+                .closed(Utc.with_ymd_and_hms(2016, 5, 10, 0, 0, 0).unwrap())
+                .build(),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Question",
+  "name": "What is the answer?",
+  "summary": "A poll",
+  "oneOf": [
+    {
+      "type": "Note",
+      "name": "Option A"
+    },
+    {
+      "type": "Note",
+      "name": "Option B"
+    }
+  ],
+  "closed": "2016-05-10T00:00:00Z"
+}"#;
+        assert_eq!(actual.to_json_pretty().unwrap(), expected);
+    }
+
+    #[test]
+    fn deserialize_closed_question() {
+        let actual = r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Question",
+  "name": "What is the answer?",
+  "anyOf": [
+    {
+      "type": "Note",
+      "name": "Option A"
+    },
+    {
+      "type": "Note",
+      "name": "Option B"
+    }
+  ],
+  "closed": true,
+  "votersCount": 3
+}"#;
+        let document: Document<Question> = Document::from_json(String::from(actual)).unwrap();
+        let question = document.object;
+        assert!(question.is_multiple_choice());
+        assert_eq!(question.options(), vec!["Option A", "Option B"]);
+        assert_eq!(question.closed, Some(Closed::Bool(true)));
+        assert!(question.is_closed(Utc::now()));
+        assert_eq!(question.voters_count, Some(3));
+    }
+
+    fn vote(actor: &str, question: &str, option: &str, published: DateTime<Utc>) -> Create {
+        CreateBuilder::new(String::new())
+            .actor(actor.parse::<Iri>().unwrap())
+            .published(published)
+            .object(
+                ObjectBuilder::new()
+                    .object_type(String::from("Note"))
+                    .name(option.to_string())
+                    .in_reply_to(question.parse::<Iri>().unwrap()),
+            )
+            .build()
+    }
+
+    #[test]
+    fn tally_question() {
+        let option = |name: &str| {
+            ObjectBuilder::new()
+                .object_type(String::from("Note"))
+                .name(name.to_string())
+        };
+        let question = "https://example.com/questions/1";
+        let mut one_of = QuestionBuilder::new(String::new())
+            .id(question.parse::<Iri>().unwrap())
+            .add_one_of(option("Yes"))
+            .add_one_of(option("No"))
+            .closed(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap())
+            .build();
+        let before = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap();
+        let replies = [
+            vote("https://example.com/sally", question, "Yes", before),
+            vote("https://example.com/john", question, "No", before),
+            // Only the first vote of an actor counts.
+            vote("https://example.com/sally", question, "No", before),
+            vote("https://example.com/alice", question, "Maybe", before),
+            vote(
+                "https://example.com/bob",
+                "https://example.com/questions/2",
+                "Yes",
+                before,
+            ),
+            vote("https://example.com/carol", question, "Yes", after),
+        ];
+
+        let tally = one_of.tally(&replies);
+        assert_eq!(
+            tally,
+            Tally {
+                votes: vec![(String::from("Yes"), 1), (String::from("No"), 1)],
+                voters_count: 2,
+            }
+        );
+
+        one_of.apply_tally(&tally);
+        assert_eq!(one_of.voters_count, Some(2));
+        let json = serde_json::to_value(&one_of).unwrap();
+        assert_eq!(json["oneOf"][0]["replies"]["totalItems"], 1);
+        assert_eq!(json["oneOf"][1]["replies"]["totalItems"], 1);
+
+        let any_of = QuestionBuilder::new(String::new())
+            .id(question.parse::<Iri>().unwrap())
+            .add_any_of(option("Yes"))
+            .add_any_of(option("No"))
+            .build();
+        assert_eq!(
+            any_of.tally(&replies),
+            Tally {
+                votes: vec![(String::from("Yes"), 2), (String::from("No"), 2)],
+                voters_count: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_wrong_actor_type() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Group",
+  "name": "Big Beards of Austin"
+}"#;
+        let result: crate::Result<Document<Person>> = Document::from_json(String::from(actual));
+        match result {
+            Err(Error::UnexpectedType {
+                path,
+                expected,
+                found,
+            }) => {
+                assert_eq!(path, "$");
+                assert_eq!(expected, "Person");
+                assert_eq!(found.as_deref(), Some("Group"));
+            }
+            other => panic!("expected an unexpected type error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_wrong_object_type() {
+        fn unexpected<T: Serde>(actual: &str) -> (String, Option<String>) {
+            match Document::<T>::from_json(String::from(actual)) {
+                Err(Error::UnexpectedType {
+                    path,
+                    expected,
+                    found,
+                }) => {
+                    assert_eq!(path, "$");
+                    (expected, found)
+                }
+                other => panic!("expected an unexpected type error, got {:?}", other.err()),
+            }
+        }
+
+        let follow = r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Follow",
+  "actor": "https://example.com/sally",
+  "object": "https://example.com/john"
+}"#;
+        assert_eq!(
+            unexpected::<Create>(follow),
+            (String::from("Create"), Some(String::from("Follow")))
+        );
+        let note = r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Note",
+  "content": "Which?"
+}"#;
+        assert_eq!(
+            unexpected::<Question>(note),
+            (String::from("Question"), Some(String::from("Note")))
+        );
+        assert_eq!(
+            unexpected::<Article>(note),
+            (String::from("Article"), Some(String::from("Note")))
+        );
+        let untyped = r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "content": "Hello"
+}"#;
+        assert_eq!(unexpected::<Note>(untyped), (String::from("Note"), None));
+    }
+
+    #[test]
+    fn deserialize_wrong_item_type() {
+        let actual = r#"{
+  "@context": "https://www.w3.org/ns/activitystreams",
+  "type": "Collection",
+  "items": [
+    "https://example.com/notes/1",
+    {
+      "type": "Article",
+      "name": "Not a note"
+    }
+  ]
+}"#;
+        let result: crate::Result<Document<Collection<Reference<Note>>>> =
+            Document::from_json(String::from(actual));
+        match result {
+            Err(Error::UnexpectedType {
+                path,
+                expected,
+                found,
+            }) => {
+                assert_eq!(path, "$.items[1]");
+                assert_eq!(expected, "Note");
+                assert_eq!(found.as_deref(), Some("Article"));
+            }
+            other => panic!("expected an unexpected type error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_actor_kind() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Service",
+  "name": "Acme Web Service"
+}"#;
+        let document: Document<ActorKind> = Document::from_json(String::from(actual)).unwrap();
+        match document.object {
+            ActorKind::Service(service) => {
+                assert_eq!(service.name, Some(String::from("Acme Web Service")))
+            }
+            other => panic!("unexpected actor kind {:?}", other),
+        }
+
+        let actor = ActorBuilder::new(String::from("http://example.org/Robot")).build();
+        assert!(matches!(actor.into_kind(), ActorKind::Other(_)));
+    }
+
+    #[test]
+    fn serialize_place() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            PlaceBuilder::new()
+                .name(String::from("Fresno Area"))
+                .latitude(36.75)
+                .longitude(119.7667)
+                .radius(15.0)
+                .units(String::from("miles"))
+                .build(),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Place",
+  "name": "Fresno Area",
+  "latitude": 36.75,
+  "longitude": 119.7667,
+  "radius": 15.0,
+  "units": "miles"
+}"#;
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn serialize_tombstone() {
+        let actual = Document::new(
+            ContextBuilder::new().build(),
+            TombstoneBuilder::new()
+                .url("http://image.example/2".parse::<Iri>().unwrap())
+                .former_type(String::from(Image::TYPE))
+                .deleted(Utc.with_ymd_and_hms(2016, 3, 17, 0, 0, 0).unwrap())
+                .build(),
+        );
+        let expected = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Tombstone",
+  "url": "http://image.example/2",
+  "formerType": "Image",
+  "deleted": "2016-03-17T00:00:00Z"
+}"#;
+        assert!(actual.to_json_pretty().is_ok());
+        assert_eq!(actual.to_json_pretty().unwrap(), expected)
+    }
+
+    #[test]
+    fn deserialize_article() {
+        let actual = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Article",
+  "name": "What a Crazy Day I Had",
+  "content": "<div>... you will never believe ...</div>"
+}"#;
+        let document: Document<Article> = Document::from_json(String::from(actual)).unwrap();
+        let article = document.object as Article;
+        assert_eq!(article.object_type, Some(ObjectType::from("Article")));
+        assert_eq!(article.name, Some(String::from("What a Crazy Day I Had")));
+        assert_eq!(
+            article.content,
+            Some(String::from("<div>... you will never believe ...</div>"))
+        );
     }
 }
@@ -0,0 +1,775 @@
+//! Server-to-server interactions: applying the side effects of an activity
+//! delivered to an actor's inbox.
+//! <https://www.w3.org/TR/activitypub/#server-to-server-interactions>
+
+use crate::core::{Activity, ActivityBuilder, Document};
+use crate::extended::{
+    Accept, Actor, Add, Announce, Block, Create, Delete, Follow, Like, Reject, Remove, Undo, Update,
+};
+use crate::iri::Iri;
+use crate::store::{
+    actor_of, embedded_objects, objects_of, required, Change, ObjectStore, Recorder,
+};
+use crate::validate::{Mode, Validate};
+use crate::{Error, Result, Serde};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// What receiving an activity led to.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// Activities the receiving actor sends in response, such as the
+    /// `Accept` of a `Follow`. They have no `id` yet and are to be delivered
+    /// like any other activity of the actor.
+    pub activities: Vec<Activity>,
+    /// The changes made to the store, in the order they were made.
+    pub changes: Vec<Change>,
+}
+
+/// The activity types [receive] has side effects for.
+const HANDLED: [&str; 12] = [
+    Create::TYPE,
+    Update::TYPE,
+    Delete::TYPE,
+    Follow::TYPE,
+    Accept::TYPE,
+    Reject::TYPE,
+    Add::TYPE,
+    Remove::TYPE,
+    Like::TYPE,
+    Announce::TYPE,
+    Undo::TYPE,
+    Block::TYPE,
+];
+
+/// Applies the side effects of `document`, an activity delivered to the
+/// inbox of `actor`, to `store`. `now` is the time of the delivery.
+///
+/// The activity is stored and added to the actor's `inbox`, unless it is
+/// already there, in which case nothing is done. Objects that the activity
+/// only refers to by IRI are not fetched. The activity, and the objects (or
+/// target collections) a `Create`, `Update`, `Delete`, `Add` or `Remove`
+/// modifies, must be of the same origin as its actor, and an `Undo` may only
+/// undo activities of its actor, otherwise [Error::Unauthorized] is returned
+/// and the store is left untouched, as it is when `actor` lacks the
+/// `followers` or `following` collection the activity changes. A `Follow` of
+/// `actor` is accepted straight away, unless `actor` has
+/// `manuallyApprovesFollowers` set. An `Accept` or `Reject` only counts for a
+/// `Follow` of `actor` found in the store.
+pub fn receive<S, E>(
+    store: &mut S,
+    actor: &Actor<E>,
+    document: &Document<Activity>,
+    now: DateTime<Utc>,
+) -> Result<Outcome>
+where
+    S: ObjectStore,
+    E: Serde,
+{
+    let activity = &document.object;
+    activity.check(Mode::Lenient)?;
+    let id = required(activity.id.as_ref(), "id")?;
+    let sender = required(actor_of(activity), "actor")?;
+    let inbox = required(actor.inbox.as_ref(), "inbox")?;
+    if store.contains_item(inbox, id)? {
+        return Ok(Outcome::default());
+    }
+
+    let mut receiver = Receiver {
+        recorder: Recorder::new(store, &document.context),
+        receiver: required(actor.id.as_ref(), "id")?,
+        followers: actor.followers.as_ref(),
+        following: actor.following.as_ref(),
+        manually_approves_followers: manually_approves_followers(actor)?,
+        activity,
+        id,
+        sender,
+        activities: Vec::new(),
+    };
+    let activity_type = activity
+        .object_type
+        .iter()
+        .flat_map(|t| t.iter())
+        .find(|object_type| HANDLED.contains(object_type));
+    // Nothing is written before the whole activity is authorized, so that a
+    // rejected activity leaves no trace and can't be replayed later.
+    receiver.authorize(id)?;
+    if let Some(activity_type) = activity_type {
+        receiver.authorize_effects(activity_type)?;
+    }
+    receiver.recorder.put(id, activity)?;
+    receiver.recorder.add(inbox, id)?;
+
+    match activity_type {
+        Some(Create::TYPE | Update::TYPE) => receiver.create_or_update()?,
+        Some(Delete::TYPE) => receiver.recorder.delete_objects(activity, now)?,
+        Some(Follow::TYPE) => receiver.follow()?,
+        Some(Accept::TYPE) => receiver.respond_to_follow(true)?,
+        Some(Reject::TYPE) => receiver.respond_to_follow(false)?,
+        Some(Add::TYPE) => receiver.recorder.add_to_target(activity, true)?,
+        Some(Remove::TYPE) => receiver.recorder.add_to_target(activity, false)?,
+        Some(Like::TYPE) => receiver.react("likes")?,
+        Some(Announce::TYPE) => receiver.react("shares")?,
+        Some(Undo::TYPE) => receiver.undo()?,
+        Some(Block::TYPE) => receiver.block()?,
+        _ => {}
+    }
+    Ok(Outcome {
+        activities: receiver.activities,
+        changes: receiver.recorder.changes,
+    })
+}
+
+fn manually_approves_followers<E: Serde>(actor: &Actor<E>) -> Result<bool> {
+    let actor = serde_json::to_value(actor).map_err(Error::Serialize)?;
+    Ok(actor
+        .get("manuallyApprovesFollowers")
+        .and_then(Value::as_bool)
+        .unwrap_or(false))
+}
+
+/// The state of receiving one activity.
+struct Receiver<'a, S> {
+    recorder: Recorder<'a, S>,
+    receiver: &'a Iri,
+    followers: Option<&'a Iri>,
+    following: Option<&'a Iri>,
+    manually_approves_followers: bool,
+    activity: &'a Activity,
+    id: &'a Iri,
+    sender: &'a Iri,
+    activities: Vec<Activity>,
+}
+
+impl<S: ObjectStore> Receiver<'_, S> {
+    /// Fails unless the sender may modify `id`, i.e. shares its origin.
+    fn authorize(&self, id: &Iri) -> Result<()> {
+        if self.sender.same_origin(id) {
+            Ok(())
+        } else {
+            Err(Error::Unauthorized(format!(
+                "{} may not modify {}",
+                self.sender, id
+            )))
+        }
+    }
+
+    /// Fails unless the sender may have the side effects of an activity of
+    /// type `activity_type`.
+    fn authorize_effects(&self, activity_type: &str) -> Result<()> {
+        match activity_type {
+            Create::TYPE | Update::TYPE => {
+                for (id, _) in embedded_objects(self.activity) {
+                    self.authorize(id)?;
+                }
+            }
+            Delete::TYPE => {
+                for id in objects_of(self.activity) {
+                    self.authorize(id)?;
+                }
+            }
+            Add::TYPE | Remove::TYPE => {
+                let targets = self.activity.target.iter().flatten();
+                for target in targets.filter_map(|target| target.id()) {
+                    self.authorize(target)?;
+                }
+            }
+            Follow::TYPE if self.follows_receiver() && !self.manually_approves_followers => {
+                required(self.followers, "followers")?;
+            }
+            Accept::TYPE | Reject::TYPE if !self.answered_follows()?.is_empty() => {
+                required(self.following, "following")?;
+            }
+            Undo::TYPE => {
+                for undone in self.recorder.object_activities(self.activity)? {
+                    if actor_of(&undone) != Some(self.sender) {
+                        return Err(Error::Unauthorized(format!(
+                            "{} may not undo an activity of another actor",
+                            self.sender
+                        )));
+                    }
+                    // An activity embedded in the Undo may claim any id.
+                    if let Some(id) = undone.id.as_ref() {
+                        self.authorize(id)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Stores the objects created, or replaces the stored copies of the
+    /// objects updated.
+    fn create_or_update(&mut self) -> Result<()> {
+        for (id, object) in embedded_objects(self.activity) {
+            self.recorder.put(id, object)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the sender to the followers of the receiver and accepts, or
+    /// leaves the request pending.
+    fn follow(&mut self) -> Result<()> {
+        if !self.follows_receiver() {
+            return Ok(());
+        }
+        if self.manually_approves_followers {
+            self.recorder.changes.push(Change::FollowRequested {
+                follow: self.id.clone(),
+                follower: self.sender.clone(),
+            });
+            return Ok(());
+        }
+        let followers = required(self.followers, "followers")?;
+        self.recorder.add(followers, self.sender)?;
+        let accept = ActivityBuilder::new(
+            Accept::TYPE.to_string(),
+            format!(
+                "{} accepted the follow request of {}",
+                self.receiver, self.sender
+            ),
+        )
+        .actor(self.receiver.clone())
+        .object(self.id.clone())
+        .add_to(self.sender.clone())
+        .build();
+        self.activities.push(accept);
+        Ok(())
+    }
+
+    /// Whether the activity is a `Follow` of the receiver.
+    fn follows_receiver(&self) -> bool {
+        objects_of(self.activity).any(|id| id == self.receiver)
+    }
+
+    /// The `Follow`s of the sender by the receiver the activity answers.
+    /// Only stored copies count, as one embedded in the answer may be forged.
+    fn answered_follows(&self) -> Result<Vec<&Iri>> {
+        let mut follows = Vec::new();
+        for id in objects_of(self.activity) {
+            if !self.receiver.same_origin(id) {
+                continue;
+            }
+            let Some(follow) = self.recorder.store.get_document::<Activity>(id)? else {
+                continue;
+            };
+            let follow = follow.object;
+            if follow.has_type(Follow::TYPE)
+                && actor_of(&follow) == Some(self.receiver)
+                && objects_of(&follow).any(|id| id == self.sender)
+            {
+                follows.push(id);
+            }
+        }
+        Ok(follows)
+    }
+
+    /// Adds the sender to the actors the receiver follows if it accepted the
+    /// receiver's `Follow`, or removes it if it rejected it.
+    fn respond_to_follow(&mut self, accepted: bool) -> Result<()> {
+        if self.answered_follows()?.is_empty() {
+            return Ok(());
+        }
+        let following = required(self.following, "following")?;
+        if accepted {
+            self.recorder.add(following, self.sender)?;
+        } else {
+            self.recorder.remove(following, self.sender)?;
+        }
+        Ok(())
+    }
+
+    /// Adds a `Like` or `Announce` to the `likes` or `shares` collection of
+    /// the stored objects it is about, if they have one.
+    fn react(&mut self, property: &str) -> Result<()> {
+        for object in objects_of(self.activity) {
+            if let Some(collection) = self.recorder.collection_of(object, property)? {
+                self.recorder.add(&collection, self.id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reverts the side effects of the sender's activities undone.
+    fn undo(&mut self) -> Result<()> {
+        for undone in self.recorder.object_activities(self.activity)? {
+            if undone.has_type(Follow::TYPE) {
+                if let Some(followers) = self.followers {
+                    if objects_of(&undone).any(|id| id == self.receiver) {
+                        self.recorder.remove(followers, self.sender)?;
+                    }
+                }
+                continue;
+            }
+            let property = if undone.has_type(Like::TYPE) {
+                "likes"
+            } else if undone.has_type(Announce::TYPE) {
+                "shares"
+            } else {
+                continue;
+            };
+            let Some(id) = undone.id.as_ref() else {
+                continue;
+            };
+            for object in objects_of(&undone) {
+                if let Some(collection) = self.recorder.collection_of(object, property)? {
+                    self.recorder.remove(&collection, id)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ends the follow relationships between the sender and the receiver it
+    /// blocks.
+    fn block(&mut self) -> Result<()> {
+        if !objects_of(self.activity).any(|id| id == self.receiver) {
+            return Ok(());
+        }
+        for collection in [self.followers, self.following].into_iter().flatten() {
+            self.recorder.remove(collection, self.sender)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Null, Object};
+    use crate::extended::{ActorBuilder, MastodonActorExtension};
+    use crate::store::MemoryStore;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    fn iri(iri: &str) -> Iri {
+        iri.parse().unwrap()
+    }
+
+    fn sally() -> Actor {
+        ActorBuilder::new(String::from("Person"))
+            .id(iri("https://example.com/sally"))
+            .inbox(iri("https://example.com/sally/inbox"))
+            .followers(iri("https://example.com/sally/followers"))
+            .following(iri("https://example.com/sally/following"))
+            .build()
+    }
+
+    fn activity(json: &str) -> Document<Activity> {
+        Document::from_json(String::from(json)).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    const FOLLOW: &str = r#"{
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "type": "Follow",
+        "id": "https://social.example/john/follows/1",
+        "actor": "https://social.example/john",
+        "object": "https://example.com/sally"
+    }"#;
+
+    #[test]
+    fn accept_follow() {
+        let mut store = MemoryStore::new();
+        let outcome = receive(&mut store, &sally(), &activity(FOLLOW), now()).unwrap();
+        assert_eq!(
+            outcome.changes,
+            vec![
+                Change::Stored(iri("https://social.example/john/follows/1")),
+                Change::Added {
+                    collection: iri("https://example.com/sally/inbox"),
+                    item: iri("https://social.example/john/follows/1"),
+                },
+                Change::Added {
+                    collection: iri("https://example.com/sally/followers"),
+                    item: iri("https://social.example/john"),
+                },
+            ]
+        );
+        let accept = &outcome.activities[0];
+        assert!(accept.has_type(Accept::TYPE));
+        assert_eq!(actor_of(accept), Some(&iri("https://example.com/sally")));
+        assert_eq!(
+            objects_of(accept).collect::<Vec<_>>(),
+            vec![&iri("https://social.example/john/follows/1")]
+        );
+
+        // Deliveries of an activity already received are ignored.
+        let outcome = receive(&mut store, &sally(), &activity(FOLLOW), now()).unwrap();
+        assert!(outcome.changes.is_empty() && outcome.activities.is_empty());
+    }
+
+    #[test]
+    fn follow_awaiting_approval() {
+        let mut store = MemoryStore::new();
+        let sally = ActorBuilder::new(String::from("Person"))
+            .with_extension(MastodonActorExtension {
+                manually_approves_followers: Some(true),
+                ..Default::default()
+            })
+            .id(iri("https://example.com/sally"))
+            .inbox(iri("https://example.com/sally/inbox"))
+            .followers(iri("https://example.com/sally/followers"))
+            .build();
+        let outcome = receive(&mut store, &sally, &activity(FOLLOW), now()).unwrap();
+        assert!(outcome.activities.is_empty());
+        assert_eq!(
+            outcome.changes.last(),
+            Some(&Change::FollowRequested {
+                follow: iri("https://social.example/john/follows/1"),
+                follower: iri("https://social.example/john"),
+            })
+        );
+        assert!(store
+            .items(&iri("https://example.com/sally/followers"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn undo_follow() {
+        let mut store = MemoryStore::new();
+        receive(&mut store, &sally(), &activity(FOLLOW), now()).unwrap();
+        let undo = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Undo",
+                "id": "https://social.example/john/undos/1",
+                "actor": "https://social.example/john",
+                "object": "https://social.example/john/follows/1"
+            }"#,
+        );
+        let outcome = receive(&mut store, &sally(), &undo, now()).unwrap();
+        assert_eq!(
+            outcome.changes.last(),
+            Some(&Change::Removed {
+                collection: iri("https://example.com/sally/followers"),
+                item: iri("https://social.example/john"),
+            })
+        );
+
+        let forged = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Undo",
+                "id": "https://evil.example/undos/1",
+                "actor": "https://evil.example/mallory",
+                "object": "https://social.example/john/follows/1"
+            }"#,
+        );
+        let error = receive(&mut store, &sally(), &forged, now()).unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(_)));
+    }
+
+    #[test]
+    fn accept_own_follow() {
+        let mut store = MemoryStore::new();
+        let follow = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Follow",
+                "id": "https://example.com/sally/follows/1",
+                "actor": "https://example.com/sally",
+                "object": "https://social.example/john"
+            }"#,
+        );
+        store.put_document(&follow).unwrap();
+        let accept = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Accept",
+                "id": "https://social.example/john/accepts/1",
+                "actor": "https://social.example/john",
+                "object": {
+                    "type": "Follow",
+                    "id": "https://example.com/sally/follows/1",
+                    "actor": "https://example.com/sally",
+                    "object": "https://social.example/john"
+                }
+            }"#,
+        );
+        receive(&mut store, &sally(), &accept, now()).unwrap();
+        assert_eq!(
+            store
+                .items(&iri("https://example.com/sally/following"))
+                .unwrap(),
+            vec![iri("https://social.example/john")]
+        );
+    }
+
+    #[test]
+    fn ignore_accept_of_follow_never_sent() {
+        let mut store = MemoryStore::new();
+        let accept = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Accept",
+                "id": "https://evil.example/accepts/1",
+                "actor": "https://evil.example/mallory",
+                "object": {
+                    "type": "Follow",
+                    "id": "https://example.com/sally/follows/2",
+                    "actor": "https://example.com/sally",
+                    "object": "https://evil.example/mallory"
+                }
+            }"#,
+        );
+        receive(&mut store, &sally(), &accept, now()).unwrap();
+        assert!(store
+            .items(&iri("https://example.com/sally/following"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn reject_follow_of_actor_without_collections() {
+        let mut store = MemoryStore::new();
+        let sally = ActorBuilder::new(String::from("Person"))
+            .id(iri("https://example.com/sally"))
+            .inbox(iri("https://example.com/sally/inbox"))
+            .build();
+        let inbox = iri("https://example.com/sally/inbox");
+        let follow = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Follow",
+                "id": "https://social.example/john/follows/1",
+                "actor": "https://social.example/john",
+                "object": "https://example.com/sally"
+            }"#,
+        );
+        let error = receive(&mut store, &sally, &follow, now()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid document: $: missing property followers"
+        );
+        assert_eq!(store.count_items(&inbox).unwrap(), 0);
+        assert!(store
+            .get(&iri("https://social.example/john/follows/1"))
+            .unwrap()
+            .is_none());
+
+        store
+            .put_document(&activity(
+                r#"{
+                    "@context": "https://www.w3.org/ns/activitystreams",
+                    "type": "Follow",
+                    "id": "https://example.com/sally/follows/1",
+                    "actor": "https://example.com/sally",
+                    "object": "https://social.example/john"
+                }"#,
+            ))
+            .unwrap();
+        let accept = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Accept",
+                "id": "https://social.example/john/accepts/1",
+                "actor": "https://social.example/john",
+                "object": "https://example.com/sally/follows/1"
+            }"#,
+        );
+        let error = receive(&mut store, &sally, &accept, now()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid document: $: missing property following"
+        );
+        assert_eq!(store.count_items(&inbox).unwrap(), 0);
+    }
+
+    #[test]
+    fn forged_activities_leave_store_untouched() {
+        let mut store = MemoryStore::new();
+        let note = iri("https://example.com/sally/notes/1");
+        let stored = serde_json::json!({
+            "type": "Note",
+            "id": note.as_str(),
+            "content": "Hello"
+        });
+        store.put(&note, stored.clone()).unwrap();
+        let inbox = iri("https://example.com/sally/inbox");
+
+        let forgeries = [
+            // A Like claiming the id of the note it is about.
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Like",
+                "id": "https://example.com/sally/notes/1",
+                "actor": "https://evil.example/mallory",
+                "object": "https://example.com/sally/notes/1"
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Delete",
+                "id": "https://evil.example/deletes/1",
+                "actor": "https://evil.example/mallory",
+                "object": "https://example.com/sally/notes/1"
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "id": "https://evil.example/updates/1",
+                "actor": "https://evil.example/mallory",
+                "object": {
+                    "type": "Note",
+                    "id": "https://example.com/sally/notes/1",
+                    "content": "Goodbye"
+                }
+            }"#,
+        ];
+        for forged in forgeries {
+            // Retrying doesn't get a forged activity through either.
+            for _ in 0..2 {
+                let error = receive(&mut store, &sally(), &activity(forged), now()).unwrap_err();
+                assert!(matches!(error, Error::Unauthorized(_)));
+            }
+        }
+        assert_eq!(store.get(&note).unwrap(), Some(stored));
+        assert_eq!(store.count_items(&inbox).unwrap(), 0);
+        assert!(store
+            .get(&iri("https://evil.example/deletes/1"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn create_update_and_delete() {
+        let mut store = MemoryStore::new();
+        let note = iri("https://social.example/john/notes/1");
+        let create = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Create",
+                "id": "https://social.example/john/creates/1",
+                "actor": "https://social.example/john",
+                "object": {
+                    "type": "Note",
+                    "id": "https://social.example/john/notes/1",
+                    "content": "Hello",
+                    "likes": "https://social.example/john/notes/1/likes"
+                }
+            }"#,
+        );
+        let outcome = receive(&mut store, &sally(), &create, now()).unwrap();
+        assert_eq!(outcome.changes.last(), Some(&Change::Stored(note.clone())));
+        let stored: Document<Object<Null>> = store.get_document(&note).unwrap().unwrap();
+        assert_eq!(stored.object.content, Some(String::from("Hello")));
+
+        let update = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "id": "https://social.example/john/updates/1",
+                "actor": "https://social.example/john",
+                "object": {
+                    "type": "Note",
+                    "id": "https://social.example/john/notes/1",
+                    "content": "Hello, world"
+                }
+            }"#,
+        );
+        let outcome = receive(&mut store, &sally(), &update, now()).unwrap();
+        assert_eq!(outcome.changes.last(), Some(&Change::Updated(note.clone())));
+
+        let forged = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Delete",
+                "id": "https://evil.example/deletes/1",
+                "actor": "https://evil.example/mallory",
+                "object": "https://social.example/john/notes/1"
+            }"#,
+        );
+        let error = receive(&mut store, &sally(), &forged, now()).unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(_)));
+
+        let delete = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Delete",
+                "id": "https://social.example/john/deletes/1",
+                "actor": "https://social.example/john",
+                "object": "https://social.example/john/notes/1"
+            }"#,
+        );
+        let outcome = receive(&mut store, &sally(), &delete, now()).unwrap();
+        assert_eq!(outcome.changes.last(), Some(&Change::Deleted(note.clone())));
+        let tombstone = store.get(&note).unwrap().unwrap();
+        assert_eq!(tombstone["type"], "Tombstone");
+        assert_eq!(tombstone["formerType"], "Note");
+    }
+
+    #[test]
+    fn like_and_undo_like() {
+        let mut store = MemoryStore::new();
+        let likes = iri("https://example.com/sally/notes/1/likes");
+        store
+            .put(
+                &iri("https://example.com/sally/notes/1"),
+                serde_json::json!({
+                    "type": "Note",
+                    "id": "https://example.com/sally/notes/1",
+                    "likes": { "type": "Collection", "id": likes.as_str() }
+                }),
+            )
+            .unwrap();
+        let like = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Like",
+                "id": "https://social.example/john/likes/1",
+                "actor": "https://social.example/john",
+                "object": "https://example.com/sally/notes/1"
+            }"#,
+        );
+        receive(&mut store, &sally(), &like, now()).unwrap();
+        assert_eq!(
+            store.items(&likes).unwrap(),
+            vec![iri("https://social.example/john/likes/1")]
+        );
+
+        let undo = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Undo",
+                "id": "https://social.example/john/undos/2",
+                "actor": "https://social.example/john",
+                "object": {
+                    "type": "Like",
+                    "id": "https://social.example/john/likes/1",
+                    "actor": "https://social.example/john",
+                    "object": "https://example.com/sally/notes/1"
+                }
+            }"#,
+        );
+        receive(&mut store, &sally(), &undo, now()).unwrap();
+        assert!(store.items(&likes).unwrap().is_empty());
+    }
+
+    #[test]
+    fn block() {
+        let mut store = MemoryStore::new();
+        receive(&mut store, &sally(), &activity(FOLLOW), now()).unwrap();
+        let block = activity(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Block",
+                "id": "https://social.example/john/blocks/1",
+                "actor": "https://social.example/john",
+                "object": "https://example.com/sally"
+            }"#,
+        );
+        let outcome = receive(&mut store, &sally(), &block, now()).unwrap();
+        assert_eq!(
+            outcome.changes.last(),
+            Some(&Change::Removed {
+                collection: iri("https://example.com/sally/followers"),
+                item: iri("https://social.example/john"),
+            })
+        );
+    }
+}
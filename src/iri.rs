@@ -0,0 +1,704 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Borrow;
+use std::fmt;
+use std::str::FromStr;
+
+/// An [RFC 3987](https://www.rfc-editor.org/rfc/rfc3987) IRI reference, i.e.
+/// an absolute IRI such as `https://example.com/@ülrich#main-key` or a
+/// relative one such as `/notes/1`. Unlike [http::Uri], non-ASCII characters
+/// and fragments are accepted and kept as written.
+///
+/// IRIs compare equal when they are written the same; [Iri::equivalent]
+/// compares their [normalized](Iri::normalize) forms instead, so that
+/// `HTTPS://Example.com:443/a/../b` and `https://example.com/b` match.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(into = "String")]
+pub struct Iri(String);
+
+impl Iri {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The scheme, e.g. `https`, if the IRI is absolute.
+    pub fn scheme(&self) -> Option<&str> {
+        split(&self.0).scheme
+    }
+
+    /// The authority, e.g. `alice@example.com:8080`, if any.
+    pub fn authority(&self) -> Option<&str> {
+        split(&self.0).authority
+    }
+
+    /// The host part of the authority, e.g. `example.com`.
+    pub fn host(&self) -> Option<&str> {
+        self.authority()
+            .map(|authority| split_authority(authority).1)
+    }
+
+    /// The path, which may be empty.
+    pub fn path(&self) -> &str {
+        split(&self.0).path
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        split(&self.0).query
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        split(&self.0).fragment
+    }
+
+    /// Whether the IRI has a scheme, i.e. is not a relative reference.
+    pub fn is_absolute(&self) -> bool {
+        self.scheme().is_some()
+    }
+
+    /// Whether both IRIs are absolute and share their scheme and authority,
+    /// i.e. are served by the same origin.
+    pub fn same_origin(&self, other: &Iri) -> bool {
+        let (this, other) = (self.normalize(), other.normalize());
+        this.is_absolute()
+            && this.scheme() == other.scheme()
+            && this.authority() == other.authority()
+    }
+
+    /// The IRI with its fragment, if any, removed.
+    pub fn without_fragment(&self) -> Iri {
+        match self.0.split_once('#') {
+            Some((iri, _)) => Iri(iri.to_string()),
+            None => self.clone(),
+        }
+    }
+
+    /// Resolves `reference` against this IRI as its base (RFC 3986, section
+    /// 5.2), e.g. `../b` against `https://example.com/a/c` gives
+    /// `https://example.com/b`. Absolute references are returned as they are,
+    /// less any dot segments.
+    pub fn resolve(&self, reference: &Iri) -> Iri {
+        let base = split(&self.0);
+        let reference = split(&reference.0);
+        let resolved = if reference.scheme.is_some() {
+            Components {
+                path: &remove_dot_segments(reference.path),
+                ..reference
+            }
+            .to_string()
+        } else if reference.authority.is_some() {
+            Components {
+                scheme: base.scheme,
+                path: &remove_dot_segments(reference.path),
+                ..reference
+            }
+            .to_string()
+        } else if reference.path.is_empty() {
+            Components {
+                query: reference.query.or(base.query),
+                fragment: reference.fragment,
+                ..base
+            }
+            .to_string()
+        } else {
+            let path = if reference.path.starts_with('/') {
+                remove_dot_segments(reference.path)
+            } else {
+                remove_dot_segments(&merge(&base, reference.path))
+            };
+            Components {
+                path: &path,
+                query: reference.query,
+                fragment: reference.fragment,
+                ..base
+            }
+            .to_string()
+        };
+        Iri(resolved)
+    }
+
+    /// The syntax- and scheme-based normalization of the IRI (RFC 3987,
+    /// section 5.3): the scheme and host are lowercased, percent-encodings
+    /// uppercased and decoded where they encode unreserved characters, dot
+    /// segments removed, and default ports and empty HTTP paths dropped.
+    /// Non-ASCII hosts are not case-folded.
+    pub fn normalize(&self) -> Iri {
+        let components = split(&self.0);
+        let scheme = components.scheme.map(str::to_ascii_lowercase);
+        let authority = components.authority.map(|authority| {
+            let (userinfo, host, port) = split_authority(authority);
+            let mut normalized = String::new();
+            if let Some(userinfo) = userinfo {
+                normalized += &normalize_percent_encoding(userinfo);
+                normalized.push('@');
+            }
+            normalized += &normalize_percent_encoding(&host.to_ascii_lowercase());
+            let default_port = match scheme.as_deref() {
+                Some("http" | "ws") => Some("80"),
+                Some("https" | "wss") => Some("443"),
+                _ => None,
+            };
+            if let Some(port) = port.filter(|port| !port.is_empty() && Some(*port) != default_port)
+            {
+                normalized.push(':');
+                normalized += port;
+            }
+            normalized
+        });
+        let mut path = normalize_percent_encoding(components.path);
+        if scheme.is_some() {
+            path = remove_dot_segments(&path);
+        }
+        if path.is_empty()
+            && authority.is_some()
+            && matches!(scheme.as_deref(), Some("http" | "https" | "ws" | "wss"))
+        {
+            path.push('/');
+        }
+        let query = components.query.map(normalize_percent_encoding);
+        let fragment = components.fragment.map(normalize_percent_encoding);
+        Iri(Components {
+            scheme: scheme.as_deref(),
+            authority: authority.as_deref(),
+            path: &path,
+            query: query.as_deref(),
+            fragment: fragment.as_deref(),
+        }
+        .to_string())
+    }
+
+    /// Whether both IRIs are the same once [normalized](Iri::normalize).
+    pub fn equivalent(&self, other: &Iri) -> bool {
+        self == other || self.normalize() == other.normalize()
+    }
+
+    /// The URI the IRI maps to (RFC 3987, section 3.1), with non-ASCII
+    /// characters percent-encoded as UTF-8, e.g. for use with [http::Uri].
+    pub fn to_uri(&self) -> String {
+        let mut uri = String::with_capacity(self.0.len());
+        for c in self.0.chars() {
+            if c.is_ascii() {
+                uri.push(c);
+            } else {
+                let mut buffer = [0; 4];
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    uri += &format!("%{:02X}", byte);
+                }
+            }
+        }
+        uri
+    }
+}
+
+/// Error returned when a string is not a well-formed IRI reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidIri(pub String);
+
+impl fmt::Display for InvalidIri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid IRI: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidIri {}
+
+impl FromStr for Iri {
+    type Err = InvalidIri;
+
+    fn from_str(iri: &str) -> Result<Self, Self::Err> {
+        if is_iri_reference(iri) {
+            Ok(Iri(iri.to_string()))
+        } else {
+            Err(InvalidIri(iri.to_string()))
+        }
+    }
+}
+
+impl TryFrom<String> for Iri {
+    type Error = InvalidIri;
+
+    fn try_from(iri: String) -> Result<Self, Self::Error> {
+        if is_iri_reference(&iri) {
+            Ok(Iri(iri))
+        } else {
+            Err(InvalidIri(iri))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Iri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::error::try_from::<_, String, _>(deserializer)
+    }
+}
+
+impl TryFrom<&str> for Iri {
+    type Error = InvalidIri;
+
+    fn try_from(iri: &str) -> Result<Self, Self::Error> {
+        iri.parse()
+    }
+}
+
+/// Every URI is an IRI.
+impl From<http::Uri> for Iri {
+    fn from(uri: http::Uri) -> Self {
+        Iri(uri.to_string())
+    }
+}
+
+impl From<Iri> for String {
+    fn from(iri: Iri) -> Self {
+        iri.0
+    }
+}
+
+impl fmt::Display for Iri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Iri {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Iri {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Iri {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Iri {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// The five components of an IRI reference (RFC 3986, section 3).
+#[derive(Clone, Copy)]
+struct Components<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl fmt::Display for Components<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(scheme) = self.scheme {
+            write!(f, "{}:", scheme)?;
+        }
+        if let Some(authority) = self.authority {
+            write!(f, "//{}", authority)?;
+        }
+        f.write_str(self.path)?;
+        if let Some(query) = self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+fn split(iri: &str) -> Components<'_> {
+    let (rest, fragment) = match iri.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (iri, None),
+    };
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    let (scheme, rest) = match rest.split_once(':') {
+        Some((scheme, rest)) if is_scheme(scheme) => (Some(scheme), rest),
+        _ => (None, rest),
+    };
+    let (authority, path) = match rest.strip_prefix("//") {
+        Some(rest) => {
+            let end = rest.find('/').unwrap_or(rest.len());
+            (Some(&rest[..end]), &rest[end..])
+        }
+        None => (None, rest),
+    };
+    Components {
+        scheme,
+        authority,
+        path,
+        query,
+        fragment,
+    }
+}
+
+/// Splits an authority into its userinfo, host and port.
+fn split_authority(authority: &str) -> (Option<&str>, &str, Option<&str>) {
+    let (userinfo, host_port) = match authority.split_once('@') {
+        Some((userinfo, host_port)) => (Some(userinfo), host_port),
+        None => (None, authority),
+    };
+    // The host of an IP literal contains colons of its own.
+    let host_end = if host_port.starts_with('[') {
+        host_port.find(']').map_or(host_port.len(), |end| end + 1)
+    } else {
+        host_port.find(':').unwrap_or(host_port.len())
+    };
+    let (host, port) = host_port.split_at(host_end);
+    (userinfo, host, port.strip_prefix(':'))
+}
+
+fn is_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Checks `iri` against the RFC 3987 `IRI-reference` production.
+fn is_iri_reference(iri: &str) -> bool {
+    let components = split(iri);
+    if components.scheme.is_none()
+        && components
+            .path
+            .split('/')
+            .next()
+            .is_some_and(|segment| segment.contains(':'))
+    {
+        // A relative reference whose first segment would read as a scheme.
+        return false;
+    }
+    components.authority.is_none_or(is_authority)
+        && is_encoded(components.path, |c| is_pchar(c) || c == '/')
+        && components.query.is_none_or(|query| {
+            is_encoded(query, |c| {
+                is_pchar(c) || is_private(c) || c == '/' || c == '?'
+            })
+        })
+        && components
+            .fragment
+            .is_none_or(|fragment| is_encoded(fragment, |c| is_pchar(c) || c == '/' || c == '?'))
+}
+
+fn is_authority(authority: &str) -> bool {
+    let (userinfo, host, port) = split_authority(authority);
+    let is_host = match host.strip_prefix('[') {
+        Some(literal) => literal.strip_suffix(']').is_some_and(|literal| {
+            !literal.is_empty()
+                && literal
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || ":.-_~".contains(c) || is_sub_delim(c))
+        }),
+        None => is_encoded(host, |c| is_unreserved(c) || is_sub_delim(c)),
+    };
+    is_host
+        && userinfo.is_none_or(|userinfo| {
+            is_encoded(userinfo, |c| {
+                is_unreserved(c) || is_sub_delim(c) || c == ':'
+            })
+        })
+        && port.is_none_or(|port| port.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Whether every character of `component` is either allowed by `is_allowed`
+/// or part of a well-formed percent-encoding.
+fn is_encoded(component: &str, is_allowed: impl Fn(char) -> bool) -> bool {
+    let mut chars = component.chars();
+    while let Some(c) = chars.next() {
+        let is_valid = if c == '%' {
+            chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+                && chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+        } else {
+            is_allowed(c)
+        };
+        if !is_valid {
+            return false;
+        }
+    }
+    true
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~') || is_ucschar(c)
+}
+
+fn is_sub_delim(c: char) -> bool {
+    matches!(
+        c,
+        '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '='
+    )
+}
+
+fn is_pchar(c: char) -> bool {
+    is_unreserved(c) || is_sub_delim(c) || c == ':' || c == '@'
+}
+
+/// The non-ASCII characters IRIs allow outside the query.
+fn is_ucschar(c: char) -> bool {
+    let c = c as u32;
+    matches!(c, 0xA0..=0xD7FF | 0xF900..=0xFDCF | 0xFDF0..=0xFFEF)
+        || ((0x10000..=0xEFFFD).contains(&c)
+            && c & 0xFFFF <= 0xFFFD
+            && !(0xE0000..=0xE0FFF).contains(&c))
+}
+
+/// Private use characters, which IRIs only allow in the query.
+fn is_private(c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
+/// Merges a relative path with the path of its base (RFC 3986, section 5.2.3).
+fn merge(base: &Components, path: &str) -> String {
+    if base.authority.is_some() && base.path.is_empty() {
+        format!("/{}", path)
+    } else {
+        match base.path.rfind('/') {
+            Some(end) => format!("{}{}", &base.path[..=end], path),
+            None => path.to_string(),
+        }
+    }
+}
+
+/// Interprets the `.` and `..` segments of `path` (RFC 3986, section 5.2.4).
+fn remove_dot_segments(path: &str) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    let mut input = path;
+    while !input.is_empty() {
+        if let Some(rest) = input
+            .strip_prefix("../")
+            .or_else(|| input.strip_prefix("./"))
+        {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            output.pop();
+        } else if input == "/.." {
+            input = "/";
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            // The first segment, including its leading slash if any.
+            let end = input
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '/')
+                .map_or(input.len(), |(end, _)| end);
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output.concat()
+}
+
+/// Uppercases percent-encodings, and decodes those of unreserved characters.
+fn normalize_percent_encoding(component: &str) -> String {
+    let mut normalized = String::with_capacity(component.len());
+    let mut rest = component;
+    while let Some(start) = rest.find('%') {
+        normalized += &rest[..start];
+        rest = &rest[start..];
+        let mut bytes = Vec::new();
+        while let Some(byte) = rest
+            .get(1..3)
+            .filter(|_| rest.starts_with('%'))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            bytes.push(byte);
+            rest = &rest[3..];
+        }
+        if bytes.is_empty() {
+            // Not reachable for parsed IRIs, which are well-formed.
+            normalized.push('%');
+            rest = &rest[1..];
+        }
+        for chunk in bytes.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                if is_unreserved(c) {
+                    normalized.push(c);
+                } else {
+                    let mut buffer = [0; 4];
+                    for byte in c.encode_utf8(&mut buffer).bytes() {
+                        normalized += &format!("%{:02X}", byte);
+                    }
+                }
+            }
+            for byte in chunk.invalid() {
+                normalized += &format!("%{:02X}", byte);
+            }
+        }
+    }
+    normalized + rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn iri(iri: &str) -> Iri {
+        iri.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_iris() {
+        for valid in [
+            "https://example.com/",
+            "https://example.com/users/ülrich#main-key",
+            "https://例え.jp/パス?クエリ",
+            "https://alice:secret@[2001:db8::1]:8080/a;b=c?d=e&f#g",
+            "urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6",
+            "mailto:alice@example.com",
+            "as:Public",
+            "/notes/1",
+            "notes/1?page=2",
+            "//example.com/notes",
+            "#fragment",
+            "",
+        ] {
+            assert!(valid.parse::<Iri>().is_ok(), "{} should be valid", valid);
+        }
+        for invalid in [
+            "https://example.com/a b",
+            "https://example.com/<a>",
+            "https://example.com/%zz",
+            "https://example.com/%4",
+            "https://example.com/a#b#c",
+            "https://example.com:80a/",
+            "https://[::1/",
+            "https://example.com/\u{e000}",
+            "1http://example.com/",
+            "-notes:1",
+        ] {
+            assert_eq!(
+                invalid.parse::<Iri>(),
+                Err(InvalidIri(invalid.to_string())),
+                "{} should be invalid",
+                invalid
+            );
+        }
+        assert!("https://example.com/?\u{e000}".parse::<Iri>().is_ok());
+    }
+
+    #[test]
+    fn iri_components() {
+        let parsed = iri("https://alice@例え.jp:8443/パス/a?q=1#frag");
+        assert!(parsed.is_absolute());
+        assert_eq!(parsed.scheme(), Some("https"));
+        assert_eq!(parsed.authority(), Some("alice@例え.jp:8443"));
+        assert_eq!(parsed.host(), Some("例え.jp"));
+        assert_eq!(parsed.path(), "/パス/a");
+        assert_eq!(parsed.query(), Some("q=1"));
+        assert_eq!(parsed.fragment(), Some("frag"));
+        assert_eq!(
+            parsed.without_fragment(),
+            iri("https://alice@例え.jp:8443/パス/a?q=1")
+        );
+
+        let relative = iri("../notes/1");
+        assert!(!relative.is_absolute());
+        assert_eq!(relative.scheme(), None);
+        assert_eq!(relative.host(), None);
+        assert_eq!(relative.path(), "../notes/1");
+        assert_eq!(iri("https://[::1]:80/").host(), Some("[::1]"));
+
+        assert!(iri("https://Example.com:443/a").same_origin(&iri("https://example.com/b")));
+        assert!(!iri("https://example.com/a").same_origin(&iri("http://example.com/a")));
+        assert!(!iri("https://example.com/a").same_origin(&iri("https://example.org/a")));
+        assert!(!iri("/a").same_origin(&iri("/b")));
+    }
+
+    #[test]
+    fn resolve_references() {
+        // RFC 3986, section 5.4.
+        let base = iri("http://a/b/c/d;p?q");
+        for (reference, expected) in [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            (";x", "http://a/b/c/;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+        ] {
+            assert_eq!(
+                base.resolve(&iri(reference)),
+                iri(expected),
+                "resolving {}",
+                reference
+            );
+        }
+        assert_eq!(
+            iri("https://example.com/users/ülrich").resolve(&iri("ülrich/inbox")),
+            iri("https://example.com/users/ülrich/inbox")
+        );
+    }
+
+    #[test]
+    fn normalize_iris() {
+        assert_eq!(
+            iri("HTTPS://Example.COM:443/a/./b/../c/%7euser/%c3%a4?x=%2f#%41").normalize(),
+            iri("https://example.com/a/c/~user/ä?x=%2F#A")
+        );
+        assert_eq!(
+            iri("http://example.com").normalize(),
+            iri("http://example.com/")
+        );
+        assert_eq!(
+            iri("http://example.com:8080/%FF").normalize(),
+            iri("http://example.com:8080/%FF")
+        );
+        assert_eq!(iri("urn:UUID:ABC").normalize(), iri("urn:UUID:ABC"));
+        assert!(
+            iri("https://Example.com/%C3%BClrich").equivalent(&iri("https://example.com/ülrich"))
+        );
+        assert!(!iri("https://example.com/a").equivalent(&iri("https://example.com/A")));
+        assert_ne!(iri("https://example.com"), iri("https://example.com/"));
+    }
+
+    #[test]
+    fn map_to_uri() {
+        assert_eq!(
+            iri("https://example.com/ülrich?q=ä#main-key").to_uri(),
+            "https://example.com/%C3%BClrich?q=%C3%A4#main-key"
+        );
+        let uri: http::Uri = iri("https://example.com/ülrich").to_uri().parse().unwrap();
+        assert_eq!(Iri::from(uri), iri("https://example.com/%C3%BClrich"));
+    }
+
+    #[test]
+    fn serde_iri() {
+        let parsed: Iri = serde_json::from_str(r#""https://example.com/ülrich#main-key""#).unwrap();
+        assert_eq!(parsed, "https://example.com/ülrich#main-key");
+        assert_eq!(
+            serde_json::to_string(&parsed).unwrap(),
+            r#""https://example.com/ülrich#main-key""#
+        );
+        let result: serde_json::Result<Iri> = serde_json::from_str(r#""https://example.com/a b""#);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,1457 @@
+//! JSON-LD [expansion](https://www.w3.org/TR/json-ld11-api/#expansion-algorithm)
+//! and [compaction](https://www.w3.org/TR/json-ld11-api/#compaction-algorithm).
+//!
+//! Peers are free to write the same document using prefixes (`as:Note`), full
+//! IRIs or their own aliases for Activity Streams terms. Expanding a document
+//! and compacting it again against the Activity Streams context yields the
+//! property names the typed structs expect, see [normalize].
+//!
+//! This is a subset of the JSON-LD 1.1 algorithms covering what Activity
+//! Streams documents use in practice: remote and inline contexts, term and
+//! keyword aliases, compact IRIs, `@vocab`, `@language`, typed values and
+//! `@language` and `@list` containers. Remote contexts are served from copies
+//! bundled with the crate, or registered with the loader; others are only
+//! fetched if a fetcher is plugged in with [BundledContextLoader::with_fetcher].
+
+use crate::core::{Context, ContextBuilder, ContextEntry};
+use crate::iri::Iri;
+use crate::{Error, Result, Serde};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+/// The Activity Streams 2.0 namespace.
+pub const ACTIVITY_STREAMS_NAMESPACE: &str = "https://www.w3.org/ns/activitystreams#";
+
+/// Maximum nesting of remote contexts, guarding against cycles.
+const MAX_REMOTE_CONTEXTS: usize = 16;
+
+const KEYWORDS: &[&str] = &[
+    "@base",
+    "@container",
+    "@context",
+    "@direction",
+    "@graph",
+    "@id",
+    "@import",
+    "@included",
+    "@index",
+    "@json",
+    "@language",
+    "@list",
+    "@nest",
+    "@none",
+    "@prefix",
+    "@propagate",
+    "@protected",
+    "@reverse",
+    "@set",
+    "@type",
+    "@value",
+    "@version",
+    "@vocab",
+];
+
+/// Errors raised while processing a JSON-LD context, wrapped in
+/// [Error::Context].
+#[derive(Debug)]
+pub enum ContextError {
+    /// A remote context could not be loaded.
+    LoadingContextFailed(String),
+    /// A context or term definition is malformed.
+    InvalidContext(String),
+    /// Remote contexts are nested too deeply, most likely in a cycle.
+    ContextOverflow,
+    /// A term definition depends on itself.
+    CyclicTermDefinition(String),
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::LoadingContextFailed(iri) => write!(f, "failed to load context {}", iri),
+            ContextError::InvalidContext(reason) => write!(f, "invalid context: {}", reason),
+            ContextError::ContextOverflow => f.write_str("too many nested remote contexts"),
+            ContextError::CyclicTermDefinition(term) => {
+                write!(f, "cyclic definition of term {:?}", term)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContextError {}
+
+/// Resolves remote contexts, i.e. `@context` values given as an IRI, to the
+/// context documents they refer to.
+pub trait ContextLoader {
+    /// Returns the context document (an object with an `@context` member)
+    /// found at `iri`.
+    fn load(&self, iri: &str) -> Result<Value>;
+}
+
+/// Contexts embedded in the crate, keyed by IRI.
+const BUNDLED_CONTEXTS: &[(&str, &str)] = &[
+    (
+        "https://www.w3.org/ns/activitystreams",
+        include_str!("../contexts/activitystreams.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/v1",
+        include_str!("../contexts/security-v1.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/v2",
+        include_str!("../contexts/security-v2.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/multikey/v1",
+        include_str!("../contexts/multikey-v1.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/data-integrity/v1",
+        include_str!("../contexts/data-integrity-v1.jsonld"),
+    ),
+    (
+        "https://www.w3.org/ns/did/v1",
+        include_str!("../contexts/did-v1.jsonld"),
+    ),
+];
+
+/// The default [ContextLoader]. Serves the Activity Streams, security
+/// (v1 and v2), Multikey, Data Integrity and DID contexts from copies
+/// embedded in the crate, plus any context registered at runtime. Other IRIs
+/// are passed to the fetcher, if one is plugged in, and fail otherwise; the
+/// network is never touched otherwise.
+#[derive(Default)]
+pub struct BundledContextLoader {
+    registered: RwLock<HashMap<String, Value>>,
+    fetcher: Option<Box<dyn ContextLoader + Send + Sync>>,
+}
+
+impl BundledContextLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Falls back to `fetcher` for contexts that are neither bundled nor
+    /// registered.
+    pub fn with_fetcher(fetcher: impl ContextLoader + Send + Sync + 'static) -> Self {
+        BundledContextLoader {
+            registered: RwLock::default(),
+            fetcher: Some(Box::new(fetcher)),
+        }
+    }
+
+    /// Registers the context `document` under `iri`, replacing any context
+    /// previously registered or bundled for it.
+    pub fn register(&self, iri: &str, document: Value) {
+        self.registered
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(normalize_iri(iri).to_string(), document);
+    }
+}
+
+impl ContextLoader for BundledContextLoader {
+    fn load(&self, iri: &str) -> Result<Value> {
+        let key = normalize_iri(iri);
+        if let Some(document) = self
+            .registered
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+        {
+            return Ok(document.clone());
+        }
+        if let Some((_, document)) = BUNDLED_CONTEXTS.iter().find(|(i, _)| *i == key) {
+            return Ok(serde_json::from_str(document)?);
+        }
+        match &self.fetcher {
+            Some(fetcher) => fetcher.load(iri),
+            None => Err(ContextError::LoadingContextFailed(iri.to_string()).into()),
+        }
+    }
+}
+
+/// Maps equivalent spellings of a context IRI (`http` scheme, trailing slash,
+/// `.jsonld` suffix) to the one contexts are registered under.
+fn normalize_iri(iri: &str) -> &str {
+    let iri = iri.trim_end_matches('/');
+    let iri = iri.strip_suffix(".jsonld").unwrap_or(iri);
+    match iri {
+        "http://www.w3.org/ns/activitystreams" => Context::ACTIVITY_STREAMS,
+        "http://w3id.org/security/v1" => "https://w3id.org/security/v1",
+        "http://w3id.org/security/v2" => "https://w3id.org/security/v2",
+        iri => iri,
+    }
+}
+
+fn is_keyword(value: &str) -> bool {
+    KEYWORDS.contains(&value)
+}
+
+/// A processed term definition.
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    id: String,
+    term_type: Option<String>,
+    container: Option<String>,
+    /// `Some(None)` when the term is explicitly mapped to no language.
+    language: Option<Option<String>>,
+}
+
+/// The result of processing one or more contexts: the term definitions and
+/// defaults in effect for a part of a document.
+#[derive(Debug, Clone, Default)]
+pub struct ActiveContext {
+    /// `None` marks terms explicitly defined as `null`.
+    terms: BTreeMap<String, Option<Term>>,
+    vocab: Option<String>,
+    language: Option<String>,
+    base: Option<String>,
+}
+
+impl ActiveContext {
+    /// Processes a local context (the value of an `@context` property),
+    /// resolving remote contexts with `loader`.
+    pub fn parse(context: &Value, loader: &dyn ContextLoader) -> Result<Self> {
+        ActiveContext::default().process(context, loader, 0)
+    }
+
+    /// The default language (`@language`).
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// The IRI `term` expands to, if it is defined.
+    pub fn expand_term(&self, term: &str) -> Option<&str> {
+        self.term(term).map(|t| t.id.as_str())
+    }
+
+    fn term(&self, term: &str) -> Option<&Term> {
+        self.terms.get(term).and_then(Option::as_ref)
+    }
+
+    fn process(
+        &self,
+        local: &Value,
+        loader: &dyn ContextLoader,
+        remote_contexts: usize,
+    ) -> Result<Self> {
+        let mut result = self.clone();
+        let contexts = match local {
+            Value::Array(contexts) => contexts.iter().collect(),
+            context => vec![context],
+        };
+        for context in contexts {
+            match context {
+                Value::Null => result = ActiveContext::default(),
+                Value::String(iri) => {
+                    if remote_contexts >= MAX_REMOTE_CONTEXTS {
+                        return Err(ContextError::ContextOverflow.into());
+                    }
+                    let document = loader.load(iri)?;
+                    let context = document.get("@context").ok_or_else(|| {
+                        ContextError::InvalidContext(format!("{} has no @context", iri))
+                    })?;
+                    result = result.process(context, loader, remote_contexts + 1)?;
+                }
+                Value::Object(definition) => result.define(definition)?,
+                other => {
+                    return Err(ContextError::InvalidContext(format!(
+                        "unexpected context {}",
+                        other
+                    ))
+                    .into())
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn define(&mut self, local: &Map<String, Value>) -> Result<()> {
+        match local.get("@base") {
+            Some(Value::String(base)) => self.base = Some(base.clone()),
+            Some(Value::Null) => self.base = None,
+            _ => {}
+        }
+        match local.get("@vocab") {
+            Some(Value::String(vocab)) => self.vocab = self.expand_iri(vocab, true, true),
+            Some(Value::Null) => self.vocab = None,
+            _ => {}
+        }
+        match local.get("@language") {
+            Some(Value::String(language)) => self.language = Some(language.clone()),
+            Some(Value::Null) => self.language = None,
+            _ => {}
+        }
+
+        let mut defined = HashMap::new();
+        for term in local.keys() {
+            if !term.starts_with('@') {
+                self.create_term(local, term, &mut defined)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn create_term(
+        &mut self,
+        local: &Map<String, Value>,
+        term: &str,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<()> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => return Err(ContextError::CyclicTermDefinition(term.to_string()).into()),
+            None => {}
+        }
+        defined.insert(term.to_string(), false);
+
+        let definition = match &local[term] {
+            Value::Null => None,
+            Value::String(id) => self.expand_iri_local(id, local, defined)?.map(|id| Term {
+                id,
+                term_type: None,
+                container: None,
+                language: None,
+            }),
+            Value::Object(expanded) => self.create_expanded_term(term, expanded, local, defined)?,
+            other => {
+                return Err(ContextError::InvalidContext(format!(
+                    "invalid definition of term {:?}: {}",
+                    term, other
+                ))
+                .into())
+            }
+        };
+        self.terms.insert(term.to_string(), definition);
+        defined.insert(term.to_string(), true);
+        Ok(())
+    }
+
+    fn create_expanded_term(
+        &mut self,
+        term: &str,
+        expanded: &Map<String, Value>,
+        local: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<Option<Term>> {
+        let id = match expanded.get("@id") {
+            Some(Value::Null) => return Ok(None),
+            Some(Value::String(id)) => self.expand_iri_local(id, local, defined)?,
+            Some(other) => {
+                return Err(ContextError::InvalidContext(format!(
+                    "invalid @id of term {:?}: {}",
+                    term, other
+                ))
+                .into())
+            }
+            None if term.contains(':') => self.expand_iri_local(term, local, defined)?,
+            None => self
+                .vocab
+                .as_ref()
+                .map(|vocab| format!("{}{}", vocab, term)),
+        };
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        let term_type = match expanded.get("@type") {
+            Some(Value::String(t)) if is_keyword(t) => Some(t.clone()),
+            Some(Value::String(t)) => self.expand_iri_local(t, local, defined)?,
+            _ => None,
+        };
+        let container = match expanded.get("@container") {
+            Some(Value::String(container)) => Some(container.clone()),
+            Some(Value::Array(containers)) => {
+                let containers: Vec<&str> = containers.iter().filter_map(Value::as_str).collect();
+                containers
+                    .iter()
+                    .find(|c| **c != "@set")
+                    .or_else(|| containers.first())
+                    .map(|c| c.to_string())
+            }
+            _ => None,
+        };
+        let language = match expanded.get("@language") {
+            Some(Value::String(language)) => Some(Some(language.clone())),
+            Some(Value::Null) => Some(None),
+            _ => None,
+        };
+        Ok(Some(Term {
+            id,
+            term_type,
+            container,
+            language,
+        }))
+    }
+
+    /// Expands `value` as a vocabulary IRI while a local context is being
+    /// processed, first defining any terms of `local` it depends on.
+    fn expand_iri_local(
+        &mut self,
+        value: &str,
+        local: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<Option<String>> {
+        if local.contains_key(value) && !value.starts_with('@') {
+            self.create_term(local, value, defined)?;
+        }
+        if let Some((prefix, _)) = value.split_once(':') {
+            if local.contains_key(prefix) {
+                self.create_term(local, prefix, defined)?;
+            }
+        }
+        Ok(self.expand_iri(value, true, false))
+    }
+
+    /// The [IRI expansion](https://www.w3.org/TR/json-ld11-api/#iri-expansion)
+    /// algorithm. Returns `None` for values that cannot be expanded, such as
+    /// unknown keywords or terms explicitly defined as `null`.
+    fn expand_iri(&self, value: &str, vocab: bool, document_relative: bool) -> Option<String> {
+        if is_keyword(value) {
+            return Some(value.to_string());
+        }
+        if value.starts_with('@') {
+            return None;
+        }
+        if vocab {
+            if let Some(term) = self.terms.get(value) {
+                return term.as_ref().map(|t| t.id.clone());
+            }
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            if let Some(Some(term)) = self.terms.get(prefix) {
+                return Some(format!("{}{}", term.id, suffix));
+            }
+            return Some(value.to_string());
+        }
+        if vocab {
+            if let Some(vocab) = &self.vocab {
+                return Some(format!("{}{}", vocab, value));
+            }
+        }
+        if document_relative {
+            let base = self
+                .base
+                .as_deref()
+                .and_then(|base| base.parse::<Iri>().ok());
+            if let (Some(base), Ok(reference)) = (base, value.parse::<Iri>()) {
+                return Some(base.resolve(&reference).to_string());
+            }
+        }
+        Some(value.to_string())
+    }
+}
+
+/// Expands `document`, replacing terms and compact IRIs with absolute IRIs and
+/// values with their explicit `@value`/`@id` forms. The result is always an
+/// array of node objects.
+pub fn expand(document: &Value) -> Result<Value> {
+    expand_with(document, &BundledContextLoader::new())
+}
+
+/// Like [expand], resolving remote contexts with `loader`.
+pub fn expand_with(document: &Value, loader: &dyn ContextLoader) -> Result<Value> {
+    let expanded = expand_element(loader, &ActiveContext::default(), None, document)?;
+    Ok(match expanded {
+        Value::Null => Value::Array(Vec::new()),
+        Value::Object(mut node) if node.len() == 1 && node.contains_key("@graph") => {
+            node.remove("@graph").unwrap_or_default()
+        }
+        Value::Array(nodes) => Value::Array(nodes),
+        node => Value::Array(vec![node]),
+    })
+}
+
+fn expand_element(
+    loader: &dyn ContextLoader,
+    context: &ActiveContext,
+    property: Option<&str>,
+    element: &Value,
+) -> Result<Value> {
+    match element {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let mut result = Vec::new();
+            for item in items {
+                match expand_element(loader, context, property, item)? {
+                    Value::Null => {}
+                    Value::Array(items) => result.extend(items),
+                    item => result.push(item),
+                }
+            }
+            Ok(Value::Array(result))
+        }
+        Value::Object(node) => expand_object(loader, context, property, node),
+        scalar => Ok(match property {
+            None | Some("@graph") => Value::Null,
+            Some(property) => expand_value(context, property, scalar),
+        }),
+    }
+}
+
+fn expand_object(
+    loader: &dyn ContextLoader,
+    context: &ActiveContext,
+    property: Option<&str>,
+    node: &Map<String, Value>,
+) -> Result<Value> {
+    let context = match node.get("@context") {
+        Some(local) => Cow::Owned(context.process(local, loader, 0)?),
+        None => Cow::Borrowed(context),
+    };
+    let is_value = node
+        .keys()
+        .any(|key| context.expand_iri(key, true, false).as_deref() == Some("@value"));
+
+    let mut result = Map::new();
+    for (key, value) in node {
+        if key == "@context" {
+            continue;
+        }
+        let Some(expanded) = context.expand_iri(key, true, false) else {
+            continue;
+        };
+        if expanded.starts_with('@') {
+            let value = match expanded.as_str() {
+                "@id" => match value {
+                    Value::String(id) => Value::from(
+                        context
+                            .expand_iri(id, false, true)
+                            .unwrap_or_else(|| id.clone()),
+                    ),
+                    other => {
+                        return Err(
+                            ContextError::InvalidContext(format!("invalid @id {}", other)).into(),
+                        )
+                    }
+                },
+                "@type" => {
+                    let types: Vec<Value> = match value {
+                        Value::Array(types) => types.iter().collect::<Vec<_>>(),
+                        single => vec![single],
+                    }
+                    .into_iter()
+                    .filter_map(Value::as_str)
+                    .filter_map(|t| context.expand_iri(t, true, true))
+                    .map(Value::from)
+                    .collect();
+                    match (is_value, types.len()) {
+                        (true, 1) => types[0].clone(),
+                        _ => Value::Array(types),
+                    }
+                }
+                "@list" => Value::Array(match expand_element(loader, &context, property, value)? {
+                    Value::Array(items) => items,
+                    Value::Null => Vec::new(),
+                    item => vec![item],
+                }),
+                "@set" | "@graph" | "@included" => {
+                    expand_element(loader, &context, property, value)?
+                }
+                _ => value.clone(),
+            };
+            result.insert(expanded, value);
+            continue;
+        }
+        if !expanded.contains(':') {
+            continue;
+        }
+
+        let term = context.term(key);
+        let container = term.and_then(|t| t.container.as_deref());
+        let expanded_value = match (container, value) {
+            (Some("@language"), Value::Object(languages)) => {
+                let mut values = Vec::new();
+                for (language, value) in languages {
+                    let items = match value {
+                        Value::Array(items) => items.iter().collect(),
+                        item => vec![item],
+                    };
+                    for item in items.into_iter().filter(|v| !v.is_null()) {
+                        let mut value = Map::new();
+                        value.insert(String::from("@value"), item.clone());
+                        if language != "@none" {
+                            value.insert(String::from("@language"), Value::from(language.clone()));
+                        }
+                        values.push(Value::Object(value));
+                    }
+                }
+                Value::Array(values)
+            }
+            _ => expand_element(loader, &context, Some(key), value)?,
+        };
+        let expanded_value = match (container, expanded_value) {
+            (_, Value::Null) => continue,
+            (Some("@list"), Value::Object(list)) if list.contains_key("@list") => {
+                Value::Object(list)
+            }
+            (Some("@list"), Value::Array(items)) => list_object(items),
+            (Some("@list"), item) => list_object(vec![item]),
+            (_, value) => value,
+        };
+        append(&mut result, expanded, expanded_value);
+    }
+
+    if let Some(value) = result.get("@value") {
+        return Ok(if value.is_null() {
+            Value::Null
+        } else {
+            Value::Object(result)
+        });
+    }
+    if result.len() == 1 && result.contains_key("@language") {
+        return Ok(Value::Null);
+    }
+    Ok(Value::Object(result))
+}
+
+fn expand_value(context: &ActiveContext, property: &str, value: &Value) -> Value {
+    let term = context.term(property);
+    let mut result = Map::new();
+    match (term.and_then(|t| t.term_type.as_deref()), value) {
+        (Some("@id"), Value::String(id)) => {
+            let id = context
+                .expand_iri(id, false, true)
+                .unwrap_or_else(|| id.clone());
+            result.insert(String::from("@id"), Value::from(id));
+        }
+        (Some("@vocab"), Value::String(id)) => {
+            let id = context
+                .expand_iri(id, true, true)
+                .unwrap_or_else(|| id.clone());
+            result.insert(String::from("@id"), Value::from(id));
+        }
+        (Some(term_type), _) if !term_type.starts_with('@') => {
+            result.insert(String::from("@value"), value.clone());
+            result.insert(String::from("@type"), Value::from(term_type));
+        }
+        _ => {
+            result.insert(String::from("@value"), value.clone());
+            if value.is_string() {
+                let language = match term.and_then(|t| t.language.as_ref()) {
+                    Some(language) => language.as_deref(),
+                    None => context.language(),
+                };
+                if let Some(language) = language {
+                    result.insert(String::from("@language"), Value::from(language));
+                }
+            }
+        }
+    }
+    Value::Object(result)
+}
+
+fn list_object(items: Vec<Value>) -> Value {
+    let mut list = Map::new();
+    list.insert(String::from("@list"), Value::Array(items));
+    Value::Object(list)
+}
+
+fn append(node: &mut Map<String, Value>, property: String, value: Value) {
+    let values = node
+        .entry(property)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if let Value::Array(values) = values {
+        match value {
+            Value::Array(items) => values.extend(items),
+            item => values.push(item),
+        }
+    }
+}
+
+/// Terms of an [ActiveContext] indexed by the IRI they expand to, preferred
+/// terms first and shortest first otherwise.
+struct InverseContext<'a> {
+    context: &'a ActiveContext,
+    terms: HashMap<&'a str, Vec<(&'a str, &'a Term)>>,
+}
+
+impl<'a> InverseContext<'a> {
+    /// Terms defined identically in `preferred` win over other terms for the
+    /// same IRI.
+    fn new(context: &'a ActiveContext, preferred: Option<&ActiveContext>) -> Self {
+        let mut terms: HashMap<&str, Vec<(&str, &Term)>> = HashMap::new();
+        for (name, term) in &context.terms {
+            if let Some(term) = term {
+                terms
+                    .entry(term.id.as_str())
+                    .or_default()
+                    .push((name.as_str(), term));
+            }
+        }
+        for candidates in terms.values_mut() {
+            candidates.sort_by_key(|(name, term)| {
+                let preferred = preferred.is_some_and(|p| p.term(name) == Some(*term));
+                (!preferred, name.len(), *name)
+            });
+        }
+        InverseContext { context, terms }
+    }
+
+    /// The term aliasing `keyword`, or the keyword itself.
+    fn alias(&self, keyword: &str) -> String {
+        self.terms
+            .get(keyword)
+            .and_then(|candidates| candidates.first())
+            .map_or_else(|| keyword.to_string(), |(name, _)| name.to_string())
+    }
+
+    /// Compacts a vocabulary IRI (a property name or a type) to a term, a
+    /// vocabulary-relative IRI or a compact IRI. Other IRIs, such as `@id`
+    /// values, are left absolute.
+    fn compact_iri(&self, iri: &str, vocab: bool) -> String {
+        if is_keyword(iri) {
+            return self.alias(iri);
+        }
+        if !vocab {
+            return iri.to_string();
+        }
+        if let Some((name, _)) = self.terms.get(iri).and_then(|candidates| {
+            candidates
+                .iter()
+                .find(|(_, term)| term.container.is_none() && term.language.is_none())
+        }) {
+            return name.to_string();
+        }
+        if let Some(suffix) = self
+            .context
+            .vocab
+            .as_deref()
+            .and_then(|vocab| iri.strip_prefix(vocab))
+        {
+            if !suffix.is_empty() && !self.context.terms.contains_key(suffix) {
+                return suffix.to_string();
+            }
+        }
+        let mut compact: Option<String> = None;
+        for (name, term) in &self.context.terms {
+            let Some(term) = term else { continue };
+            if name.contains(':') || !term.id.ends_with(['/', '#', ':', '?', '[', ']', '@']) {
+                continue;
+            }
+            let Some(suffix) = iri.strip_prefix(term.id.as_str()) else {
+                continue;
+            };
+            let candidate = format!("{}:{}", name, suffix);
+            if suffix.is_empty() || self.context.terms.contains_key(&candidate) {
+                continue;
+            }
+            if compact
+                .as_ref()
+                .is_none_or(|c| (candidate.len(), &candidate) < (c.len(), c))
+            {
+                compact = Some(candidate);
+            }
+        }
+        compact.unwrap_or_else(|| iri.to_string())
+    }
+
+    /// Selects the term to compact `item`, a value of the property `iri`, to.
+    fn select_term(&self, iri: &str, item: &Value) -> Option<(&'a str, &'a Term)> {
+        let candidates = self.terms.get(iri)?;
+        let mut best: Option<(u8, (&str, &Term))> = None;
+        for &(name, term) in candidates {
+            if let Some(score) = self.score(term, item) {
+                if best.is_none_or(|(best, _)| score > best) {
+                    best = Some((score, (name, term)));
+                }
+            }
+        }
+        best.map(|(_, candidate)| candidate)
+    }
+
+    fn score(&self, term: &Term, item: &Value) -> Option<u8> {
+        let node = item.as_object();
+        let has = |key: &str| node.is_some_and(|n| n.contains_key(key));
+        let language = node
+            .filter(|n| n.contains_key("@value"))
+            .and_then(|n| n.get("@language"))
+            .and_then(Value::as_str);
+        let term_language = match &term.language {
+            Some(language) => language.as_deref(),
+            None => self.context.language(),
+        };
+        match term.container.as_deref() {
+            Some("@language") => return language.map(|_| 2),
+            Some("@list") => return has("@list").then_some(3),
+            None | Some("@set") => {}
+            Some(_) => return None,
+        }
+        if has("@list") {
+            return Some(0);
+        }
+        if let Some(language) = language {
+            return match (&term.term_type, term_language) {
+                (Some(_), _) => None,
+                (None, Some(l)) if l.eq_ignore_ascii_case(language) => Some(3),
+                (None, _) if matches!(term.language, Some(Some(_))) => None,
+                (None, _) => Some(1),
+            };
+        }
+        if has("@value") {
+            let value_type = node.and_then(|n| n.get("@type")).and_then(Value::as_str);
+            return match (term.term_type.as_deref(), value_type) {
+                (Some(t), Some(v)) if t == v => Some(3),
+                (None, Some(_)) => Some(1),
+                (None, None) => Some(if term_language.is_none() { 3 } else { 1 }),
+                _ => None,
+            };
+        }
+        match term.term_type.as_deref() {
+            Some("@id") | Some("@vocab") => Some(3),
+            None => Some(2),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Compacts an expanded document against `context`, shortening IRIs to the
+/// terms it defines. The result carries `context` as its `@context`.
+pub fn compact(expanded: &Value, context: &Context) -> Result<Value> {
+    compact_with(expanded, context, &BundledContextLoader::new())
+}
+
+/// Like [compact], resolving remote contexts with `loader`.
+pub fn compact_with(
+    expanded: &Value,
+    context: &Context,
+    loader: &dyn ContextLoader,
+) -> Result<Value> {
+    let local = serde_json::to_value(context).map_err(Error::Serialize)?;
+    let active = ActiveContext::parse(&local, loader)?;
+    Ok(compact_active(expanded, &active, local, None))
+}
+
+/// Compacts `expanded` against `active`, the processed form of `local`, which
+/// becomes the `@context` of the result.
+fn compact_active(
+    expanded: &Value,
+    active: &ActiveContext,
+    local: Value,
+    preferred: Option<&ActiveContext>,
+) -> Value {
+    let inverse = InverseContext::new(active, preferred);
+    let mut result = match compact_element(&inverse, None, expanded) {
+        Value::Array(mut nodes) if nodes.len() == 1 => match nodes.remove(0) {
+            Value::Object(node) => node,
+            _ => Map::new(),
+        },
+        Value::Array(nodes) if nodes.is_empty() => Map::new(),
+        Value::Array(nodes) => {
+            let mut graph = Map::new();
+            graph.insert(inverse.alias("@graph"), Value::Array(nodes));
+            graph
+        }
+        Value::Object(node) => node,
+        _ => Map::new(),
+    };
+    result.insert(String::from("@context"), local);
+    Value::Object(result)
+}
+
+fn compact_element(inverse: &InverseContext, term: Option<&Term>, element: &Value) -> Value {
+    match element {
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| compact_element(inverse, term, item))
+                .filter(|item| !item.is_null())
+                .collect(),
+        ),
+        Value::Object(node) => {
+            if let Some(value) = compact_value(inverse, term, node) {
+                return value;
+            }
+            if let Some(Value::Array(items)) = node.get("@list") {
+                let items: Vec<Value> = items
+                    .iter()
+                    .map(|item| compact_element(inverse, term, item))
+                    .collect();
+                if term.and_then(|t| t.container.as_deref()) == Some("@list") {
+                    return Value::Array(items);
+                }
+                let mut list = Map::new();
+                list.insert(inverse.alias("@list"), Value::Array(items));
+                return Value::Object(list);
+            }
+            compact_node(inverse, node)
+        }
+        scalar => scalar.clone(),
+    }
+}
+
+/// Compacts a value object, or a node reference, to a bare value when `term`
+/// makes it unambiguous.
+fn compact_value(
+    inverse: &InverseContext,
+    term: Option<&Term>,
+    node: &Map<String, Value>,
+) -> Option<Value> {
+    let term_type = term.and_then(|t| t.term_type.as_deref());
+    if node.len() == 1 {
+        if let Some(Value::String(id)) = node.get("@id") {
+            return match term_type {
+                Some("@id") => Some(Value::from(inverse.compact_iri(id, false))),
+                Some("@vocab") => Some(Value::from(inverse.compact_iri(id, true))),
+                _ => None,
+            };
+        }
+    }
+    let value = node.get("@value")?;
+    let language = match term.and_then(|t| t.language.as_ref()) {
+        Some(language) => language.as_deref(),
+        None => inverse.context.language(),
+    };
+    let compactable = match (node.get("@type"), node.get("@language"), node.len()) {
+        (Some(value_type), None, 2) => value_type.as_str() == term_type,
+        (None, Some(value_language), 2) => {
+            term_type.is_none()
+                && value_language
+                    .as_str()
+                    .zip(language)
+                    .is_some_and(|(a, b)| a.eq_ignore_ascii_case(b))
+        }
+        (None, None, 1) => term_type.is_none() && (!value.is_string() || language.is_none()),
+        _ => false,
+    };
+    compactable.then(|| value.clone())
+}
+
+fn compact_node(inverse: &InverseContext, node: &Map<String, Value>) -> Value {
+    let mut result = Map::new();
+    let mut properties: BTreeMap<String, (Option<&Term>, Vec<Value>)> = BTreeMap::new();
+    for (property, value) in node {
+        match property.as_str() {
+            "@id" => {
+                let id = value
+                    .as_str()
+                    .map(|id| Value::from(inverse.compact_iri(id, false)))
+                    .unwrap_or_else(|| value.clone());
+                result.insert(inverse.alias("@id"), id);
+            }
+            "@type" => {
+                let compact = |t: &Value| {
+                    t.as_str()
+                        .map(|t| Value::from(inverse.compact_iri(t, true)))
+                        .unwrap_or_else(|| t.clone())
+                };
+                let types = match value {
+                    Value::Array(types) if types.len() == 1 => compact(&types[0]),
+                    Value::Array(types) => Value::Array(types.iter().map(compact).collect()),
+                    single => compact(single),
+                };
+                result.insert(inverse.alias("@type"), types);
+            }
+            keyword if keyword.starts_with('@') => {
+                let value = match value {
+                    Value::Array(_) | Value::Object(_) => compact_element(inverse, None, value),
+                    _ => value.clone(),
+                };
+                result.insert(inverse.alias(keyword), value);
+            }
+            iri => {
+                let items = match value {
+                    Value::Array(items) => items.as_slice(),
+                    item => std::slice::from_ref(item),
+                };
+                if items.is_empty() {
+                    let name = inverse.compact_iri(iri, true);
+                    properties.entry(name).or_insert((None, Vec::new()));
+                }
+                for item in items {
+                    match inverse.select_term(iri, item) {
+                        Some((name, term)) if term.container.as_deref() == Some("@language") => {
+                            let language = item["@language"].as_str().unwrap_or("@none");
+                            let map = result
+                                .entry(name.to_string())
+                                .or_insert_with(|| Value::Object(Map::new()));
+                            if let Value::Object(map) = map {
+                                match map.get_mut(language) {
+                                    Some(Value::Array(values)) => {
+                                        values.push(item["@value"].clone())
+                                    }
+                                    Some(existing) => {
+                                        *existing = Value::Array(vec![
+                                            existing.clone(),
+                                            item["@value"].clone(),
+                                        ])
+                                    }
+                                    None => {
+                                        map.insert(language.to_string(), item["@value"].clone());
+                                    }
+                                }
+                            }
+                        }
+                        Some((name, term)) => {
+                            let compacted = compact_element(inverse, Some(term), item);
+                            properties
+                                .entry(name.to_string())
+                                .or_insert((Some(term), Vec::new()))
+                                .1
+                                .push(compacted);
+                        }
+                        None => {
+                            let compacted = compact_element(inverse, None, item);
+                            properties
+                                .entry(inverse.compact_iri(iri, true))
+                                .or_insert((None, Vec::new()))
+                                .1
+                                .push(compacted);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    for (name, (term, mut values)) in properties {
+        let container = term.and_then(|t| t.container.as_deref());
+        let value = match container {
+            // The list itself was compacted to an array already.
+            Some("@list") if values.len() == 1 => values.remove(0),
+            Some("@list") | Some("@set") => Value::Array(values),
+            _ if values.len() == 1 => values.remove(0),
+            _ => Value::Array(values),
+        };
+        result.insert(name, value);
+    }
+    Value::Object(result)
+}
+
+/// Normalizes `document` so that its properties use the terms of the Activity
+/// Streams context, whatever prefixes, IRIs or aliases it was written with.
+///
+/// The result is compacted against the document's own context, with the
+/// Activity Streams context added if it lacks it, and can be deserialized
+/// into the typed structs. Activity Streams terms take precedence over the
+/// document's aliases for the same IRIs, so an alias such as
+/// `"body": "as:content"` is replaced by `content`. Terms only the document
+/// defines are kept, and IRIs without a term in either context are kept as
+/// absolute or compact IRIs.
+///
+/// A context using the Activity Streams context IRI as its `@vocab`, as the
+/// examples of the Activity Vocabulary and [ContextBuilder::new] do, is taken
+/// to mean the Activity Streams context.
+pub fn normalize(document: &Value) -> Result<Value> {
+    normalize_with(document, &BundledContextLoader::new())
+}
+
+/// Like [normalize], resolving remote contexts with `loader`.
+pub fn normalize_with(document: &Value, loader: &dyn ContextLoader) -> Result<Value> {
+    normalize_extended(document, loader, |_| {})
+}
+
+/// Like [normalize_with], also compacting to the terms of the typed
+/// [Extension](crate::core::Extension) of `T`, if any, e.g. `featured` for a
+/// [Person](crate::extended::Person) with the Mastodon actor extension. The
+/// context of the result defines them.
+pub fn normalize_for<T: Serde>(document: &Value, loader: &dyn ContextLoader) -> Result<Value> {
+    normalize_extended(document, loader, T::extend_context)
+}
+
+fn normalize_extended(
+    document: &Value,
+    loader: &dyn ContextLoader,
+    extend: fn(&mut Context),
+) -> Result<Value> {
+    let Some(local) = document.get("@context").filter(|local| !local.is_null()) else {
+        // Without a context no term is defined; leave it to the caller to
+        // reject the document.
+        return Ok(document.clone());
+    };
+    let mut context: Context = crate::error::from_value(local.clone())?;
+    let has_activity_streams = context.iris().any(is_activity_streams)
+        || context
+            .definitions()
+            .any(|definition| definition.vocab.as_deref().is_some_and(is_activity_streams));
+    if !has_activity_streams {
+        let mut entries = vec![ContextEntry::Iri(Context::ACTIVITY_STREAMS.to_string())];
+        entries.extend(context.entries().iter().cloned());
+        context = Context::from(entries);
+    }
+    extend(&mut context);
+
+    let local = serde_json::to_value(&context).map_err(Error::Serialize)?;
+    let processing = activity_streams_vocab(&local);
+    let mut document = document.clone();
+    if let Value::Object(node) = &mut document {
+        node.insert(String::from("@context"), processing.clone());
+    }
+    let active = ActiveContext::parse(&processing, loader)?;
+
+    let mut preferred = ContextBuilder::activity_streams().build();
+    extend(&mut preferred);
+    let preferred = serde_json::to_value(&preferred).map_err(Error::Serialize)?;
+    let preferred = ActiveContext::parse(&preferred, loader)?;
+    Ok(compact_active(
+        &expand_with(&document, loader)?,
+        &active,
+        local,
+        Some(&preferred),
+    ))
+}
+
+/// Whether `iri` is the Activity Streams context, in any of its spellings.
+fn is_activity_streams(iri: &str) -> bool {
+    normalize_iri(iri) == Context::ACTIVITY_STREAMS
+}
+
+/// Replaces the `@vocab` of inline definitions that use the Activity Streams
+/// context IRI as their vocabulary by a reference to the context itself,
+/// which is what their authors expect: the Activity Streams terms, including
+/// the `id` and `type` aliases, rather than IRIs such as
+/// `https://www.w3.org/ns/activitystreamsname`.
+fn activity_streams_vocab(local: &Value) -> Value {
+    fn replace(entry: &Value) -> Vec<Value> {
+        let Value::Object(definition) = entry else {
+            return vec![entry.clone()];
+        };
+        let vocab = definition.get("@vocab").and_then(Value::as_str);
+        if !vocab.is_some_and(is_activity_streams) {
+            return vec![entry.clone()];
+        }
+        let mut definition = definition.clone();
+        definition.remove("@vocab");
+        let mut entries = vec![Value::from(Context::ACTIVITY_STREAMS)];
+        if !definition.is_empty() {
+            entries.push(Value::Object(definition));
+        }
+        entries
+    }
+    match local {
+        Value::Array(entries) => Value::Array(entries.iter().flat_map(replace).collect()),
+        entry => Value::Array(replace(entry)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Document, Null, Object, TermDefinition};
+    use crate::extended::{MastodonActorExtension, Note, Person};
+    use crate::iri::Iri;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn expand_note() {
+        let document = json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "type": "Note",
+            "id": "http://example.org/notes/1",
+            "content": "Hello",
+            "attributedTo": "http://example.org/alice",
+            "published": "2015-01-25T12:34:56Z"
+        });
+        let expected = json!([{
+            "@id": "http://example.org/notes/1",
+            "@type": ["https://www.w3.org/ns/activitystreams#Note"],
+            "https://www.w3.org/ns/activitystreams#content": [{"@value": "Hello"}],
+            "https://www.w3.org/ns/activitystreams#attributedTo": [
+                {"@id": "http://example.org/alice"}
+            ],
+            "https://www.w3.org/ns/activitystreams#published": [{
+                "@value": "2015-01-25T12:34:56Z",
+                "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+            }]
+        }]);
+        assert_eq!(expand(&document).unwrap(), expected);
+    }
+
+    #[test]
+    fn expand_language_map() {
+        let document = json!({
+            "@context": [
+                "https://www.w3.org/ns/activitystreams",
+                {"@language": "en"}
+            ],
+            "name": "Hello",
+            "contentMap": {"fi": "Hei"}
+        });
+        let expected = json!([{
+            "https://www.w3.org/ns/activitystreams#name": [
+                {"@value": "Hello", "@language": "en"}
+            ],
+            "https://www.w3.org/ns/activitystreams#content": [
+                {"@value": "Hei", "@language": "fi"}
+            ]
+        }]);
+        assert_eq!(expand(&document).unwrap(), expected);
+    }
+
+    #[test]
+    fn expand_relative_iris() {
+        let document = json!({
+            "@context": [
+                "https://www.w3.org/ns/activitystreams",
+                {"@base": "https://example.com/sally/notes/1"}
+            ],
+            "id": "../followers",
+            "attributedTo": "/sally"
+        });
+        let expected = json!([{
+            "@id": "https://example.com/sally/followers",
+            "https://www.w3.org/ns/activitystreams#attributedTo": [
+                {"@id": "https://example.com/sally"}
+            ]
+        }]);
+        assert_eq!(expand(&document).unwrap(), expected);
+    }
+
+    #[test]
+    fn normalize_prefixes_and_iris() {
+        let document = json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "@type": "as:Note",
+            "@id": "http://example.org/notes/1",
+            "https://www.w3.org/ns/activitystreams#content": "Hello",
+            "as:attributedTo": "http://example.org/alice",
+            "as:to": {"@id": "https://www.w3.org/ns/activitystreams#Public"},
+            "as:tag": [{"type": "Mention", "href": "http://example.org/bob"}],
+            "https://www.w3.org/ns/activitystreams#published": "2015-01-25T12:34:56Z"
+        });
+        let expected = json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "type": "Note",
+            "id": "http://example.org/notes/1",
+            "content": "Hello",
+            "attributedTo": "http://example.org/alice",
+            "to": "https://www.w3.org/ns/activitystreams#Public",
+            "tag": {"type": "Mention", "href": "http://example.org/bob"},
+            "published": "2015-01-25T12:34:56Z"
+        });
+        assert_eq!(normalize(&document).unwrap(), expected);
+    }
+
+    #[test]
+    fn normalize_aliases() {
+        let json = r#"{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    {
+      "@language": "en",
+      "kind": "@type",
+      "body": "as:content",
+      "author": {"@id": "as:attributedTo", "@type": "@id"},
+      "sensitive": "as:sensitive"
+    }
+  ],
+  "kind": "Note",
+  "body": "Hello",
+  "author": "http://example.org/alice",
+  "sensitive": true,
+  "contentMap": {"fi": "Hei"}
+}"#;
+        let document: Document<Note> = Document::from_json_ld(String::from(json)).unwrap();
+        assert_eq!(document.context.language(), Some("en"));
+        let note = document.object;
+        assert!(note.has_type("Note"));
+        assert_eq!(note.content_for(&["en"]), Some("Hello"));
+        assert_eq!(note.content_for(&["fi"]), Some("Hei"));
+        assert!(note.attributed_to.is_some());
+    }
+
+    #[test]
+    fn normalize_extension_terms() {
+        let json = r#"{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    {
+      "toot": "http://joinmastodon.org/ns#",
+      "pinned": {"@id": "toot:featured", "@type": "@id"},
+      "blurhash": "toot:blurhash"
+    }
+  ],
+  "type": "Person",
+  "id": "http://example.org/alice",
+  "inbox": "http://example.org/alice/inbox",
+  "pinned": "http://example.org/alice/featured",
+  "toot:discoverable": true,
+  "blurhash": "UFF=#1-;~qIU9FRjt7WB4n%Mt7xu%MWBM{Rj"
+}"#;
+        let document: Document<Person<MastodonActorExtension>> =
+            Document::from_json(String::from(json)).unwrap();
+        let person = &document.object;
+        assert_eq!(
+            person.extension.featured,
+            Some("http://example.org/alice/featured".parse::<Iri>().unwrap())
+        );
+        assert_eq!(person.extension.discoverable, Some(true));
+        // Terms only the document defines are kept as they are.
+        assert!(person.extensions.contains_key("blurhash"));
+        assert_eq!(
+            document
+                .context
+                .term("featured")
+                .and_then(TermDefinition::id),
+            Some("toot:featured")
+        );
+    }
+
+    #[test]
+    fn normalize_legacy_vocab() {
+        let json = r#"{
+  "@context": {
+    "@vocab": "https://www.w3.org/ns/activitystreams"
+  },
+  "type": "Person",
+  "id": "http://example.org/alice",
+  "name": "Alice",
+  "inbox": "http://example.org/alice/inbox"
+}"#;
+        let document: Document<Person> = Document::from_json_ld(String::from(json)).unwrap();
+        assert_eq!(document.object.name, Some(String::from("Alice")));
+        assert_eq!(
+            document.object.inbox,
+            Some("http://example.org/alice/inbox".parse::<Iri>().unwrap())
+        );
+    }
+
+    #[test]
+    fn normalize_security_context() {
+        let document = json!({
+            "@context": [
+                "https://www.w3.org/ns/activitystreams",
+                "https://w3id.org/security/v1"
+            ],
+            "type": "Person",
+            "id": "http://example.org/alice",
+            "https://w3id.org/security#publicKey": {
+                "id": "http://example.org/alice#main-key",
+                "sec:owner": "http://example.org/alice",
+                "publicKeyPem": "-----BEGIN PUBLIC KEY-----"
+            }
+        });
+        let normalized = normalize(&document).unwrap();
+        assert_eq!(
+            normalized["publicKey"]["owner"],
+            json!("http://example.org/alice")
+        );
+        assert_eq!(
+            normalized["publicKey"]["publicKeyPem"],
+            json!("-----BEGIN PUBLIC KEY-----")
+        );
+    }
+
+    #[test]
+    fn unknown_remote_context() {
+        let document = json!({
+            "@context": "https://example.org/unknown-context",
+            "type": "Note"
+        });
+        assert!(matches!(
+            normalize(&document),
+            Err(Error::Context(ContextError::LoadingContextFailed(iri))) if iri == "https://example.org/unknown-context"
+        ));
+        let result: Result<Document<Object<Null>>> = Document::from_json_ld(document.to_string());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn bundled_contexts() {
+        let loader = BundledContextLoader::new();
+        for iri in [
+            "https://www.w3.org/ns/activitystreams",
+            "http://www.w3.org/ns/activitystreams",
+            "https://www.w3.org/ns/activitystreams.jsonld",
+            "https://w3id.org/security/v1",
+            "https://w3id.org/security/v2",
+            "https://w3id.org/security/multikey/v1",
+            "https://w3id.org/security/data-integrity/v1",
+            "https://www.w3.org/ns/did/v1",
+        ] {
+            let document = loader.load(iri).unwrap();
+            assert!(document.get("@context").is_some(), "{iri}");
+            ActiveContext::parse(&Value::from(iri), &loader).unwrap();
+        }
+
+        let context = ActiveContext::parse(
+            &json!([
+                "https://www.w3.org/ns/activitystreams",
+                "https://w3id.org/security/v1"
+            ]),
+            &loader,
+        )
+        .unwrap();
+        assert_eq!(
+            context.expand_term("publicKeyPem"),
+            Some("https://w3id.org/security#publicKeyPem")
+        );
+        assert_eq!(
+            context.expand_term("Note"),
+            Some("https://www.w3.org/ns/activitystreams#Note")
+        );
+    }
+
+    #[test]
+    fn register_context() {
+        let loader = BundledContextLoader::new();
+        assert!(loader.load("https://gotosocial.org/ns").is_err());
+        loader.register(
+            "https://gotosocial.org/ns",
+            json!({
+                "@context": {
+                    "gts": "https://gotosocial.org/ns#",
+                    "interactionPolicy": {"@id": "gts:interactionPolicy", "@type": "@id"}
+                }
+            }),
+        );
+
+        let document = json!({
+            "@context": [
+                "https://www.w3.org/ns/activitystreams",
+                "https://gotosocial.org/ns"
+            ],
+            "type": "Note",
+            "https://gotosocial.org/ns#interactionPolicy": {
+                "https://gotosocial.org/ns#canLike": {"@id": "https://www.w3.org/ns/activitystreams#Public"}
+            }
+        });
+        let normalized = normalize_with(&document, &loader).unwrap();
+        assert_eq!(
+            normalized["@context"],
+            json!([
+                "https://www.w3.org/ns/activitystreams",
+                "https://gotosocial.org/ns"
+            ])
+        );
+        assert_eq!(
+            normalized["interactionPolicy"]["gts:canLike"],
+            json!({"id": "https://www.w3.org/ns/activitystreams#Public"})
+        );
+    }
+
+    #[test]
+    fn fetch_unknown_contexts() {
+        struct Fetcher;
+
+        impl ContextLoader for Fetcher {
+            fn load(&self, iri: &str) -> Result<Value> {
+                match iri {
+                    "https://example.org/context" => Ok(
+                        json!({"@context": {"body": "https://www.w3.org/ns/activitystreams#content"}}),
+                    ),
+                    _ => Err(ContextError::LoadingContextFailed(iri.to_string()).into()),
+                }
+            }
+        }
+
+        let loader = BundledContextLoader::with_fetcher(Fetcher);
+        let json = r#"{
+  "@context": ["https://www.w3.org/ns/activitystreams", "https://example.org/context"],
+  "type": "Note",
+  "body": "Hello"
+}"#;
+        let document: Document<Note> =
+            Document::from_json_ld_with(String::from(json), &loader).unwrap();
+        assert_eq!(document.object.content, Some(String::from("Hello")));
+        assert!(loader.load("https://example.org/other").is_err());
+    }
+}
@@ -0,0 +1,382 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A [BCP47](https://www.rfc-editor.org/info/bcp47) language tag such as `en`,
+/// `fi` or `zh-Hant-TW`. Tags are validated against the RFC 5646 syntax and
+/// normalized to their conventional casing (`en-us` becomes `en-US`), so two
+/// tags compare equal regardless of how they were written.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct LanguageTag(String);
+
+impl LanguageTag {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The primary language subtag, e.g. `zh` for `zh-Hant-TW`.
+    pub fn primary_language(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+
+    /// Whether this tag falls within the given language range, i.e. is equal
+    /// to it or starts with it followed by further subtags (`en` matches
+    /// `en-GB`). Comparison is case-insensitive.
+    pub fn matches(&self, range: &str) -> bool {
+        let tag = self.0.as_bytes();
+        let range = range.as_bytes();
+        range == b"*"
+            || (tag.len() >= range.len()
+                && tag[..range.len()].eq_ignore_ascii_case(range)
+                && (tag.len() == range.len() || tag[range.len()] == b'-'))
+    }
+}
+
+/// Error returned when a string is not a well-formed BCP47 language tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidLanguageTag(pub String);
+
+impl fmt::Display for InvalidLanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid language tag: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidLanguageTag {}
+
+impl FromStr for LanguageTag {
+    type Err = InvalidLanguageTag;
+
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        normalize(tag)
+            .map(LanguageTag)
+            .ok_or_else(|| InvalidLanguageTag(tag.to_string()))
+    }
+}
+
+impl TryFrom<String> for LanguageTag {
+    type Error = InvalidLanguageTag;
+
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        tag.parse()
+    }
+}
+
+impl From<LanguageTag> for String {
+    fn from(tag: LanguageTag) -> Self {
+        tag.0
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn is_alpha(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_digit(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_alphanumeric(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+fn title_case(subtag: &str) -> String {
+    let lower = subtag.to_ascii_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => lower,
+    }
+}
+
+/// Checks `tag` against the RFC 5646 `Language-Tag` production and returns
+/// it in its conventional casing. Grandfathered tags other than the `i-`
+/// forms are not recognised.
+fn normalize(tag: &str) -> Option<String> {
+    let subtags: Vec<&str> = tag.split('-').collect();
+    if subtags
+        .iter()
+        .any(|s| s.is_empty() || s.len() > 8 || !is_alphanumeric(s))
+    {
+        return None;
+    }
+
+    let mut normalized: Vec<String> = Vec::with_capacity(subtags.len());
+    let mut rest = subtags.as_slice();
+
+    let first = rest[0];
+    if first.eq_ignore_ascii_case("x") || first.eq_ignore_ascii_case("i") {
+        // Private use ("x-whatever") or an irregular grandfathered tag.
+        if rest.len() < 2 {
+            return None;
+        }
+        return Some(tag.to_ascii_lowercase());
+    }
+
+    // language: 2-3 letters (optionally followed by up to three extlangs),
+    // 4 letters (reserved) or 5-8 letters (registered).
+    if first.len() < 2 || !is_alpha(first) {
+        return None;
+    }
+    normalized.push(first.to_ascii_lowercase());
+    rest = &rest[1..];
+    if first.len() <= 3 {
+        let mut extlangs = 0;
+        while extlangs < 3 && !rest.is_empty() && rest[0].len() == 3 && is_alpha(rest[0]) {
+            normalized.push(rest[0].to_ascii_lowercase());
+            rest = &rest[1..];
+            extlangs += 1;
+        }
+    }
+
+    // script
+    if !rest.is_empty() && rest[0].len() == 4 && is_alpha(rest[0]) {
+        normalized.push(title_case(rest[0]));
+        rest = &rest[1..];
+    }
+
+    // region
+    if !rest.is_empty()
+        && ((rest[0].len() == 2 && is_alpha(rest[0])) || (rest[0].len() == 3 && is_digit(rest[0])))
+    {
+        normalized.push(rest[0].to_ascii_uppercase());
+        rest = &rest[1..];
+    }
+
+    // variants
+    while !rest.is_empty()
+        && (rest[0].len() >= 5 || (rest[0].len() == 4 && rest[0].as_bytes()[0].is_ascii_digit()))
+    {
+        normalized.push(rest[0].to_ascii_lowercase());
+        rest = &rest[1..];
+    }
+
+    // extensions
+    while !rest.is_empty() && rest[0].len() == 1 && !rest[0].eq_ignore_ascii_case("x") {
+        normalized.push(rest[0].to_ascii_lowercase());
+        rest = &rest[1..];
+        let mut count = 0;
+        while !rest.is_empty() && rest[0].len() >= 2 {
+            normalized.push(rest[0].to_ascii_lowercase());
+            rest = &rest[1..];
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+    }
+
+    // private use
+    if !rest.is_empty() && rest[0].eq_ignore_ascii_case("x") {
+        if rest.len() < 2 {
+            return None;
+        }
+        normalized.extend(rest.iter().map(|s| s.to_ascii_lowercase()));
+        rest = &[];
+    }
+
+    if !rest.is_empty() {
+        return None;
+    }
+    Some(normalized.join("-"))
+}
+
+/// A natural language map as used by `contentMap`, `nameMap` and
+/// `summaryMap`: one value per language, keyed by [LanguageTag].
+/// <https://www.w3.org/TR/activitystreams-core/#naturalLanguageValues>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct LanguageMap(BTreeMap<LanguageTag, String>);
+
+impl LanguageMap {
+    pub fn new() -> Self {
+        LanguageMap(BTreeMap::new())
+    }
+
+    /// Sets the value for `language`, returning the previous one if any.
+    pub fn insert(&mut self, language: LanguageTag, value: String) -> Option<String> {
+        self.0.insert(language, value)
+    }
+
+    /// Returns the value for exactly `language` (compared case-insensitively).
+    pub fn get(&self, language: &str) -> Option<&str> {
+        let language: LanguageTag = language.parse().ok()?;
+        self.0.get(&language).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&LanguageTag, &str)> {
+        self.0.iter().map(|(tag, value)| (tag, value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the value best matching `languages`, given in order of
+    /// preference. For each preferred language an exact match is tried first,
+    /// then the language with trailing subtags removed (`en-US` falls back to
+    /// `en`), then any more specific tag within it (`en` picks `en-GB`).
+    pub fn resolve(&self, languages: &[&str]) -> Option<&str> {
+        languages.iter().find_map(|language| self.lookup(language))
+    }
+
+    fn lookup(&self, language: &str) -> Option<&str> {
+        let mut range = language;
+        loop {
+            if let Some(value) = self.get(range) {
+                return Some(value);
+            }
+            match range.rfind('-') {
+                Some(end) => range = &range[..end],
+                None => break,
+            }
+        }
+        self.iter()
+            .find(|(tag, _)| tag.matches(language))
+            .map(|(_, value)| value)
+    }
+}
+
+impl FromIterator<(LanguageTag, String)> for LanguageMap {
+    fn from_iter<I: IntoIterator<Item = (LanguageTag, String)>>(iter: I) -> Self {
+        LanguageMap(iter.into_iter().collect())
+    }
+}
+
+/// Resolves a natural language value from its `*Map` form and plain form.
+/// The plain value is taken to be in `default_language` (normally the
+/// context's `@language`), which is also the last resort looked up in the
+/// map.
+pub(crate) fn resolve<'a>(
+    map: Option<&'a LanguageMap>,
+    plain: Option<&'a str>,
+    languages: &[&str],
+    default_language: Option<&str>,
+) -> Option<&'a str> {
+    for language in languages {
+        if let Some(value) = map.and_then(|map| map.lookup(language)) {
+            return Some(value);
+        }
+        if let (Some(plain), Some(default_language)) = (plain, default_language) {
+            if default_language
+                .parse::<LanguageTag>()
+                .is_ok_and(|tag| tag.matches(language))
+            {
+                return Some(plain);
+            }
+        }
+    }
+    plain.or_else(|| {
+        map.and_then(|map| map.resolve(&default_language.into_iter().collect::<Vec<_>>()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_language_tags() {
+        for (tag, normalized) in [
+            ("en", "en"),
+            ("EN-us", "en-US"),
+            ("zh-hant-tw", "zh-Hant-TW"),
+            ("zh-yue-HK", "zh-yue-HK"),
+            ("es-419", "es-419"),
+            ("sl-rozaj-biske", "sl-rozaj-biske"),
+            ("de-CH-1901", "de-CH-1901"),
+            ("en-US-u-islamcal", "en-US-u-islamcal"),
+            ("de-CH-x-phonebk", "de-CH-x-phonebk"),
+            ("x-whatever", "x-whatever"),
+            ("i-klingon", "i-klingon"),
+        ] {
+            assert_eq!(tag.parse::<LanguageTag>().unwrap().as_str(), normalized);
+        }
+    }
+
+    #[test]
+    fn reject_malformed_language_tags() {
+        for tag in [
+            "",
+            "e",
+            "en-",
+            "-en",
+            "en--US",
+            "en_US",
+            "a-DE",
+            "en-a",
+            "en-x",
+            "toolongtag",
+            "de-419-DE",
+            "fi-ü",
+        ] {
+            assert!(
+                tag.parse::<LanguageTag>().is_err(),
+                "{tag} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn language_map_serde() {
+        let map: LanguageMap =
+            serde_json::from_str(r#"{"en": "A simple note", "zh-hans": "一段简单的笔记"}"#)
+                .unwrap();
+        assert_eq!(map.get("EN"), Some("A simple note"));
+        assert_eq!(map.get("zh-Hans"), Some("一段简单的笔记"));
+        assert_eq!(
+            serde_json::to_string(&map).unwrap(),
+            r#"{"en":"A simple note","zh-Hans":"一段简单的笔记"}"#
+        );
+
+        let result: serde_json::Result<LanguageMap> = serde_json::from_str(r#"{"not a tag": "x"}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn resolve_language_map() {
+        let map: LanguageMap = [("en-GB", "colour"), ("fi", "väri"), ("sv", "färg")]
+            .into_iter()
+            .map(|(tag, value)| (tag.parse().unwrap(), value.to_string()))
+            .collect();
+        assert_eq!(map.resolve(&["fi", "en"]), Some("väri"));
+        assert_eq!(map.resolve(&["fi-FI"]), Some("väri"));
+        assert_eq!(map.resolve(&["de", "en"]), Some("colour"));
+        assert_eq!(map.resolve(&["de"]), None);
+    }
+
+    #[test]
+    fn resolve_with_plain_value() {
+        let map: LanguageMap = [("fi", "hei")]
+            .into_iter()
+            .map(|(tag, value)| (tag.parse().unwrap(), value.to_string()))
+            .collect();
+        assert_eq!(
+            resolve(Some(&map), Some("hello"), &["en", "fi"], Some("en")),
+            Some("hello")
+        );
+        assert_eq!(
+            resolve(Some(&map), Some("hello"), &["fi", "en"], Some("en")),
+            Some("hei")
+        );
+        assert_eq!(
+            resolve(Some(&map), Some("hello"), &["de"], None),
+            Some("hello")
+        );
+        assert_eq!(resolve(Some(&map), None, &["de"], Some("fi")), Some("hei"));
+        assert_eq!(resolve(None, None, &["de"], Some("fi")), None);
+    }
+}
@@ -1,37 +1,60 @@
+pub mod any;
 pub mod core;
+mod error;
 pub mod extended;
-
+pub mod inbox;
+pub mod iri;
+pub mod jsonld;
+pub mod language;
+pub mod outbox;
+#[cfg(feature = "signatures")]
+pub mod signature;
+pub mod store;
+pub mod validate;
+
+pub use error::{Error, Result};
 use serde::{de::DeserializeOwned, Serialize};
-use serde_json::Result;
 
 pub trait Serde
 where
     Self: Serialize + DeserializeOwned,
 {
     fn to_json(&self) -> Result<String> {
-        let serialized = serde_json::to_string(&self);
-        println!("serialized = {:?}", serialized);
-        serialized
+        serde_json::to_string(&self).map_err(Error::Serialize)
     }
 
     fn to_json_pretty(&self) -> Result<String> {
-        let serialized = serde_json::to_string_pretty(&self);
-        println!("serialized = {:?}", serialized);
-        serialized
+        serde_json::to_string_pretty(&self).map_err(Error::Serialize)
     }
 
+    /// Parses `json`. Errors carry the JSON path of the offending value.
     fn from_json(json: String) -> Result<Self> {
-        serde_json::from_str(json.as_str())
+        error::from_str(json.as_str())
     }
+
+    /// Adds the term definitions of the [core::Extension] the type carries,
+    /// if any, to `context`; see [core::Document::new].
+    fn extend_context(_context: &mut core::Context) {}
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{DateTime, NaiveDate, Utc};
+    use chrono::{TimeZone, Utc};
     use pretty_assertions::assert_eq;
 
-    use crate::{core::*, extended::*};
+    use crate::core::Document;
+    use crate::{core::*, extended::*, iri::Iri};
+
+    /// Parses `listing` into a [Document] and asserts that serializing it again
+    /// yields the same JSON, ignoring formatting and member order.
+    fn round_trip<T: Serde>(listing: &str) -> T {
+        let document: Document<T> = Document::from_json(String::from(listing)).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(listing).unwrap();
+        let actual: serde_json::Value = serde_json::from_str(&document.to_json().unwrap()).unwrap();
+        assert_eq!(actual, expected);
+        document.object
+    }
 
     // A set of tests from https://www.w3.org/TR/activitystreams-vocabulary examples
     #[test]
@@ -43,10 +66,10 @@ mod tests {
           "name": "A Simple, non-specific object"
         }"#;
         let object: Object<Null> = Document::from_json(String::from(listing)).unwrap().object;
-        assert_eq!(object.object_type, Some(String::from("Object")));
+        assert_eq!(object.object_type, Some(ObjectType::from("Object")));
         assert_eq!(
             object.id,
-            Some(String::from("http://www.test.example/object/1"))
+            Some("http://www.test.example/object/1".parse::<Iri>().unwrap())
         );
         assert_eq!(
             object.name,
@@ -70,7 +93,12 @@ mod tests {
         let link: Link = Document::from_json(String::from(listing)).unwrap().object;
         assert_eq!(link.link_type, "Link");
         assert_eq!(link.href.href, "http://example.org/abc");
-        assert_eq!(link.hreflang, Some(String::from("en")));
+        assert_eq!(
+            link.hreflang
+                .as_ref()
+                .map(crate::language::LanguageTag::as_str),
+            Some("en")
+        );
         assert_eq!(link.href.media_type, Some(String::from("text/html")));
         assert_eq!(link.name, Some(String::from("An example link")));
     }
@@ -94,7 +122,7 @@ mod tests {
       "#;
 
         let activity: Activity = Document::from_json(String::from(listing)).unwrap().object;
-        assert_eq!(activity.object_type, Some(String::from("Activity")));
+        assert_eq!(activity.object_type, Some(ObjectType::from("Activity")));
         assert_eq!(
             activity.summary,
             Some(String::from("Sally did something to a note"))
@@ -102,12 +130,14 @@ mod tests {
 
         assert!(activity.actor.is_some());
         let actor = activity.actor.unwrap();
-        assert_eq!(actor.object_type, Some(String::from("Person")));
+        let actor = actor.first().unwrap().as_object().unwrap();
+        assert_eq!(actor.object_type, Some(ObjectType::from("Person")));
         assert_eq!(actor.name, Some(String::from("Sally")));
 
         assert!(activity.object.is_some());
         let object = activity.object.unwrap();
-        assert_eq!(object.object_type, Some(String::from("Note")));
+        let object = object.first().unwrap().as_object().unwrap();
+        assert_eq!(object.object_type, Some(ObjectType::from("Note")));
         assert_eq!(object.name, Some(String::from("A Note")));
     }
 
@@ -131,17 +161,19 @@ mod tests {
 
         let activity: IntransitiveActivity =
             Document::from_json(String::from(listing)).unwrap().object;
-        assert_eq!(activity.object_type, Some(String::from("Travel")));
+        assert_eq!(activity.object_type, Some(ObjectType::from("Travel")));
         assert_eq!(activity.summary, Some(String::from("Sally went to work")));
 
         assert!(activity.actor.is_some());
-        let actor = activity.actor.as_ref().unwrap();
-        assert_eq!(actor.object_type, Some(String::from("Person")));
+        let actor = activity.actor.as_ref().unwrap().first().unwrap();
+        let actor = actor.as_object().unwrap();
+        assert_eq!(actor.object_type, Some(ObjectType::from("Person")));
         assert_eq!(actor.name, Some(String::from("Sally")));
 
         assert!(activity.target.is_some());
-        let target = activity.target.as_ref().unwrap();
-        assert_eq!(target.object_type, Some(String::from("Place")));
+        let target = activity.target.as_ref().unwrap().first().unwrap();
+        let target = target.as_object().unwrap();
+        assert_eq!(target.object_type, Some(ObjectType::from("Place")));
         assert_eq!(target.name, Some(String::from("Work")));
     }
 
@@ -168,15 +200,15 @@ mod tests {
 
         let collection: Collection<Object<Null>> =
             Document::from_json(String::from(listing)).unwrap().object;
-        assert_eq!(collection.object_type, Some(String::from("Collection")));
+        assert_eq!(collection.object_type, Some(ObjectType::from("Collection")));
         assert_eq!(collection.summary, Some(String::from("Sally's notes")));
         assert_eq!(collection.total_items, Some(2));
 
         let items = &collection.items;
         assert_eq!(items.len(), collection.total_items.unwrap());
-        assert_eq!(items[0].object_type, Some(String::from("Note")));
+        assert_eq!(items[0].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[0].name, Some(String::from("A Simple Note")));
-        assert_eq!(items[1].object_type, Some(String::from("Note")));
+        assert_eq!(items[1].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[1].name, Some(String::from("Another Simple Note")));
     }
 
@@ -204,16 +236,16 @@ mod tests {
             Document::from_json(String::from(listing)).unwrap().object;
         assert_eq!(
             collection.object_type,
-            Some(String::from("OrderedCollection"))
+            Some(ObjectType::from("OrderedCollection"))
         );
         assert_eq!(collection.summary, Some(String::from("Sally's notes")));
         assert_eq!(collection.total_items, Some(2));
 
         let items = &collection.ordered_items;
         assert_eq!(items.len(), collection.total_items.unwrap());
-        assert_eq!(items[0].object_type, Some(String::from("Note")));
+        assert_eq!(items[0].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[0].name, Some(String::from("A Simple Note")));
-        assert_eq!(items[1].object_type, Some(String::from("Note")));
+        assert_eq!(items[1].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[1].name, Some(String::from("Another Simple Note")));
     }
 
@@ -242,26 +274,23 @@ mod tests {
             Document::from_json(String::from(listing)).unwrap().object;
         assert_eq!(
             collection_page.object_type,
-            Some(String::from("CollectionPage"))
+            Some(ObjectType::from("CollectionPage"))
         );
         assert_eq!(
             collection_page.id,
-            Some("http://example.org/foo?page=1".to_string())
+            Some("http://example.org/foo?page=1".parse::<Iri>().unwrap())
         );
         assert_eq!(
             collection_page.summary,
             Some(String::from("Page 1 of Sally's notes"))
         );
-        assert_eq!(
-            collection_page.part_of,
-            "http://example.org/foo".to_string()
-        );
+        assert_eq!(collection_page.part_of, "http://example.org/foo");
         assert_eq!(collection_page.total_items, None);
 
         let items = &collection_page.items;
-        assert_eq!(items[0].object_type, Some(String::from("Note")));
+        assert_eq!(items[0].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[0].name, Some(String::from("A Simple Note")));
-        assert_eq!(items[1].object_type, Some(String::from("Note")));
+        assert_eq!(items[1].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[1].name, Some(String::from("Another Simple Note")));
     }
 
@@ -290,29 +319,190 @@ mod tests {
             Document::from_json(String::from(listing)).unwrap().object;
         assert_eq!(
             collection_page.object_type,
-            Some(String::from("OrderedCollectionPage"))
+            Some(ObjectType::from("OrderedCollectionPage"))
         );
         assert_eq!(
             collection_page.id,
-            Some("http://example.org/foo?page=1".to_string())
+            Some("http://example.org/foo?page=1".parse::<Iri>().unwrap())
         );
         assert_eq!(
             collection_page.summary,
             Some(String::from("Page 1 of Sally's notes"))
         );
-        assert_eq!(
-            collection_page.part_of,
-            "http://example.org/foo".to_string()
-        );
+        assert_eq!(collection_page.part_of, "http://example.org/foo");
         assert_eq!(collection_page.total_items, None);
 
         let items = &collection_page.ordered_items;
-        assert_eq!(items[0].object_type, Some(String::from("Note")));
+        assert_eq!(items[0].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[0].name, Some(String::from("A Simple Note")));
-        assert_eq!(items[1].object_type, Some(String::from("Note")));
+        assert_eq!(items[1].object_type, Some(ObjectType::from("Note")));
         assert_eq!(items[1].name, Some(String::from("Another Simple Note")));
     }
 
+    #[test]
+    fn example_47() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "summary": "Sally is an acquaintance of John",
+  "type": "Relationship",
+  "subject": {
+    "type": "Person",
+    "name": "Sally"
+  },
+  "relationship": "http://purl.org/vocab/relationship/acquaintanceOf",
+  "object": {
+    "type": "Person",
+    "name": "John"
+  }
+}"#;
+        let relationship: Relationship = round_trip(listing);
+        assert_eq!(
+            relationship.object_type,
+            Some(ObjectType::from("Relationship"))
+        );
+        assert_eq!(
+            relationship
+                .subject
+                .as_ref()
+                .and_then(Reference::as_object)
+                .unwrap()
+                .name,
+            Some(String::from("Sally"))
+        );
+        assert_eq!(
+            relationship
+                .relationship
+                .as_ref()
+                .and_then(OneOrMany::first)
+                .and_then(Reference::as_iri)
+                .map(Iri::as_str),
+            Some("http://purl.org/vocab/relationship/acquaintanceOf")
+        );
+        assert_eq!(
+            relationship
+                .object
+                .as_ref()
+                .and_then(OneOrMany::first)
+                .and_then(Reference::as_object)
+                .unwrap()
+                .name,
+            Some(String::from("John"))
+        );
+    }
+
+    #[test]
+    fn example_48() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Article",
+  "name": "What a Crazy Day I Had",
+  "content": "<div>... you will never believe ...</div>",
+  "attributedTo": "http://sally.example.org"
+}"#;
+        let article: Article = round_trip(listing);
+        assert_eq!(article.name, Some(String::from("What a Crazy Day I Had")));
+        assert_eq!(
+            article
+                .attributed_to
+                .as_ref()
+                .and_then(OneOrMany::first)
+                .and_then(Reference::as_iri)
+                .map(Iri::as_str),
+            Some("http://sally.example.org")
+        );
+    }
+
+    #[test]
+    fn example_49() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Document",
+  "name": "4Q Sales Forecast",
+  "url": "http://example.org/4q-sales-forecast.pdf"
+}"#;
+        let document: crate::extended::Document = round_trip(listing);
+        assert_eq!(document.object_type, Some(ObjectType::from("Document")));
+        assert_eq!(document.name, Some(String::from("4Q Sales Forecast")));
+        assert_eq!(
+            document
+                .url
+                .as_ref()
+                .and_then(OneOrMany::first)
+                .and_then(LinkReference::as_iri)
+                .map(Iri::as_str),
+            Some("http://example.org/4q-sales-forecast.pdf")
+        );
+    }
+
+    #[test]
+    fn example_50() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Audio",
+  "name": "Interview With A Famous Technologist",
+  "url": {
+    "type": "Link",
+    "href": "http://example.org/podcast.mp3",
+    "mediaType": "audio/mp3"
+  }
+}"#;
+        let audio: Audio = round_trip(listing);
+        let url = audio.url.as_ref().unwrap().first().unwrap();
+        assert_eq!(
+            url.as_link().unwrap().href.media_type,
+            Some(String::from("audio/mp3"))
+        );
+    }
+
+    #[test]
+    fn example_51() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Image",
+  "name": "Cat Jumping on Wagon",
+  "url": [
+    {
+      "type": "Link",
+      "href": "http://example.org/image.jpeg",
+      "mediaType": "image/jpeg"
+    },
+    {
+      "type": "Link",
+      "href": "http://example.org/image.png",
+      "mediaType": "image/png"
+    }
+  ]
+}"#;
+        let image: Image = round_trip(listing);
+        let hrefs: Vec<&str> = image
+            .url
+            .iter()
+            .flatten()
+            .filter_map(LinkReference::as_link)
+            .map(|link| link.href.href.as_str())
+            .collect();
+        assert_eq!(
+            hrefs,
+            vec![
+                "http://example.org/image.jpeg",
+                "http://example.org/image.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn example_52() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Video",
+  "name": "Puppy Plays With Ball",
+  "url": "http://example.org/video.mkv",
+  "duration": "PT2H"
+}"#;
+        let video: Video = round_trip(listing);
+        assert_eq!(video.duration, Some(String::from("PT2H")));
+    }
+
     #[test]
     fn example_53() {
         let listing = r#"{
@@ -323,7 +513,7 @@ mod tests {
         }"#;
         let document: Document<Note> = Document::from_json(String::from(listing)).unwrap();
         let note = document.object;
-        assert_eq!(note.object_type, Some(String::from("Note")));
+        assert_eq!(note.object_type, Some(ObjectType::from("Note")));
         assert_eq!(note.name, Some(String::from("A Word of Warning")));
         assert_eq!(
             note.content,
@@ -333,6 +523,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn example_54() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Page",
+  "name": "Omaha Weather Report",
+  "url": "http://example.org/weather-in-omaha.html"
+}"#;
+        let page: Page = round_trip(listing);
+        assert_eq!(page.object_type, Some(ObjectType::from("Page")));
+        assert_eq!(page.name, Some(String::from("Omaha Weather Report")));
+    }
+
+    #[test]
+    fn example_55() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Event",
+  "name": "Going-Away Party for Jim",
+  "startTime": "2014-12-31T23:00:00-08:00",
+  "endTime": "2015-01-01T06:00:00-08:00"
+}"#;
+        let event: Event = Document::from_json(String::from(listing)).unwrap().object;
+        assert_eq!(event.name, Some(String::from("Going-Away Party for Jim")));
+        assert_eq!(
+            event.start_time,
+            Some(Utc.with_ymd_and_hms(2015, 1, 1, 7, 0, 0).unwrap())
+        );
+        assert_eq!(
+            event.end_time,
+            Some(Utc.with_ymd_and_hms(2015, 1, 1, 14, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn example_56() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Place",
+  "name": "Work"
+}"#;
+        let place: Place = round_trip(listing);
+        assert_eq!(place.object_type, Some(ObjectType::from("Place")));
+        assert_eq!(place.name, Some(String::from("Work")));
+        assert_eq!(place.latitude, None);
+    }
+
+    #[test]
+    fn example_57() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Place",
+  "name": "Fresno Area",
+  "latitude": 36.75,
+  "longitude": 119.7667,
+  "radius": 15,
+  "units": "miles"
+}"#;
+        let place: Place = Document::from_json(String::from(listing)).unwrap().object;
+        assert_eq!(place.name, Some(String::from("Fresno Area")));
+        assert_eq!(place.latitude, Some(36.75));
+        assert_eq!(place.longitude, Some(119.7667));
+        assert_eq!(place.radius, Some(15.0));
+        assert_eq!(place.units, Some(String::from("miles")));
+    }
+
+    #[test]
+    fn example_59() {
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Profile",
+  "summary": "Sally's Profile",
+  "describes": {
+    "type": "Person",
+    "name": "Sally Smith"
+  }
+}"#;
+        let profile: Profile = round_trip(listing);
+        assert_eq!(profile.summary, Some(String::from("Sally's Profile")));
+        let describes = profile.describes.as_ref().unwrap().as_object().unwrap();
+        assert_eq!(describes.object_type, Some(ObjectType::from("Person")));
+        assert_eq!(describes.name, Some(String::from("Sally Smith")));
+    }
+
+    #[test]
+    fn example_60() {
+        // The Tombstone member of example 60's "orderedItems".
+        let listing = r#"{
+  "@context": {"@vocab": "https://www.w3.org/ns/activitystreams"},
+  "type": "Tombstone",
+  "formerType": "Image",
+  "url": "http://image.example/2",
+  "deleted": "2016-03-17T00:00:00Z"
+}"#;
+        let tombstone: Tombstone = round_trip(listing);
+        assert_eq!(tombstone.former_type, Some(String::from("Image")));
+        assert_eq!(
+            tombstone.deleted,
+            Some(Utc.with_ymd_and_hms(2016, 3, 17, 0, 0, 0).unwrap())
+        );
+    }
+
     #[test]
     fn example_69() {
         let listing = r#"{
@@ -350,7 +642,7 @@ mod tests {
         let document: Document<Object<Null>> = Document::from_json(String::from(listing)).unwrap();
         let object = document.object;
         assert_eq!(object.name, Some(String::from("Holiday announcement")));
-        assert_eq!(object.object_type, Some(String::from("Note")));
+        assert_eq!(object.object_type, Some(ObjectType::from("Note")));
         assert_eq!(
             object.content,
             Some(String::from(
@@ -359,9 +651,10 @@ mod tests {
         );
         assert!(object.audience.is_some());
         let audience = object.audience.unwrap();
+        let audience = audience.first().unwrap().as_object().unwrap();
         assert_eq!(
             audience.object_type,
-            Some(String::from("http://example.org/Organization"))
+            Some(ObjectType::from("http://example.org/Organization"))
         );
         assert_eq!(audience.name, Some(String::from("ExampleCo LLC")));
     }
@@ -379,7 +672,7 @@ mod tests {
         let document: Document<Object<Null>> = Document::from_json(String::from(listing)).unwrap();
         let object = document.object;
         assert_eq!(object.summary, Some(String::from("A simple note")));
-        assert_eq!(object.object_type, Some(String::from("Note")));
+        assert_eq!(object.object_type, Some(ObjectType::from("Note")));
         assert_eq!(object.content, Some(String::from("A <em>simple</em> note")));
     }
 
@@ -396,7 +689,7 @@ mod tests {
         let document: Document<Object<Null>> = Document::from_json(String::from(listing)).unwrap();
         let object = document.object;
         assert_eq!(object.summary, Some(String::from("A simple <em>note</em>")));
-        assert_eq!(object.object_type, Some(String::from("Note")));
+        assert_eq!(object.object_type, Some(ObjectType::from("Note")));
         assert_eq!(object.name, Some(String::from("Cane Sugar Processing")));
     }
 
@@ -410,13 +703,10 @@ mod tests {
                 String::from("Martin created an image"),
             )
             .actor(
-                ActorBuilder::new(String::from("Person")).id("http://www.test.example/martin"
-                    .parse::<http::Uri>()
-                    .unwrap()),
-            )
-            .object(
-                ObjectBuilder::new().id("http://example.org/foo.jpg".parse::<http::Uri>().unwrap()),
+                ActorBuilder::new(String::from("Person"))
+                    .id("http://www.test.example/martin".parse::<Iri>().unwrap()),
             )
+            .object(ObjectBuilder::new().id("http://example.org/foo.jpg".parse::<Iri>().unwrap()))
             .build(),
         );
         let expected = r#"{
@@ -446,21 +736,16 @@ mod tests {
                 String::from("Martin added an article to his blog"),
             )
             // TODO: figure out how to get a 'Z' on this. probably requires a time-zone (so not naive)
-            .published(DateTime::<Utc>::from_utc(
-                NaiveDate::from_ymd(2015, 2, 10).and_hms(15, 4, 55),
-                Utc,
-            ))
+            .published(Utc.with_ymd_and_hms(2015, 2, 10, 15, 4, 55).unwrap())
             .actor(
                 ActorBuilder::new(String::from("Person"))
-                    .id("http://www.test.example/martin"
-                        .parse::<http::Uri>()
-                        .unwrap())
+                    .id("http://www.test.example/martin".parse::<Iri>().unwrap())
                     .name(String::from("Martin Smith"))
-                    .url("http://example.org/martin".parse::<http::Uri>().unwrap())
+                    .url("http://example.org/martin".parse::<Iri>().unwrap())
                     .image(LinkBuilder::new(
                         UriBuilder::new(
                             "http://example.org/martin/image.jpg"
-                                .parse::<http::Uri>()
+                                .parse::<Iri>()
                                 .unwrap(),
                         )
                         .media_type(String::from("image/jpeg")),
@@ -470,19 +755,19 @@ mod tests {
                 ObjectBuilder::new()
                     .object_type(String::from("Article"))
                     .id("http://www.test.example/blog/abc123/xyz"
-                        .parse::<http::Uri>()
+                        .parse::<Iri>()
                         .unwrap())
                     .name(String::from("Why I love Activity Streams"))
                     .url(
                         "http://example.org/blog/2011/02/entry"
-                            .parse::<http::Uri>()
+                            .parse::<Iri>()
                             .unwrap(),
                     ),
             )
             .target(
                 ObjectBuilder::new()
                     .object_type(String::from("OrderedCollection"))
-                    .id("http://example.org/blog/".parse::<http::Uri>().unwrap())
+                    .id("http://example.org/blog/".parse::<Iri>().unwrap())
                     .name(String::from("Martin's Blog")),
             )
             .build(),
@@ -526,16 +811,13 @@ mod tests {
         let actual = Document::new(
             ContextBuilder::new().build(),
             ObjectBuilder::new()
-                .id("http://example.org/foo".parse::<http::Uri>().unwrap())
+                .id("http://example.org/foo".parse::<Iri>().unwrap())
                 .object_type(String::from("Note"))
                 .name(String::from("My favourite stew recipe"))
-                .published(DateTime::<Utc>::from_utc(
-                    NaiveDate::from_ymd(2014, 8, 21).and_hms(12, 34, 56),
-                    Utc,
-                ))
+                .published(Utc.with_ymd_and_hms(2014, 8, 21, 12, 34, 56).unwrap())
                 .add_attributed_to(
                     ActorBuilder::new(String::from("Person"))
-                        .id("http://joe.website.example/".parse::<http::Uri>().unwrap())
+                        .id("http://joe.website.example/".parse::<Iri>().unwrap())
                         .name(String::from("Joe Smith"))
                         .build(),
                 )
@@ -550,13 +832,11 @@ mod tests {
   "id": "http://example.org/foo",
   "name": "My favourite stew recipe",
   "published": "2014-08-21T12:34:56Z",
-  "attributedTo": [
-    {
-      "type": "Person",
-      "id": "http://joe.website.example/",
-      "name": "Joe Smith"
-    }
-  ]
+  "attributedTo": {
+    "type": "Person",
+    "id": "http://joe.website.example/",
+    "name": "Joe Smith"
+  }
 }"#;
         assert!(actual.to_json_pretty().is_ok());
         assert_eq!(actual.to_json_pretty().unwrap(), expected);
@@ -0,0 +1,1028 @@
+//! Client-to-server interactions: applying the side effects of an activity,
+//! or bare object, a client posts to an actor's outbox.
+//! <https://www.w3.org/TR/activitypub/#client-to-server-interactions>
+
+use crate::core::{Activity, ActivityBuilder, Document, Null, Object, OneOrMany, Reference};
+use crate::extended::{Actor, Add, Block, Create, Delete, Follow, Like, Remove, Undo, Update};
+use crate::iri::Iri;
+use crate::store::{
+    actor_of, embedded_objects, objects_of, required, Change, ObjectStore, Recorder,
+};
+use crate::validate::{is_activity_type, Mode, Validate};
+use crate::{Error, Result, Serde};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+type Addressees = OneOrMany<Reference<Object<Null>>>;
+
+/// The activity types [post] has side effects for. A `Follow` has none until
+/// it is accepted.
+const HANDLED: [&str; 8] = [
+    Create::TYPE,
+    Update::TYPE,
+    Delete::TYPE,
+    Add::TYPE,
+    Remove::TYPE,
+    Like::TYPE,
+    Block::TYPE,
+    Undo::TYPE,
+];
+
+/// What posting to an outbox led to.
+#[derive(Debug)]
+pub struct Outcome {
+    /// The activity as stored, with its `id` assigned, to be delivered to
+    /// its addressees. Delivery must drop its `bto` and `bcc`.
+    pub activity: Document<Activity>,
+    /// The changes made to the store, in the order they were made.
+    pub changes: Vec<Change>,
+}
+
+/// Applies the side effects of `document`, the JSON a client posted to the
+/// outbox of `actor`, to `store`. `now` is the time of the post.
+///
+/// An object that is not an activity is wrapped in a `Create`. The activity,
+/// and the objects it creates, are given ids minted by `mint_id` from their
+/// type; ids set by the client are ignored. The activity's `actor` defaults
+/// to `actor`, and must be `actor` if set, otherwise [Error::Unauthorized] is
+/// returned. The addressing of a `Create` and of its objects is copied from
+/// one to the other.
+///
+/// Side effects follow section 6 of the specification:
+///
+/// * `Create` stores the objects created.
+/// * `Update` replaces the properties of the stored objects it carries, and
+///   removes those it sets to `null`. It may not change their `id` or
+///   `type`, nor attribute them to anyone but `actor`.
+/// * `Delete` replaces the stored objects by `Tombstone`s.
+/// * `Follow` has none until the followed actor accepts, see
+///   [crate::inbox::receive].
+/// * `Add` and `Remove` change the members of their `target` collections.
+/// * `Like` adds its objects to the actor's `liked` collection.
+/// * `Block` removes the blocked actors from the actor's `followers`.
+/// * `Undo` reverts the side effects of a `Like` or `Follow` of the actor,
+///   i.e. removes its objects from `liked` or `following`.
+///
+/// Objects created are attributed to `actor`, and may not be attributed to
+/// anyone else. Objects updated or deleted must be `actor` itself or stored
+/// objects attributed to it, target collections must be collections of
+/// `actor` (such as its `followers` or `featured`) or stored collections
+/// attributed to it, and an `Undo` may only undo activities of `actor`,
+/// otherwise [Error::Unauthorized] is returned and the store is left
+/// untouched. Updating or deleting an object that isn't stored fails with
+/// [Error::NotFound].
+pub fn post<S, E, F>(
+    store: &mut S,
+    actor: &Actor<E>,
+    document: Value,
+    mut mint_id: F,
+    now: DateTime<Utc>,
+) -> Result<Outcome>
+where
+    S: ObjectStore,
+    E: Serde,
+    F: FnMut(&str) -> Iri,
+{
+    let actor_id = required(actor.id.as_ref(), "id")?;
+    let outbox = required(actor.outbox.as_ref(), "outbox")?;
+    let Document { context, object } =
+        crate::error::from_value::<Document<Object<Null>>>(document.clone())?;
+
+    let mut activity = if object
+        .object_type
+        .iter()
+        .flat_map(|t| t.iter())
+        .any(is_activity_type)
+    {
+        let object = serde_json::to_value(object).map_err(Error::Serialize)?;
+        crate::error::from_value::<Activity>(object)?
+    } else {
+        ActivityBuilder::new(
+            Create::TYPE.to_string(),
+            format!("{} created a {}", actor_id, type_of(&object)),
+        )
+        .object(Reference::Object(Box::new(object)))
+        .build()
+    };
+    match actor_of(&activity) {
+        None => activity.actor = Some(OneOrMany::One(Reference::Iri(actor_id.clone()))),
+        Some(id) if id != actor_id => {
+            return Err(Error::Unauthorized(format!(
+                "{} may not post activities of {}",
+                actor_id, id
+            )))
+        }
+        Some(_) => {}
+    }
+    activity.check(Mode::Lenient)?;
+    activity.id = Some(mint_id(type_of(&activity)));
+    if activity.has_type(Create::TYPE) {
+        let mut objects = activity.object.take();
+        for object in objects.iter_mut().flat_map(|objects| objects.iter_mut()) {
+            if let Reference::Object(object) = object {
+                attribute(object, actor_id)?;
+                object.id = Some(mint_id(type_of(object)));
+                copy_addressing(&mut activity, object);
+            }
+        }
+        activity.object = objects;
+    }
+
+    let collections = own_collections(actor)?;
+    let mut poster = Poster {
+        recorder: Recorder::new(store, &context),
+        actor: actor_id,
+        collections: &collections,
+        followers: actor.followers.as_ref(),
+        following: actor.following.as_ref(),
+        liked: actor.liked.as_ref(),
+        activity: &activity,
+        posted: &document,
+    };
+    let id = required(activity.id.as_ref(), "id")?;
+    let activity_type = activity
+        .object_type
+        .iter()
+        .flat_map(|t| t.iter())
+        .find(|object_type| HANDLED.contains(object_type));
+    if let Some(activity_type) = activity_type {
+        poster.authorize_effects(activity_type)?;
+    }
+    poster.recorder.put(id, &activity)?;
+    poster.recorder.add(outbox, id)?;
+
+    match activity_type {
+        Some(Create::TYPE) => poster.create()?,
+        Some(Update::TYPE) => poster.update()?,
+        Some(Delete::TYPE) => poster.recorder.delete_objects(&activity, now)?,
+        Some(Add::TYPE) => poster.recorder.add_to_target(&activity, true)?,
+        Some(Remove::TYPE) => poster.recorder.add_to_target(&activity, false)?,
+        Some(Like::TYPE) => poster.like()?,
+        Some(Block::TYPE) => poster.block()?,
+        Some(Undo::TYPE) => poster.undo()?,
+        _ => {}
+    }
+    let changes = poster.recorder.changes;
+    Ok(Outcome {
+        activity: Document {
+            context,
+            object: activity,
+        },
+        changes,
+    })
+}
+
+/// The first type of `object`, `Object` if it has none.
+fn type_of(object: &Object<Null>) -> &str {
+    object
+        .object_type
+        .as_ref()
+        .and_then(|t| t.iter().next())
+        .unwrap_or("Object")
+}
+
+/// The collections of `actor` its activities may change: its `followers`,
+/// `following`, `liked` and `streams`, and Mastodon's `featured` and
+/// `featuredTags`.
+fn own_collections<E: Serde>(actor: &Actor<E>) -> Result<Vec<Iri>> {
+    let actor = serde_json::to_value(actor).map_err(Error::Serialize)?;
+    let properties = [
+        "followers",
+        "following",
+        "liked",
+        "streams",
+        "featured",
+        "featuredTags",
+    ];
+    let mut collections = Vec::new();
+    for value in properties.iter().filter_map(|property| actor.get(property)) {
+        let values = match value {
+            Value::Array(values) => values.as_slice(),
+            value => std::slice::from_ref(value),
+        };
+        for value in values {
+            let id = value.as_str().or_else(|| value.get("id")?.as_str());
+            if let Some(id) = id {
+                collections.push(id.parse()?);
+            }
+        }
+    }
+    Ok(collections)
+}
+
+/// The actor `attribution` names, unless it is an embedded object, whose
+/// `id` [Null] doesn't keep.
+fn attribution(attribution: &Reference<Null>) -> Option<&Iri> {
+    match attribution {
+        Reference::Iri(iri) => Some(iri),
+        Reference::Link(link) => Some(&link.href.href),
+        Reference::Object(_) => None,
+    }
+}
+
+fn attributions(object: &Object<Null>) -> impl Iterator<Item = &Iri> {
+    object
+        .attributed_to
+        .iter()
+        .flatten()
+        .filter_map(attribution)
+}
+
+/// Attributes `object`, about to be created, to `actor`, failing if it is
+/// attributed to anyone else.
+fn attribute(object: &mut Object<Null>, actor: &Iri) -> Result<()> {
+    let Some(attributed_to) = &object.attributed_to else {
+        object.attributed_to = Some(OneOrMany::One(Reference::Iri(actor.clone())));
+        return Ok(());
+    };
+    if attributed_to
+        .iter()
+        .any(|other| attribution(other) != Some(actor))
+    {
+        return Err(Error::Unauthorized(format!(
+            "{} may not create objects attributed to others",
+            actor
+        )));
+    }
+    Ok(())
+}
+
+/// The JSON objects embedded as the `object` of the posted activity.
+fn posted_objects(posted: &Value) -> impl Iterator<Item = &serde_json::Map<String, Value>> {
+    let objects = match posted.get("object") {
+        Some(Value::Array(objects)) => objects.as_slice(),
+        Some(object) => std::slice::from_ref(object),
+        None => &[],
+    };
+    objects.iter().filter_map(Value::as_object)
+}
+
+/// Gives `activity` and `object` the union of their addressees.
+fn copy_addressing(activity: &mut Object<Null>, object: &mut Object<Null>) {
+    for (activity, object) in addressing(activity).into_iter().zip(addressing(object)) {
+        merge(activity, object);
+        merge(object, activity);
+    }
+}
+
+fn addressing(object: &mut Object<Null>) -> [&mut Option<Addressees>; 5] {
+    [
+        &mut object.to,
+        &mut object.bto,
+        &mut object.cc,
+        &mut object.bcc,
+        &mut object.audience,
+    ]
+}
+
+/// Adds the addressees of `from` missing from `into`.
+fn merge(into: &mut Option<Addressees>, from: &Option<Addressees>) {
+    let mut addressees: Vec<_> = into.take().into_iter().flatten().collect();
+    for addressee in from.iter().flatten() {
+        let known = addressees
+            .iter()
+            .any(|known| known.id().is_some() && known.id() == addressee.id());
+        if !known {
+            addressees.push(addressee.clone());
+        }
+    }
+    *into = (!addressees.is_empty()).then(|| addressees.into_iter().collect());
+}
+
+/// The state of posting one activity.
+struct Poster<'a, S> {
+    recorder: Recorder<'a, S>,
+    actor: &'a Iri,
+    /// The collections of the actor its activities may add to and remove
+    /// from.
+    collections: &'a [Iri],
+    followers: Option<&'a Iri>,
+    following: Option<&'a Iri>,
+    liked: Option<&'a Iri>,
+    activity: &'a Activity,
+    /// The JSON posted, which unlike `activity` keeps the properties the
+    /// client set to `null`.
+    posted: &'a Value,
+}
+
+impl<S: ObjectStore> Poster<'_, S> {
+    /// Fails unless the actor may modify `id`, i.e. it is the actor or a
+    /// stored object attributed to it. Objects of other origins aren't
+    /// looked up.
+    fn authorize(&self, id: &Iri) -> Result<()> {
+        if !self.actor.same_origin(id) {
+            return Err(self.unauthorized(id));
+        }
+        if id == self.actor {
+            return Ok(());
+        }
+        let Some(object) = self.recorder.store.get_document::<Object<Null>>(id)? else {
+            return Err(Error::NotFound(id.clone()));
+        };
+        if attributions(&object.object).any(|attribution| attribution == self.actor) {
+            Ok(())
+        } else {
+            Err(self.unauthorized(id))
+        }
+    }
+
+    /// Fails unless the actor may add to or remove from the collection
+    /// `id`, i.e. it is one of the actor's or a stored collection attributed
+    /// to it.
+    fn authorize_target(&self, id: &Iri) -> Result<()> {
+        if self.collections.contains(id) {
+            return Ok(());
+        }
+        match self.authorize(id) {
+            Err(Error::NotFound(_)) => Err(self.unauthorized(id)),
+            authorized => authorized,
+        }
+    }
+
+    /// The stored object `id`, if any, and what the `Update` replaces it
+    /// by: the stored object with the properties of the posted one, less
+    /// those set to `null`.
+    fn updated(&self, id: &Iri) -> Result<Option<(Object<Null>, Object<Null>)>> {
+        let Some(stored) = self.recorder.store.get_document::<Object<Null>>(id)? else {
+            return Ok(None);
+        };
+        let Value::Object(mut properties) =
+            serde_json::to_value(&stored.object).map_err(Error::Serialize)?
+        else {
+            return Ok(None);
+        };
+        let updated = posted_objects(self.posted).find(|object| {
+            let posted = object.get("id").and_then(Value::as_str);
+            posted
+                .and_then(|posted| posted.parse::<Iri>().ok())
+                .as_ref()
+                == Some(id)
+        });
+        for (property, value) in updated.into_iter().flatten() {
+            if value.is_null() {
+                properties.remove(property);
+            } else {
+                properties.insert(property.clone(), value.clone());
+            }
+        }
+        let object = crate::error::from_value(Value::Object(properties))?;
+        Ok(Some((stored.object, object)))
+    }
+
+    /// Fails if the `Update` changes the `id` or `type` of the object `id`,
+    /// or attributes it to anyone but the actor.
+    fn authorize_update(&self, id: &Iri) -> Result<()> {
+        let Some((stored, updated)) = self.updated(id)? else {
+            return Ok(());
+        };
+        if updated.id != stored.id || updated.object_type != stored.object_type {
+            return Err(Error::Unauthorized(format!(
+                "{} may not change the id or type of {}",
+                self.actor, id
+            )));
+        }
+        let attributed_away = updated
+            .attributed_to
+            .iter()
+            .flatten()
+            .any(|other| attribution(other) != Some(self.actor));
+        let unattributed = stored.attributed_to.is_some() && updated.attributed_to.is_none();
+        if attributed_away || unattributed {
+            return Err(Error::Unauthorized(format!(
+                "{} may not attribute {} to others",
+                self.actor, id
+            )));
+        }
+        Ok(())
+    }
+
+    fn unauthorized(&self, id: &Iri) -> Error {
+        Error::Unauthorized(format!("{} may not modify {}", self.actor, id))
+    }
+
+    /// Fails unless the actor may have the side effects of an activity of
+    /// type `activity_type`, and the objects an `Update` replaces exist.
+    fn authorize_effects(&self, activity_type: &str) -> Result<()> {
+        match activity_type {
+            Update::TYPE => {
+                for (id, _) in embedded_objects(self.activity) {
+                    self.authorize(id)?;
+                    self.authorize_update(id)?;
+                }
+            }
+            Delete::TYPE => {
+                for id in objects_of(self.activity) {
+                    self.authorize(id)?;
+                }
+            }
+            Add::TYPE | Remove::TYPE => {
+                let targets = self.activity.target.iter().flatten();
+                for target in targets.filter_map(|target| target.id()) {
+                    self.authorize_target(target)?;
+                }
+            }
+            Undo::TYPE => {
+                for undone in self.recorder.object_activities(self.activity)? {
+                    if actor_of(&undone) != Some(self.actor) {
+                        return Err(Error::Unauthorized(format!(
+                            "{} may not undo an activity of another actor",
+                            self.actor
+                        )));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Stores the objects created.
+    fn create(&mut self) -> Result<()> {
+        for (id, object) in embedded_objects(self.activity) {
+            self.recorder.put(id, object)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the stored objects by their [updated](Self::updated) forms.
+    fn update(&mut self) -> Result<()> {
+        for (id, _) in embedded_objects(self.activity) {
+            if let Some((_, object)) = self.updated(id)? {
+                self.recorder.put(id, &object)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the objects liked to the actor's `liked` collection.
+    fn like(&mut self) -> Result<()> {
+        let Some(liked) = self.liked else {
+            return Ok(());
+        };
+        for object in objects_of(self.activity) {
+            self.recorder.add(liked, object)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the actors blocked from the actor's followers.
+    fn block(&mut self) -> Result<()> {
+        let Some(followers) = self.followers else {
+            return Ok(());
+        };
+        for blocked in objects_of(self.activity) {
+            self.recorder.remove(followers, blocked)?;
+        }
+        Ok(())
+    }
+
+    /// Reverts the side effects of the actor's activities undone.
+    fn undo(&mut self) -> Result<()> {
+        for undone in self.recorder.object_activities(self.activity)? {
+            let collection = if undone.has_type(Like::TYPE) {
+                self.liked
+            } else if undone.has_type(Follow::TYPE) {
+                self.following
+            } else {
+                None
+            };
+            let Some(collection) = collection else {
+                continue;
+            };
+            for object in objects_of(&undone) {
+                self.recorder.remove(collection, object)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extended::ActorBuilder;
+    use crate::store::MemoryStore;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    fn iri(iri: &str) -> Iri {
+        iri.parse().unwrap()
+    }
+
+    fn sally() -> Actor {
+        ActorBuilder::new(String::from("Person"))
+            .id(iri("https://example.com/sally"))
+            .inbox(iri("https://example.com/sally/inbox"))
+            .outbox(iri("https://example.com/sally/outbox"))
+            .followers(iri("https://example.com/sally/followers"))
+            .following(iri("https://example.com/sally/following"))
+            .liked(iri("https://example.com/sally/liked"))
+            .extension(
+                String::from("featured"),
+                Value::from("https://example.com/sally/featured"),
+            )
+            .build()
+    }
+
+    fn document(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Mints ids numbering the objects of each type.
+    fn mint_id() -> impl FnMut(&str) -> Iri {
+        let mut minted = 0;
+        move |object_type| {
+            minted += 1;
+            let object_type = object_type.to_lowercase();
+            iri(&format!(
+                "https://example.com/sally/{object_type}s/{minted}"
+            ))
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn ids(addressees: &Option<Addressees>) -> Vec<&str> {
+        addressees
+            .iter()
+            .flatten()
+            .filter_map(|addressee| addressee.id())
+            .map(Iri::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn wrap_object_in_create() {
+        let mut store = MemoryStore::new();
+        let note = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Note",
+                "id": "https://example.com/chosen-by-the-client",
+                "content": "Hello",
+                "to": "https://www.w3.org/ns/activitystreams#Public",
+                "cc": "https://example.com/sally/followers"
+            }"#,
+        );
+        let outcome = post(&mut store, &sally(), note, mint_id(), now()).unwrap();
+        let create = &outcome.activity.object;
+        assert!(create.has_type(Create::TYPE));
+        assert_eq!(create.id, Some(iri("https://example.com/sally/creates/1")));
+        assert_eq!(actor_of(create), Some(&iri("https://example.com/sally")));
+        assert_eq!(
+            ids(&create.to),
+            vec!["https://www.w3.org/ns/activitystreams#Public"]
+        );
+        assert_eq!(ids(&create.cc), vec!["https://example.com/sally/followers"]);
+
+        let note = iri("https://example.com/sally/notes/2");
+        assert_eq!(objects_of(create).collect::<Vec<_>>(), vec![&note]);
+        assert_eq!(
+            outcome.changes,
+            vec![
+                Change::Stored(iri("https://example.com/sally/creates/1")),
+                Change::Added {
+                    collection: iri("https://example.com/sally/outbox"),
+                    item: iri("https://example.com/sally/creates/1"),
+                },
+                Change::Stored(note.clone()),
+            ]
+        );
+        let stored: Document<Object<Null>> = store.get_document(&note).unwrap().unwrap();
+        assert_eq!(stored.object.content, Some(String::from("Hello")));
+    }
+
+    #[test]
+    fn copy_addressing_of_create() {
+        let mut store = MemoryStore::new();
+        let create = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Create",
+                "to": "https://social.example/john",
+                "object": {
+                    "type": "Note",
+                    "content": "Hello",
+                    "to": ["https://social.example/john", "https://social.example/jane"],
+                    "bcc": "https://social.example/jim"
+                }
+            }"#,
+        );
+        let outcome = post(&mut store, &sally(), create, mint_id(), now()).unwrap();
+        let create = &outcome.activity.object;
+        let note = create.object.as_ref().unwrap().first().unwrap();
+        let note = note.as_object().unwrap();
+        for object in [&**create, note] {
+            assert_eq!(
+                ids(&object.to),
+                vec!["https://social.example/john", "https://social.example/jane"]
+            );
+            assert_eq!(ids(&object.bcc), vec!["https://social.example/jim"]);
+        }
+    }
+
+    #[test]
+    fn update_and_delete() {
+        let mut store = MemoryStore::new();
+        let note = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Note",
+                "name": "Greeting",
+                "content": "Hello"
+            }"#,
+        );
+        post(&mut store, &sally(), note, mint_id(), now()).unwrap();
+        let note = iri("https://example.com/sally/notes/2");
+
+        let update = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "object": {
+                    "id": "https://example.com/sally/notes/2",
+                    "content": "Hello, world"
+                }
+            }"#,
+        );
+        let outcome = post(&mut store, &sally(), update, mint_id(), now()).unwrap();
+        assert_eq!(outcome.changes.last(), Some(&Change::Updated(note.clone())));
+        let stored: Document<Object<Null>> = store.get_document(&note).unwrap().unwrap();
+        assert_eq!(stored.object.name, Some(String::from("Greeting")));
+        assert_eq!(stored.object.content, Some(String::from("Hello, world")));
+
+        let update = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "object": {
+                    "id": "https://example.com/sally/notes/2",
+                    "name": null
+                }
+            }"#,
+        );
+        post(&mut store, &sally(), update, mint_id(), now()).unwrap();
+        let stored: Document<Object<Null>> = store.get_document(&note).unwrap().unwrap();
+        assert_eq!(stored.object.name, None);
+        assert_eq!(stored.object.content, Some(String::from("Hello, world")));
+
+        let missing = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "object": {
+                    "id": "https://example.com/sally/notes/404",
+                    "content": "Hello"
+                }
+            }"#,
+        );
+        let error = post(&mut store, &sally(), missing, mint_id(), now()).unwrap_err();
+        assert!(
+            matches!(error, Error::NotFound(id) if id == "https://example.com/sally/notes/404")
+        );
+
+        let foreign = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Delete",
+                "object": "https://social.example/john/notes/1"
+            }"#,
+        );
+        let error = post(&mut store, &sally(), foreign, mint_id(), now()).unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(_)));
+
+        let delete = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Delete",
+                "object": "https://example.com/sally/notes/2"
+            }"#,
+        );
+        let outcome = post(&mut store, &sally(), delete, mint_id(), now()).unwrap();
+        assert_eq!(outcome.changes.last(), Some(&Change::Deleted(note.clone())));
+        assert_eq!(store.get(&note).unwrap().unwrap()["type"], "Tombstone");
+    }
+
+    #[test]
+    fn like_and_undo_like() {
+        let mut store = MemoryStore::new();
+        let liked = iri("https://example.com/sally/liked");
+        let like = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Like",
+                "object": "https://social.example/john/notes/1"
+            }"#,
+        );
+        let outcome = post(&mut store, &sally(), like, mint_id(), now()).unwrap();
+        assert_eq!(
+            store.items(&liked).unwrap(),
+            vec![iri("https://social.example/john/notes/1")]
+        );
+
+        let undo = document(&format!(
+            r#"{{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Undo",
+                "object": "{}"
+            }}"#,
+            outcome.activity.object.id.as_ref().unwrap()
+        ));
+        post(&mut store, &sally(), undo, mint_id(), now()).unwrap();
+        assert!(store.items(&liked).unwrap().is_empty());
+    }
+
+    #[test]
+    fn add_to_own_collections_only() {
+        let mut store = MemoryStore::new();
+        let add = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Add",
+                "object": "https://example.com/sally/notes/1",
+                "target": "https://example.com/sally/featured"
+            }"#,
+        );
+        post(&mut store, &sally(), add, mint_id(), now()).unwrap();
+        assert_eq!(
+            store
+                .items(&iri("https://example.com/sally/featured"))
+                .unwrap(),
+            vec![iri("https://example.com/sally/notes/1")]
+        );
+
+        let add = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Add",
+                "object": "https://example.com/sally/notes/1",
+                "target": "https://social.example/john/featured"
+            }"#,
+        );
+        let error = post(&mut store, &sally(), add, mint_id(), now()).unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(_)));
+
+        let bob_featured = iri("https://example.com/bob/featured");
+        store
+            .put(
+                &bob_featured,
+                serde_json::json!({
+                    "@context": "https://www.w3.org/ns/activitystreams",
+                    "type": "OrderedCollection",
+                    "id": "https://example.com/bob/featured",
+                    "attributedTo": "https://example.com/bob"
+                }),
+            )
+            .unwrap();
+        let add = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Add",
+                "object": "https://example.com/sally/notes/1",
+                "target": "https://example.com/bob/featured"
+            }"#,
+        );
+        let error = post(&mut store, &sally(), add, mint_id(), now()).unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(_)));
+        assert!(store.items(&bob_featured).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unauthorized_activities_leave_store_untouched() {
+        let mut store = MemoryStore::new();
+        let note = iri("https://social.example/john/notes/1");
+        let stored = serde_json::json!({
+            "type": "Note",
+            "id": note.as_str(),
+            "content": "Hello"
+        });
+        store.put(&note, stored.clone()).unwrap();
+        let update = r#"{
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "type": "Update",
+            "object": {
+                "id": "https://social.example/john/notes/1",
+                "content": "Goodbye"
+            }
+        }"#;
+        let delete = r#"{
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "type": "Delete",
+            "object": "https://social.example/john/notes/1"
+        }"#;
+        for json in [update, delete] {
+            let error = post(&mut store, &sally(), document(json), mint_id(), now()).unwrap_err();
+            assert!(matches!(error, Error::Unauthorized(_)));
+        }
+        assert_eq!(store.get(&note).unwrap(), Some(stored));
+        let outbox = iri("https://example.com/sally/outbox");
+        assert_eq!(store.count_items(&outbox).unwrap(), 0);
+        assert!(store
+            .get(&iri("https://example.com/sally/updates/1"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn leave_objects_of_other_local_actors_alone() {
+        let mut store = MemoryStore::new();
+        let bob = iri("https://example.com/bob");
+        let stored_bob = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "type": "Person",
+            "id": "https://example.com/bob",
+            "inbox": "https://example.com/bob/inbox",
+            "outbox": "https://example.com/bob/outbox",
+            "followers": "https://example.com/bob/followers",
+            "publicKey": {
+                "id": "https://example.com/bob#main-key",
+                "owner": "https://example.com/bob",
+                "publicKeyPem": "-----BEGIN PUBLIC KEY-----"
+            }
+        });
+        store.put(&bob, stored_bob.clone()).unwrap();
+        let note = iri("https://example.com/bob/notes/1");
+        let stored_note = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "type": "Note",
+            "id": "https://example.com/bob/notes/1",
+            "attributedTo": "https://example.com/bob",
+            "content": "Hello"
+        });
+        store.put(&note, stored_note.clone()).unwrap();
+        let followers = iri("https://example.com/bob/followers");
+        store
+            .add_item(&followers, &iri("https://social.example/john"))
+            .unwrap();
+
+        let activities = [
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "object": {
+                    "id": "https://example.com/bob/notes/1",
+                    "content": "Goodbye"
+                }
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "object": {
+                    "id": "https://example.com/bob",
+                    "publicKey": {
+                        "id": "https://example.com/bob#main-key",
+                        "owner": "https://example.com/bob",
+                        "publicKeyPem": "-----BEGIN PUBLIC KEY----- forged"
+                    }
+                }
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Delete",
+                "object": "https://example.com/bob/notes/1"
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Delete",
+                "object": "https://example.com/bob"
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Add",
+                "object": "https://example.com/sally",
+                "target": "https://example.com/bob/followers"
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Remove",
+                "object": "https://social.example/john",
+                "target": "https://example.com/bob/followers"
+            }"#,
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Create",
+                "object": {
+                    "type": "Note",
+                    "attributedTo": "https://example.com/bob",
+                    "content": "Bob says hello"
+                }
+            }"#,
+        ];
+        for json in activities {
+            let error = post(&mut store, &sally(), document(json), mint_id(), now()).unwrap_err();
+            assert!(
+                matches!(error, Error::Unauthorized(_)),
+                "{json} gave {error:?}"
+            );
+        }
+        assert_eq!(store.get(&bob).unwrap(), Some(stored_bob));
+        assert_eq!(store.get(&note).unwrap(), Some(stored_note));
+        assert_eq!(
+            store.items(&followers).unwrap(),
+            vec![iri("https://social.example/john")]
+        );
+        let outbox = iri("https://example.com/sally/outbox");
+        assert_eq!(store.count_items(&outbox).unwrap(), 0);
+    }
+
+    #[test]
+    fn reject_updates_of_type_and_attribution() {
+        let mut store = MemoryStore::new();
+        let note = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Note",
+                "content": "Hello"
+            }"#,
+        );
+        post(&mut store, &sally(), note, mint_id(), now()).unwrap();
+        let note = iri("https://example.com/sally/notes/2");
+        for object in [
+            r#"{"id": "https://example.com/sally/notes/2", "type": "Article"}"#,
+            r#"{"id": "https://example.com/sally/notes/2", "attributedTo": "https://example.com/bob"}"#,
+            r#"{"id": "https://example.com/sally/notes/2", "attributedTo": null}"#,
+        ] {
+            let update = document(&format!(
+                r#"{{
+                    "@context": "https://www.w3.org/ns/activitystreams",
+                    "type": "Update",
+                    "object": {object}
+                }}"#
+            ));
+            let error = post(&mut store, &sally(), update, mint_id(), now()).unwrap_err();
+            assert!(
+                matches!(error, Error::Unauthorized(_)),
+                "{object} gave {error:?}"
+            );
+        }
+        let stored: Document<Object<Null>> = store.get_document(&note).unwrap().unwrap();
+        assert!(stored.object.has_type("Note"));
+        assert_eq!(
+            attributions(&stored.object).collect::<Vec<_>>(),
+            vec![&iri("https://example.com/sally")]
+        );
+
+        let update = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "object": {
+                    "id": "https://example.com/sally/notes/2",
+                    "type": "Note",
+                    "attributedTo": "https://example.com/sally",
+                    "content": "Hello, world"
+                }
+            }"#,
+        );
+        post(&mut store, &sally(), update, mint_id(), now()).unwrap();
+        let stored: Document<Object<Null>> = store.get_document(&note).unwrap().unwrap();
+        assert_eq!(stored.object.content, Some(String::from("Hello, world")));
+    }
+
+    #[test]
+    fn update_own_actor() {
+        let mut store = MemoryStore::new();
+        let sally = sally();
+        store
+            .put_document(&Document::new(
+                crate::core::ContextBuilder::new().build(),
+                sally.clone(),
+            ))
+            .unwrap();
+        let update = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Update",
+                "object": {
+                    "id": "https://example.com/sally",
+                    "name": "Sally"
+                }
+            }"#,
+        );
+        post(&mut store, &sally, update, mint_id(), now()).unwrap();
+        let stored: Document<Actor> = store
+            .get_document(&iri("https://example.com/sally"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.object.name, Some(String::from("Sally")));
+    }
+
+    #[test]
+    fn reject_activity_of_another_actor() {
+        let mut store = MemoryStore::new();
+        let follow = document(
+            r#"{
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "type": "Follow",
+                "actor": "https://social.example/john",
+                "object": "https://example.com/sally"
+            }"#,
+        );
+        let error = post(&mut store, &sally(), follow, mint_id(), now()).unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(_)));
+        let outbox = iri("https://example.com/sally/outbox");
+        assert_eq!(store.count_items(&outbox).unwrap(), 0);
+    }
+}
@@ -0,0 +1,517 @@
+//! Signing and verifying requests with HTTP Signatures, as servers such as
+//! Mastodon require of deliveries to inboxes and, often, of fetches.
+//! <https://datatracker.ietf.org/doc/html/draft-cavage-http-signatures-12>
+//!
+//! Requests are signed over `(request-target)`, `host`, `date` and, if they
+//! have a body, `digest`, with an RSA (`rsa-sha256`) or Ed25519 key.
+
+use crate::extended::PublicKey;
+use crate::iri::Iri;
+use crate::{Error, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use http::header::{HeaderName, HeaderValue, DATE, HOST};
+use http::Request;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1v15;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::signature::{SignatureEncoding, Signer as _, Verifier as _};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+const SIGNATURE: &str = "signature";
+const DIGEST: &str = "digest";
+const REQUEST_TARGET: &str = "(request-target)";
+
+/// The format of the `Date` header, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+const HTTP_DATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// A private key requests are signed with.
+#[derive(Debug, Clone)]
+pub enum SigningKey {
+    Rsa(pkcs1v15::SigningKey<Sha256>),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl SigningKey {
+    /// Reads a PEM encoded PKCS #8 RSA or Ed25519 key, or PKCS #1 RSA key.
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        if let Ok(key) = RsaPrivateKey::from_pkcs8_pem(pem) {
+            return Ok(SigningKey::Rsa(pkcs1v15::SigningKey::new(key)));
+        }
+        if let Ok(key) = RsaPrivateKey::from_pkcs1_pem(pem) {
+            return Ok(SigningKey::Rsa(pkcs1v15::SigningKey::new(key)));
+        }
+        ed25519_dalek::SigningKey::from_pkcs8_pem(pem)
+            .map(SigningKey::Ed25519)
+            .map_err(|error| Error::Signature(format!("unsupported private key: {}", error)))
+    }
+
+    /// The `algorithm` parameter of signatures made with the key. Ed25519
+    /// signatures use `hs2019`, leaving the algorithm to the key.
+    fn algorithm(&self) -> &'static str {
+        match self {
+            SigningKey::Rsa(_) => "rsa-sha256",
+            SigningKey::Ed25519(_) => "hs2019",
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            SigningKey::Rsa(key) => key.sign(message).to_vec(),
+            SigningKey::Ed25519(key) => key.sign(message).to_vec(),
+        }
+    }
+}
+
+/// A public key signatures are verified with.
+#[derive(Debug, Clone)]
+pub enum VerifyingKey {
+    Rsa(pkcs1v15::VerifyingKey<Sha256>),
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl VerifyingKey {
+    /// Reads a PEM encoded RSA or Ed25519 public key, as found in an actor's
+    /// `publicKeyPem`.
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        if let Ok(key) = RsaPublicKey::from_public_key_pem(pem) {
+            return Ok(VerifyingKey::Rsa(pkcs1v15::VerifyingKey::new(key)));
+        }
+        ed25519_dalek::VerifyingKey::from_public_key_pem(pem)
+            .map(VerifyingKey::Ed25519)
+            .map_err(|error| Error::Signature(format!("unsupported public key: {}", error)))
+    }
+
+    /// Whether the key may verify signatures whose `algorithm` parameter is
+    /// `algorithm`.
+    fn accepts(&self, algorithm: &str) -> bool {
+        matches!(
+            (self, algorithm),
+            (_, "hs2019")
+                | (VerifyingKey::Rsa(_), "rsa-sha256")
+                | (VerifyingKey::Ed25519(_), "ed25519")
+        )
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            VerifyingKey::Rsa(key) => pkcs1v15::Signature::try_from(signature)
+                .is_ok_and(|signature| key.verify(message, &signature).is_ok()),
+            VerifyingKey::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify(message, &signature).is_ok()),
+        }
+    }
+}
+
+/// Signs requests on behalf of the owner of a key.
+#[derive(Debug, Clone)]
+pub struct Signer {
+    key_id: Iri,
+    key: SigningKey,
+}
+
+impl Signer {
+    /// `key_id` is the `id` of the key's [PublicKey], through which
+    /// recipients find it.
+    pub fn new(key_id: Iri, key: SigningKey) -> Self {
+        Signer { key_id, key }
+    }
+
+    /// Adds a `Signature` header to `request`, sent at `now`.
+    ///
+    /// The `Host` header is set from the request's URI unless present, the
+    /// `Date` header is set to `now`, and a request with a body gets the
+    /// SHA-256 `Digest` of it.
+    pub fn sign<B: AsRef<[u8]>>(&self, request: &mut Request<B>, now: DateTime<Utc>) -> Result<()> {
+        if !request.headers().contains_key(HOST) {
+            let host = request
+                .uri()
+                .authority()
+                .ok_or_else(|| Error::Signature(String::from("request has no host")))?;
+            let host = header_value(host.as_str())?;
+            request.headers_mut().insert(HOST, host);
+        }
+        let date = header_value(&now.format(HTTP_DATE).to_string())?;
+        request.headers_mut().insert(DATE, date);
+
+        let mut headers = vec![REQUEST_TARGET, "host", "date"];
+        if !request.body().as_ref().is_empty() {
+            let digest = header_value(&digest(request.body().as_ref()))?;
+            request
+                .headers_mut()
+                .insert(HeaderName::from_static(DIGEST), digest);
+            headers.push(DIGEST);
+        }
+
+        let signature = self.key.sign(signing_string(request, &headers)?.as_bytes());
+        let signature = format!(
+            r#"keyId="{}",algorithm="{}",headers="{}",signature="{}""#,
+            self.key_id,
+            self.key.algorithm(),
+            headers.join(" "),
+            BASE64.encode(signature)
+        );
+        request.headers_mut().insert(
+            HeaderName::from_static(SIGNATURE),
+            header_value(&signature)?,
+        );
+        Ok(())
+    }
+}
+
+/// Finds the keys signatures name by their `keyId`, e.g. by fetching the
+/// actor owning them.
+pub trait KeyLookup {
+    /// The key whose `id` is `key_id`, if it exists.
+    fn lookup(&self, key_id: &Iri) -> Result<Option<PublicKey>>;
+}
+
+impl<F> KeyLookup for F
+where
+    F: Fn(&Iri) -> Result<Option<PublicKey>>,
+{
+    fn lookup(&self, key_id: &Iri) -> Result<Option<PublicKey>> {
+        self(key_id)
+    }
+}
+
+/// Verifies the signatures of requests.
+#[derive(Debug, Clone)]
+pub struct Verifier<K> {
+    keys: K,
+    max_skew: Duration,
+}
+
+impl<K: KeyLookup> Verifier<K> {
+    /// Verifies signatures with the keys `keys` finds, accepting requests
+    /// dated up to 12 hours away from the time of verification.
+    pub fn new(keys: K) -> Self {
+        Verifier {
+            keys,
+            max_skew: Duration::hours(12),
+        }
+    }
+
+    /// Sets how far from the time of verification a request's `Date` may be.
+    pub fn max_skew(mut self, max_skew: Duration) -> Self {
+        self.max_skew = max_skew;
+        self
+    }
+
+    /// Verifies the `Signature` header of `request`, received at `now`, and
+    /// returns the key it was signed with. Its `owner` is the actor the
+    /// request is from.
+    ///
+    /// The signature must cover `(request-target)`, `host`, `date` and, if
+    /// the request has a body, `digest`, which must match the body.
+    pub fn verify<B: AsRef<[u8]>>(
+        &self,
+        request: &Request<B>,
+        now: DateTime<Utc>,
+    ) -> Result<PublicKey> {
+        let signature_header = request
+            .headers()
+            .get(SIGNATURE)
+            .ok_or_else(|| Error::Signature(String::from("missing Signature header")))?
+            .to_str()
+            .map_err(|_| Error::Signature(String::from("malformed Signature header")))?;
+        let parameters = parameters(signature_header)?;
+        let parameter = |name: &str| parameters.iter().find(|(n, _)| *n == name).map(|(_, v)| *v);
+
+        let key_id: Iri = parameter("keyId")
+            .ok_or_else(|| Error::Signature(String::from("missing keyId")))?
+            .parse()?;
+        let signature = parameter("signature")
+            .and_then(|signature| BASE64.decode(signature).ok())
+            .ok_or_else(|| Error::Signature(String::from("missing or malformed signature")))?;
+        let headers: Vec<String> = parameter("headers")
+            .unwrap_or("date")
+            .split_ascii_whitespace()
+            .map(str::to_ascii_lowercase)
+            .collect();
+        let mut required = vec![REQUEST_TARGET, "host", "date"];
+        if !request.body().as_ref().is_empty() {
+            required.push(DIGEST);
+        }
+        if let Some(missing) = required.iter().find(|r| !headers.iter().any(|h| h == *r)) {
+            return Err(Error::Signature(format!("{} is not signed", missing)));
+        }
+
+        let date = header(request, "date")?;
+        let date = DateTime::parse_from_rfc2822(&date)
+            .map_err(|_| Error::Signature(format!("malformed Date {:?}", date)))?;
+        if (now - date.with_timezone(&Utc)).abs() > self.max_skew {
+            return Err(Error::Signature(format!(
+                "Date {} is too far from now",
+                date
+            )));
+        }
+        if required.contains(&DIGEST) {
+            let expected = digest(request.body().as_ref());
+            let matches = header(request, DIGEST)?
+                .split(',')
+                .any(|digest| digest.trim() == expected);
+            if !matches {
+                return Err(Error::Signature(String::from(
+                    "Digest doesn't match the body",
+                )));
+            }
+        }
+
+        let public_key = self
+            .keys
+            .lookup(&key_id)?
+            .ok_or_else(|| Error::Signature(format!("unknown key {}", key_id)))?;
+        let key = VerifyingKey::from_pem(&public_key.public_key_pem)?;
+        if let Some(algorithm) = parameter("algorithm") {
+            if !key.accepts(&algorithm.to_ascii_lowercase()) {
+                return Err(Error::Signature(format!(
+                    "algorithm {} doesn't match key {}",
+                    algorithm, key_id
+                )));
+            }
+        }
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        if !key.verify(signing_string(request, &headers)?.as_bytes(), &signature) {
+            return Err(Error::Signature(String::from("signature doesn't verify")));
+        }
+        Ok(public_key)
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value)
+        .map_err(|_| Error::Signature(format!("invalid header value {:?}", value)))
+}
+
+/// The `Digest` header value of `body`.
+fn digest(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64.encode(Sha256::digest(body)))
+}
+
+/// The values of the header `name`, joined by `, `.
+fn header<B>(request: &Request<B>, name: &str) -> Result<String> {
+    let values: Vec<&str> = request
+        .headers()
+        .get_all(name)
+        .iter()
+        .map(|value| value.to_str())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| Error::Signature(format!("malformed {} header", name)))?;
+    if values.is_empty() {
+        return Err(Error::Signature(format!("missing {} header", name)));
+    }
+    Ok(values.join(", "))
+}
+
+/// The string signed for `headers` of `request`.
+fn signing_string<B>(request: &Request<B>, headers: &[&str]) -> Result<String> {
+    let mut lines = Vec::new();
+    for name in headers {
+        let value = if *name == REQUEST_TARGET {
+            let path = request
+                .uri()
+                .path_and_query()
+                .map_or("/", |path| path.as_str());
+            format!(
+                "{} {}",
+                request.method().as_str().to_ascii_lowercase(),
+                path
+            )
+        } else {
+            header(request, name)?
+        };
+        lines.push(format!("{}: {}", name, value));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// The `name="value"` parameters of a `Signature` header.
+fn parameters(header: &str) -> Result<Vec<(&str, &str)>> {
+    let malformed = || Error::Signature(String::from("malformed Signature header"));
+    let mut parameters = Vec::new();
+    let mut rest = header.trim();
+    while !rest.is_empty() {
+        let (name, value) = rest.split_once('=').ok_or_else(malformed)?;
+        let value = value.trim_start().strip_prefix('"').ok_or_else(malformed)?;
+        let (value, after) = value.split_once('"').ok_or_else(malformed)?;
+        parameters.push((name.trim(), value));
+        rest = after.trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+    }
+    Ok(parameters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    const RSA_PRIVATE_KEY: &str = include_str!("../tests/fixtures/keys/rsa.pem");
+    const RSA_PUBLIC_KEY: &str = include_str!("../tests/fixtures/keys/rsa.pub");
+    const ED25519_PRIVATE_KEY: &str = include_str!("../tests/fixtures/keys/ed25519.pem");
+    const ED25519_PUBLIC_KEY: &str = include_str!("../tests/fixtures/keys/ed25519.pub");
+
+    fn key_id() -> Iri {
+        "https://example.com/sally#main-key".parse().unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    /// Looks up the key of sally, with `pem` as its public key.
+    fn keys(pem: &'static str) -> impl KeyLookup {
+        move |key_id: &Iri| {
+            Ok((*key_id == self::key_id()).then(|| PublicKey {
+                id: self::key_id(),
+                owner: "https://example.com/sally".parse().unwrap(),
+                public_key_pem: pem.to_string(),
+            }))
+        }
+    }
+
+    fn delivery() -> Request<Vec<u8>> {
+        Request::post("https://social.example/john/inbox")
+            .header("content-type", "application/activity+json")
+            .body(br#"{"type":"Like"}"#.to_vec())
+            .unwrap()
+    }
+
+    #[test]
+    fn sign_post() {
+        let signer = Signer::new(key_id(), SigningKey::from_pem(RSA_PRIVATE_KEY).unwrap());
+        let mut request = delivery();
+        signer.sign(&mut request, now()).unwrap();
+        let headers = request.headers();
+        assert_eq!(headers["host"], "social.example");
+        assert_eq!(headers["date"], "Mon, 01 Jan 2024 12:00:00 GMT");
+        assert_eq!(
+            headers["digest"],
+            "SHA-256=fQLcyuiC5b53id2wfhThCI+LFjFoCGXZfRwgMiR7Fp0="
+        );
+        let signature = headers["signature"].to_str().unwrap();
+        assert!(signature.starts_with(
+            r#"keyId="https://example.com/sally#main-key",algorithm="rsa-sha256",headers="(request-target) host date digest",signature=""#
+        ));
+        assert_eq!(
+            signing_string(&request, &[REQUEST_TARGET, "host", "date", "digest"]).unwrap(),
+            "(request-target): post /john/inbox\n\
+             host: social.example\n\
+             date: Mon, 01 Jan 2024 12:00:00 GMT\n\
+             digest: SHA-256=fQLcyuiC5b53id2wfhThCI+LFjFoCGXZfRwgMiR7Fp0="
+        );
+    }
+
+    #[test]
+    fn verify_rsa_signature() {
+        let signer = Signer::new(key_id(), SigningKey::from_pem(RSA_PRIVATE_KEY).unwrap());
+        let verifier = Verifier::new(keys(RSA_PUBLIC_KEY));
+        let mut request = delivery();
+        signer.sign(&mut request, now()).unwrap();
+        let key = verifier.verify(&request, now()).unwrap();
+        assert_eq!(key.owner, "https://example.com/sally");
+
+        let mut tampered = request;
+        *tampered.body_mut() = br#"{"type":"Undo"}"#.to_vec();
+        let error = verifier.verify(&tampered, now()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid signature: Digest doesn't match the body"
+        );
+
+        let mut forged = delivery();
+        signer.sign(&mut forged, now()).unwrap();
+        forged
+            .headers_mut()
+            .insert(HOST, HeaderValue::from_static("evil.example"));
+        let error = verifier.verify(&forged, now()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid signature: signature doesn't verify"
+        );
+    }
+
+    #[test]
+    fn verify_ed25519_signature() {
+        let signer = Signer::new(key_id(), SigningKey::from_pem(ED25519_PRIVATE_KEY).unwrap());
+        let mut request = Request::get("https://social.example/john")
+            .body(Vec::new())
+            .unwrap();
+        signer.sign(&mut request, now()).unwrap();
+        let signature = request.headers()["signature"].to_str().unwrap();
+        assert!(signature.contains(r#"algorithm="hs2019",headers="(request-target) host date""#));
+
+        let verifier = Verifier::new(keys(ED25519_PUBLIC_KEY));
+        assert!(verifier.verify(&request, now()).is_ok());
+        let verifier = Verifier::new(keys(RSA_PUBLIC_KEY));
+        assert!(verifier.verify(&request, now()).is_err());
+    }
+
+    #[test]
+    fn enforce_clock_skew() {
+        let signer = Signer::new(key_id(), SigningKey::from_pem(RSA_PRIVATE_KEY).unwrap());
+        let mut request = delivery();
+        signer.sign(&mut request, now()).unwrap();
+
+        let verifier = Verifier::new(keys(RSA_PUBLIC_KEY));
+        assert!(verifier
+            .verify(&request, now() + Duration::hours(11))
+            .is_ok());
+        assert!(verifier
+            .verify(&request, now() - Duration::hours(11))
+            .is_ok());
+        assert!(verifier
+            .verify(&request, now() + Duration::hours(13))
+            .is_err());
+
+        let verifier = verifier.max_skew(Duration::minutes(5));
+        assert!(verifier
+            .verify(&request, now() + Duration::minutes(4))
+            .is_ok());
+        let error = verifier
+            .verify(&request, now() + Duration::minutes(6))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid signature: Date 2024-01-01 12:00:00 +00:00 is too far from now"
+        );
+    }
+
+    #[test]
+    fn require_signed_headers() {
+        let verifier = Verifier::new(keys(RSA_PUBLIC_KEY));
+        let mut request = delivery();
+        request.headers_mut().insert(
+            "signature",
+            HeaderValue::from_static(
+                r#"keyId="https://example.com/sally#main-key",headers="(request-target) host date",signature="AAAA""#,
+            ),
+        );
+        let error = verifier.verify(&request, now()).unwrap_err();
+        assert_eq!(error.to_string(), "invalid signature: digest is not signed");
+
+        let unsigned = delivery();
+        let error = verifier.verify(&unsigned, now()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid signature: missing Signature header"
+        );
+    }
+
+    #[test]
+    fn parse_parameters() {
+        assert_eq!(
+            parameters(r#"keyId="a,b", algorithm="hs2019",headers="date""#).unwrap(),
+            vec![
+                ("keyId", "a,b"),
+                ("algorithm", "hs2019"),
+                ("headers", "date")
+            ]
+        );
+        assert!(parameters(r#"keyId=a"#).is_err());
+    }
+}
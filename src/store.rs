@@ -0,0 +1,500 @@
+//! Storage of the objects and collections server-side processing works on,
+//! see [crate::inbox] and [crate::outbox].
+//!
+//! An [ObjectStore] keeps JSON documents keyed by their `id`, and the
+//! membership of collections (an actor's `inbox` or `followers`, an object's
+//! `likes`) as ordered lists of IRIs, which it serves as
+//! [OrderedCollection]s split into [OrderedCollectionPage]s.
+//!
+//! [MemoryStore] keeps everything in memory. With the `sqlite` feature,
+//! [SqliteStore] keeps it in a SQLite database.
+
+use crate::core::{
+    Activity, Context, Document, Null, Object, OrderedCollection, OrderedCollectionBuilder,
+    OrderedCollectionPage, OrderedCollectionPageBuilder, Reference,
+};
+use crate::extended::TombstoneBuilder;
+use crate::iri::Iri;
+use crate::validate::{Violation, ViolationKind};
+use crate::{Error, Result, Serde};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+/// A store of documents keyed by IRI, plus the members of collections.
+pub trait ObjectStore {
+    /// The document stored under `id`, if any.
+    fn get(&self, id: &Iri) -> Result<Option<Value>>;
+
+    /// Stores `document` under `id`, replacing any previous one.
+    fn put(&mut self, id: &Iri, document: Value) -> Result<()>;
+
+    /// Removes the document stored under `id`. Returns whether there was one.
+    fn remove(&mut self, id: &Iri) -> Result<bool>;
+
+    /// Adds `item` to the front of `collection`. Returns `false` if it was
+    /// already a member.
+    fn add_item(&mut self, collection: &Iri, item: &Iri) -> Result<bool>;
+
+    /// Removes `item` from `collection`. Returns whether it was a member.
+    fn remove_item(&mut self, collection: &Iri, item: &Iri) -> Result<bool>;
+
+    /// The members of `collection`, most recently added first.
+    fn items(&self, collection: &Iri) -> Result<Vec<Iri>>;
+
+    fn contains_item(&self, collection: &Iri, item: &Iri) -> Result<bool> {
+        Ok(self.items(collection)?.contains(item))
+    }
+
+    /// The number of members of `collection`.
+    fn count_items(&self, collection: &Iri) -> Result<usize> {
+        Ok(self.items(collection)?.len())
+    }
+
+    /// At most `limit` members of `collection`, skipping the `offset` most
+    /// recently added.
+    fn page_items(&self, collection: &Iri, offset: usize, limit: usize) -> Result<Vec<Iri>> {
+        let items = self.items(collection)?;
+        Ok(items.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// `collection` as an [OrderedCollection] linking to its first page,
+    /// without its members.
+    fn ordered_collection(
+        &self,
+        collection: &Iri,
+    ) -> Result<OrderedCollection<Reference<Object<Null>>>> {
+        Ok(
+            OrderedCollectionBuilder::new(String::from("OrderedCollection"), Vec::new())
+                .id(collection.clone())
+                .total_items(self.count_items(collection)?)
+                .first(page_id(collection, 1)?)
+                .build(),
+        )
+    }
+
+    /// Page `page`, counting from 1, of `collection` split into pages of
+    /// `page_size` members, most recently added first.
+    fn ordered_collection_page(
+        &self,
+        collection: &Iri,
+        page: usize,
+        page_size: usize,
+    ) -> Result<OrderedCollectionPage<Reference<Object<Null>>>> {
+        let page = page.max(1);
+        // The page number usually comes from a request, so may be anything;
+        // pages past the end, even past `usize::MAX` members, are empty.
+        let offset = (page - 1).checked_mul(page_size);
+        let items = match offset {
+            Some(offset) => self.page_items(collection, offset, page_size)?,
+            None => Vec::new(),
+        };
+        let count = self.count_items(collection)?;
+        let mut builder = OrderedCollectionPageBuilder::new(
+            String::from("OrderedCollectionPage"),
+            items.into_iter().map(Reference::Iri).collect(),
+            collection.clone(),
+        )
+        .id(page_id(collection, page)?)
+        .total_items(count);
+        let has_next = offset.is_some_and(|offset| offset.saturating_add(page_size) < count);
+        if let (true, Some(next)) = (has_next, page.checked_add(1)) {
+            builder = builder.next(page_id(collection, next)?);
+        }
+        if page > 1 {
+            builder = builder.prev(page_id(collection, page - 1)?);
+        }
+        Ok(builder.build())
+    }
+
+    /// The document stored under `id`, parsed as `T`.
+    fn get_document<T: DeserializeOwned>(&self, id: &Iri) -> Result<Option<Document<T>>>
+    where
+        Self: Sized,
+    {
+        self.get(id)?.map(crate::error::from_value).transpose()
+    }
+
+    /// Stores `document` under the `id` of its object, which it must have.
+    fn put_document<T: Serialize>(&mut self, document: &Document<T>) -> Result<Iri>
+    where
+        Self: Sized,
+    {
+        let document = serde_json::to_value(document).map_err(Error::Serialize)?;
+        let id: Iri = match document.get("id").and_then(Value::as_str) {
+            Some(id) => id.parse()?,
+            None => return Err(missing("id")),
+        };
+        self.put(&id, document)?;
+        Ok(id)
+    }
+}
+
+/// The id of page `page` of `collection`, which takes it as its `page` query
+/// parameter.
+fn page_id(collection: &Iri, page: usize) -> Result<Iri> {
+    let separator = if collection.query().is_some() {
+        '&'
+    } else {
+        '?'
+    };
+    Ok(format!("{}{}page={}", collection, separator, page).parse()?)
+}
+
+/// An [ObjectStore] keeping everything in memory, e.g. for tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    documents: BTreeMap<Iri, Value>,
+    collections: BTreeMap<Iri, Vec<Iri>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+}
+
+impl ObjectStore for MemoryStore {
+    fn get(&self, id: &Iri) -> Result<Option<Value>> {
+        Ok(self.documents.get(id).cloned())
+    }
+
+    fn put(&mut self, id: &Iri, document: Value) -> Result<()> {
+        self.documents.insert(id.clone(), document);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &Iri) -> Result<bool> {
+        Ok(self.documents.remove(id).is_some())
+    }
+
+    fn add_item(&mut self, collection: &Iri, item: &Iri) -> Result<bool> {
+        let items = self.collections.entry(collection.clone()).or_default();
+        if items.contains(item) {
+            return Ok(false);
+        }
+        items.insert(0, item.clone());
+        Ok(true)
+    }
+
+    fn remove_item(&mut self, collection: &Iri, item: &Iri) -> Result<bool> {
+        let Some(items) = self.collections.get_mut(collection) else {
+            return Ok(false);
+        };
+        let len = items.len();
+        items.retain(|member| member != item);
+        Ok(items.len() != len)
+    }
+
+    fn items(&self, collection: &Iri) -> Result<Vec<Iri>> {
+        Ok(self
+            .collections
+            .get(collection)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+/// A change made to an [ObjectStore] while processing an activity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A document was stored for the first time.
+    Stored(Iri),
+    /// A stored document was replaced by a newer version.
+    Updated(Iri),
+    /// A stored object was replaced by a `Tombstone`.
+    Deleted(Iri),
+    Added {
+        collection: Iri,
+        item: Iri,
+    },
+    Removed {
+        collection: Iri,
+        item: Iri,
+    },
+    /// A `Follow` of an actor approving its followers manually was received
+    /// and awaits an `Accept` or a `Reject`.
+    FollowRequested {
+        follow: Iri,
+        follower: Iri,
+    },
+}
+
+/// The error for a document lacking `property`.
+pub(crate) fn missing(property: &'static str) -> Error {
+    Error::Validation(vec![Violation {
+        path: String::from("$"),
+        kind: ViolationKind::MissingProperty(property),
+    }])
+}
+
+/// `value`, which the document must have as `property`.
+pub(crate) fn required<'a>(value: Option<&'a Iri>, property: &'static str) -> Result<&'a Iri> {
+    value.ok_or_else(|| missing(property))
+}
+
+/// The id of the first actor of `activity`.
+pub(crate) fn actor_of(activity: &Activity) -> Option<&Iri> {
+    activity.actor.iter().flatten().find_map(|actor| actor.id())
+}
+
+/// The ids of the objects of `activity`.
+pub(crate) fn objects_of(activity: &Activity) -> impl Iterator<Item = &Iri> {
+    activity
+        .object
+        .iter()
+        .flatten()
+        .filter_map(|object| object.id())
+}
+
+/// The objects embedded in `activity`, with their ids.
+pub(crate) fn embedded_objects(activity: &Activity) -> impl Iterator<Item = (&Iri, &Object<Null>)> {
+    activity
+        .object
+        .iter()
+        .flatten()
+        .filter_map(Reference::as_object)
+        .filter_map(|object| object.id.as_ref().map(|id| (id, object)))
+}
+
+/// Makes changes to an [ObjectStore] on behalf of an activity, keeping
+/// track of them.
+pub(crate) struct Recorder<'a, S> {
+    pub(crate) store: &'a mut S,
+    /// The `@context` documents are stored with.
+    context: &'a Context,
+    pub(crate) changes: Vec<Change>,
+}
+
+impl<'a, S: ObjectStore> Recorder<'a, S> {
+    pub(crate) fn new(store: &'a mut S, context: &'a Context) -> Self {
+        Recorder {
+            store,
+            context,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Stores `object` under `id`, replacing any previous version.
+    pub(crate) fn put<T: Serde>(&mut self, id: &Iri, object: &T) -> Result<()> {
+        let change = match self.store.get(id)? {
+            Some(_) => Change::Updated(id.clone()),
+            None => Change::Stored(id.clone()),
+        };
+        self.write(id, object)?;
+        self.changes.push(change);
+        Ok(())
+    }
+
+    fn write<T: Serde>(&mut self, id: &Iri, object: &T) -> Result<()> {
+        let document = Document {
+            context: self.context.clone(),
+            object,
+        };
+        let document = serde_json::to_value(document).map_err(Error::Serialize)?;
+        self.store.put(id, document)
+    }
+
+    /// Replaces the object stored under `id`, if any, by a `Tombstone`.
+    pub(crate) fn delete(&mut self, id: &Iri, now: DateTime<Utc>) -> Result<()> {
+        let Some(object) = self.store.get(id)? else {
+            return Ok(());
+        };
+        let mut tombstone = TombstoneBuilder::new().id(id.clone()).deleted(now);
+        if let Some(former_type) = object.get("type").and_then(Value::as_str) {
+            tombstone = tombstone.former_type(former_type.to_string());
+        }
+        self.write(id, &tombstone.build())?;
+        self.changes.push(Change::Deleted(id.clone()));
+        Ok(())
+    }
+
+    /// Replaces the stored copies of the objects `activity` deletes by
+    /// `Tombstone`s.
+    pub(crate) fn delete_objects(&mut self, activity: &Activity, now: DateTime<Utc>) -> Result<()> {
+        for id in objects_of(activity) {
+            self.delete(id, now)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the objects of `activity` to, or removes them from, its target
+    /// collections.
+    pub(crate) fn add_to_target(&mut self, activity: &Activity, add: bool) -> Result<()> {
+        let targets = activity.target.iter().flatten();
+        for target in targets.filter_map(|target| target.id()) {
+            for object in objects_of(activity) {
+                if add {
+                    self.add(target, object)?;
+                } else {
+                    self.remove(target, object)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn add(&mut self, collection: &Iri, item: &Iri) -> Result<()> {
+        if self.store.add_item(collection, item)? {
+            self.changes.push(Change::Added {
+                collection: collection.clone(),
+                item: item.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove(&mut self, collection: &Iri, item: &Iri) -> Result<()> {
+        if self.store.remove_item(collection, item)? {
+            self.changes.push(Change::Removed {
+                collection: collection.clone(),
+                item: item.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The IRI of the collection the stored object `id` has as `property`,
+    /// e.g. its `likes`, if any.
+    pub(crate) fn collection_of(&self, id: &Iri, property: &str) -> Result<Option<Iri>> {
+        let collection = self
+            .store
+            .get(id)?
+            .and_then(|object| match object.get(property)? {
+                Value::String(iri) => Some(iri.clone()),
+                collection => collection.get("id")?.as_str().map(String::from),
+            });
+        Ok(collection.map(|iri| iri.parse()).transpose()?)
+    }
+
+    /// The objects of `activity` that are themselves activities, embedded or
+    /// stored.
+    pub(crate) fn object_activities(&self, activity: &Activity) -> Result<Vec<Activity>> {
+        let mut activities = Vec::new();
+        for object in activity.object.iter().flatten() {
+            let activity = match object {
+                Reference::Object(object) => {
+                    let object = serde_json::to_value(object).map_err(Error::Serialize)?;
+                    Some(crate::error::from_value(object)?)
+                }
+                reference => match reference.id() {
+                    Some(id) => self
+                        .store
+                        .get_document::<Activity>(id)?
+                        .map(|document| document.object),
+                    None => None,
+                },
+            };
+            activities.extend(activity);
+        }
+        Ok(activities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ContextBuilder, Null, Object, ObjectBuilder};
+    use crate::Serde;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn put_and_get_documents() {
+        let mut store = MemoryStore::new();
+        let note: Object<Null> = ObjectBuilder::new()
+            .object_type(String::from("Note"))
+            .id("https://example.com/notes/1".parse::<Iri>().unwrap())
+            .content(String::from("Hello"))
+            .build();
+        let id = store
+            .put_document(&Document::new(ContextBuilder::new().build(), note))
+            .unwrap();
+        assert_eq!(id, "https://example.com/notes/1");
+
+        let document: Document<Object<Null>> = store.get_document(&id).unwrap().unwrap();
+        assert_eq!(document.object.content, Some(String::from("Hello")));
+        assert!(store.remove(&id).unwrap());
+        assert!(store.get(&id).unwrap().is_none());
+
+        let anonymous: Object<Null> = ObjectBuilder::new().build();
+        let result = store.put_document(&Document::new(ContextBuilder::new().build(), anonymous));
+        assert_eq!(result.unwrap_err().path(), Some("$"));
+    }
+
+    #[test]
+    fn collection_membership() {
+        let mut store = MemoryStore::new();
+        let followers = "https://example.com/sally/followers"
+            .parse::<Iri>()
+            .unwrap();
+        let john = "https://example.com/john".parse::<Iri>().unwrap();
+        let jane = "https://example.com/jane".parse::<Iri>().unwrap();
+        assert!(store.add_item(&followers, &john).unwrap());
+        assert!(store.add_item(&followers, &jane).unwrap());
+        assert!(!store.add_item(&followers, &john).unwrap());
+        assert_eq!(
+            store.items(&followers).unwrap(),
+            vec![jane.clone(), john.clone()]
+        );
+        assert!(store.contains_item(&followers, &john).unwrap());
+        assert!(store.remove_item(&followers, &john).unwrap());
+        assert!(!store.remove_item(&followers, &john).unwrap());
+        assert_eq!(store.items(&followers).unwrap(), vec![jane]);
+    }
+
+    #[test]
+    fn collection_views() {
+        let mut store = MemoryStore::new();
+        let followers = "https://example.com/sally/followers"
+            .parse::<Iri>()
+            .unwrap();
+        for name in ["john", "jane", "jim"] {
+            let follower = format!("https://example.com/{}", name).parse().unwrap();
+            store.add_item(&followers, &follower).unwrap();
+        }
+
+        let collection = store.ordered_collection(&followers).unwrap();
+        assert_eq!(
+            collection.to_json_pretty().unwrap(),
+            r#"{
+  "type": "OrderedCollection",
+  "id": "https://example.com/sally/followers",
+  "totalItems": 3,
+  "first": "https://example.com/sally/followers?page=1"
+}"#
+        );
+
+        let page = store.ordered_collection_page(&followers, 1, 2).unwrap();
+        assert_eq!(
+            page.to_json_pretty().unwrap(),
+            r#"{
+  "type": "OrderedCollectionPage",
+  "id": "https://example.com/sally/followers?page=1",
+  "totalItems": 3,
+  "orderedItems": [
+    "https://example.com/jim",
+    "https://example.com/jane"
+  ],
+  "partOf": "https://example.com/sally/followers",
+  "next": "https://example.com/sally/followers?page=2"
+}"#
+        );
+        let page = store.ordered_collection_page(&followers, 2, 2).unwrap();
+        assert_eq!(page.ordered_items.len(), 1);
+        assert_eq!(page.total_items, Some(3));
+        assert_eq!(page.next, None);
+
+        let page = store
+            .ordered_collection_page(&followers, usize::MAX, 20)
+            .unwrap();
+        assert!(page.ordered_items.is_empty());
+        assert_eq!(page.next, None);
+    }
+}
@@ -0,0 +1,203 @@
+use super::ObjectStore;
+use crate::iri::Iri;
+use crate::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::path::Path;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS documents (
+        id TEXT PRIMARY KEY,
+        document TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS collection_items (
+        position INTEGER PRIMARY KEY AUTOINCREMENT,
+        collection TEXT NOT NULL,
+        item TEXT NOT NULL,
+        UNIQUE (collection, item)
+    );
+    CREATE INDEX IF NOT EXISTS collection_items_by_collection
+        ON collection_items (collection, position);
+";
+
+/// An [ObjectStore] keeping documents, as JSON text, and collection members
+/// in a SQLite database.
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Opens, or creates, the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        SqliteStore::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a database that lives as long as the store.
+    pub fn open_in_memory() -> Result<Self> {
+        SqliteStore::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Uses `connection`, creating the tables the store needs if missing.
+    pub fn from_connection(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteStore { connection })
+    }
+}
+
+impl ObjectStore for SqliteStore {
+    fn get(&self, id: &Iri) -> Result<Option<Value>> {
+        let document: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT document FROM documents WHERE id = ?1",
+                params![id.as_str()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(document
+            .map(|document| serde_json::from_str(&document))
+            .transpose()?)
+    }
+
+    fn put(&mut self, id: &Iri, document: Value) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO documents (id, document) VALUES (?1, ?2)
+                ON CONFLICT (id) DO UPDATE SET document = excluded.document",
+            params![id.as_str(), document.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &Iri) -> Result<bool> {
+        let removed = self
+            .connection
+            .execute("DELETE FROM documents WHERE id = ?1", params![id.as_str()])?;
+        Ok(removed > 0)
+    }
+
+    fn add_item(&mut self, collection: &Iri, item: &Iri) -> Result<bool> {
+        let added = self.connection.execute(
+            "INSERT OR IGNORE INTO collection_items (collection, item) VALUES (?1, ?2)",
+            params![collection.as_str(), item.as_str()],
+        )?;
+        Ok(added > 0)
+    }
+
+    fn remove_item(&mut self, collection: &Iri, item: &Iri) -> Result<bool> {
+        let removed = self.connection.execute(
+            "DELETE FROM collection_items WHERE collection = ?1 AND item = ?2",
+            params![collection.as_str(), item.as_str()],
+        )?;
+        Ok(removed > 0)
+    }
+
+    fn items(&self, collection: &Iri) -> Result<Vec<Iri>> {
+        self.page_items(collection, 0, usize::MAX)
+    }
+
+    fn contains_item(&self, collection: &Iri, item: &Iri) -> Result<bool> {
+        let found = self
+            .connection
+            .query_row(
+                "SELECT 1 FROM collection_items WHERE collection = ?1 AND item = ?2",
+                params![collection.as_str(), item.as_str()],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    fn count_items(&self, collection: &Iri) -> Result<usize> {
+        let count: i64 = self.connection.query_row(
+            "SELECT COUNT(*) FROM collection_items WHERE collection = ?1",
+            params![collection.as_str()],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    fn page_items(&self, collection: &Iri, offset: usize, limit: usize) -> Result<Vec<Iri>> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT item FROM collection_items WHERE collection = ?1
+                ORDER BY position DESC LIMIT ?2 OFFSET ?3",
+        )?;
+        // SQLite integers are signed, and a negative limit means none.
+        let limit = i64::try_from(limit).unwrap_or(-1);
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+        let items = statement.query_map(params![collection.as_str(), limit, offset], |row| {
+            row.get::<_, String>(0)
+        })?;
+        let mut iris = Vec::new();
+        for item in items {
+            iris.push(item?.parse()?);
+        }
+        Ok(iris)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ContextBuilder, Document, Null, Object, ObjectBuilder};
+    use pretty_assertions::assert_eq;
+
+    fn iri(iri: &str) -> Iri {
+        iri.parse().unwrap()
+    }
+
+    #[test]
+    fn store_documents() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let note: Object<Null> = ObjectBuilder::new()
+            .object_type(String::from("Note"))
+            .id(iri("https://example.com/notes/1"))
+            .content(String::from("Hello"))
+            .build();
+        let id = store
+            .put_document(&Document::new(ContextBuilder::new().build(), note))
+            .unwrap();
+        let note: Object<Null> = ObjectBuilder::new()
+            .object_type(String::from("Note"))
+            .id(id.clone())
+            .content(String::from("Hello, world"))
+            .build();
+        store
+            .put_document(&Document::new(ContextBuilder::new().build(), note))
+            .unwrap();
+
+        let document: Document<Object<Null>> = store.get_document(&id).unwrap().unwrap();
+        assert_eq!(document.object.content, Some(String::from("Hello, world")));
+        assert!(store.remove(&id).unwrap());
+        assert!(!store.remove(&id).unwrap());
+        assert!(store.get(&id).unwrap().is_none());
+    }
+
+    #[test]
+    fn page_collection() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let outbox = iri("https://example.com/sally/outbox");
+        for n in 1..=5 {
+            let item = iri(&format!("https://example.com/sally/notes/{n}"));
+            assert!(store.add_item(&outbox, &item).unwrap());
+        }
+        assert!(!store
+            .add_item(&outbox, &iri("https://example.com/sally/notes/1"))
+            .unwrap());
+        assert!(store
+            .remove_item(&outbox, &iri("https://example.com/sally/notes/3"))
+            .unwrap());
+        assert_eq!(store.count_items(&outbox).unwrap(), 4);
+        assert!(store
+            .contains_item(&outbox, &iri("https://example.com/sally/notes/5"))
+            .unwrap());
+
+        let page = store.ordered_collection_page(&outbox, 2, 3).unwrap();
+        let items: Vec<_> = page.ordered_items.iter().filter_map(|i| i.id()).collect();
+        assert_eq!(items, vec![&iri("https://example.com/sally/notes/1")]);
+        assert_eq!(page.next, None);
+        assert_eq!(
+            page.prev,
+            Some(iri("https://example.com/sally/outbox?page=1"))
+        );
+    }
+}
@@ -0,0 +1,675 @@
+//! Validation of documents against the requirements of Activity Streams and
+//! ActivityPub that the types themselves don't enforce, e.g. a `Follow`
+//! without an `object` or a [crate::core::Link] with a relative `href`.
+//!
+//! Every type of the crate can be checked through [Validate], and raw JSON
+//! through [validate_json]. Each problem is reported as a [Violation] naming
+//! the JSON path of the offending value, e.g. `$.object.href`.
+
+use crate::iri::Iri;
+use crate::{Error, Result, Serde};
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// How thoroughly to validate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Reports values of the wrong type or form and violations of what
+    /// Activity Streams requires, such as an activity missing its `object`.
+    #[default]
+    Lenient,
+    /// Additionally reports what ActivityPub requires of federated
+    /// documents: an `id` and `type` on the document, a `type` on embedded
+    /// objects, an `actor` on activities (or an `attributedTo` on questions),
+    /// a `target` on `Add` and `Remove`, and an `inbox` and `outbox` on actors.
+    Strict,
+}
+
+/// A single problem found in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The JSON path of the offending value, or of the object missing a
+    /// property, e.g. `$.tag[0]`.
+    pub path: String,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+/// The kind of a [Violation].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// A required property is absent.
+    MissingProperty(&'static str),
+    /// A property is present where it is not allowed, e.g. `object` on an
+    /// `IntransitiveActivity`.
+    UnexpectedProperty(&'static str),
+    /// The value is not of the type the property takes.
+    WrongValueType { expected: &'static str },
+    /// An IRI is relative or malformed.
+    NonAbsoluteIri(String),
+    /// A `mediaType` is not a valid MIME media type.
+    InvalidMediaType(String),
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::MissingProperty(property) => write!(f, "missing property {}", property),
+            ViolationKind::UnexpectedProperty(property) => {
+                write!(f, "unexpected property {}", property)
+            }
+            ViolationKind::WrongValueType { expected } => write!(f, "expected {}", expected),
+            ViolationKind::NonAbsoluteIri(iri) => write!(f, "{} is not an absolute IRI", iri),
+            ViolationKind::InvalidMediaType(media_type) => {
+                write!(f, "{} is not a valid media type", media_type)
+            }
+        }
+    }
+}
+
+/// Validation of any of the crate's types, see [validate_json].
+pub trait Validate {
+    /// Returns the violations found, if any, failing with [Error::Serialize]
+    /// if the value can't be turned into a document to validate.
+    fn validate(&self, mode: Mode) -> Result<Vec<Violation>>;
+
+    /// Like [Validate::validate], failing with [Error::Validation] if any
+    /// violations are found.
+    fn check(&self, mode: Mode) -> Result<()> {
+        let violations = self.validate(mode)?;
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(violations))
+        }
+    }
+}
+
+impl<T: Serde> Validate for T {
+    fn validate(&self, mode: Mode) -> Result<Vec<Violation>> {
+        let document = serde_json::to_value(self).map_err(Error::Serialize)?;
+        Ok(validate_json(&document, mode))
+    }
+}
+
+/// Validates a compacted Activity Streams document, i.e. one using the
+/// property names of the Activity Streams context. Members it doesn't know,
+/// such as extensions, are not checked.
+pub fn validate_json(document: &Value, mode: Mode) -> Vec<Violation> {
+    let mut validator = Validator {
+        mode,
+        violations: Vec::new(),
+    };
+    match document {
+        Value::Object(object) => validator.object(object, "$", true),
+        _ => validator.report("$", ViolationKind::WrongValueType { expected: "object" }),
+    }
+    validator.violations
+}
+
+/// Properties referring to other objects, given as an IRI, an embedded object
+/// or a link.
+const REFERENCE_PROPERTIES: &[&str] = &[
+    "actor",
+    "anyOf",
+    "attachment",
+    "attributedTo",
+    "audience",
+    "bcc",
+    "bto",
+    "cc",
+    "context",
+    "current",
+    "describes",
+    "first",
+    "followers",
+    "following",
+    "generator",
+    "icon",
+    "image",
+    "inReplyTo",
+    "inbox",
+    "instrument",
+    "items",
+    "last",
+    "liked",
+    "location",
+    "next",
+    "object",
+    "oneOf",
+    "orderedItems",
+    "origin",
+    "outbox",
+    "partOf",
+    "prev",
+    "preview",
+    "relationship",
+    "replies",
+    "result",
+    "streams",
+    "subject",
+    "tag",
+    "target",
+    "to",
+    "url",
+];
+
+const STRING_PROPERTIES: &[&str] = &[
+    "content",
+    "duration",
+    "formerType",
+    "hreflang",
+    "name",
+    "preferredUsername",
+    "summary",
+    "units",
+];
+
+const DATETIME_PROPERTIES: &[&str] = &["deleted", "endTime", "published", "startTime", "updated"];
+
+const NON_NEGATIVE_INTEGER_PROPERTIES: &[&str] =
+    &["height", "startIndex", "totalItems", "votersCount", "width"];
+
+const NUMBER_PROPERTIES: &[&str] = &["accuracy", "altitude", "latitude", "longitude", "radius"];
+
+const LANGUAGE_MAP_PROPERTIES: &[&str] = &["contentMap", "nameMap", "summaryMap"];
+
+const LINK_TYPES: &[&str] = &["Link", "Mention", "Hashtag"];
+
+const ACTOR_TYPES: &[&str] = &["Application", "Group", "Organization", "Person", "Service"];
+
+const INTRANSITIVE_ACTIVITY_TYPES: &[&str] =
+    &["IntransitiveActivity", "Arrive", "Question", "Travel"];
+
+/// Activity types whose meaning depends on an `object`.
+const TRANSITIVE_ACTIVITY_TYPES: &[&str] = &[
+    "Accept",
+    "Add",
+    "Announce",
+    "Block",
+    "Create",
+    "Delete",
+    "Dislike",
+    "Flag",
+    "Follow",
+    "Ignore",
+    "Invite",
+    "Join",
+    "Leave",
+    "Like",
+    "Listen",
+    "Move",
+    "Offer",
+    "Read",
+    "Reject",
+    "Remove",
+    "TentativeAccept",
+    "TentativeReject",
+    "Undo",
+    "Update",
+    "View",
+];
+
+/// Whether `object_type` is that of an activity.
+pub(crate) fn is_activity_type(object_type: &str) -> bool {
+    object_type == "Activity"
+        || INTRANSITIVE_ACTIVITY_TYPES.contains(&object_type)
+        || TRANSITIVE_ACTIVITY_TYPES.contains(&object_type)
+}
+
+struct Validator {
+    mode: Mode,
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn report(&mut self, path: &str, kind: ViolationKind) {
+        self.violations.push(Violation {
+            path: path.to_string(),
+            kind,
+        });
+    }
+
+    fn object(&mut self, object: &Map<String, Value>, path: &str, root: bool) {
+        let types = self.types(object, path);
+        let is = |candidates: &[&str]| types.iter().any(|t| candidates.contains(t));
+        let is_link = is(LINK_TYPES);
+        let is_intransitive = is(INTRANSITIVE_ACTIVITY_TYPES);
+        let is_transitive = is(TRANSITIVE_ACTIVITY_TYPES);
+
+        if self.mode == Mode::Strict {
+            if root && !is_link && !object.contains_key("id") {
+                self.report(path, ViolationKind::MissingProperty("id"));
+            }
+            if !object.contains_key("type") {
+                self.report(path, ViolationKind::MissingProperty("type"));
+            }
+            // Polls are attributed to their author rather than performed,
+            // e.g. by Mastodon.
+            let attributed_question = is(&["Question"]) && object.contains_key("attributedTo");
+            if (is_transitive || is_intransitive || is(&["Activity"]))
+                && !object.contains_key("actor")
+                && !attributed_question
+            {
+                self.report(path, ViolationKind::MissingProperty("actor"));
+            }
+            if is(&["Add", "Remove"]) && !object.contains_key("target") {
+                self.report(path, ViolationKind::MissingProperty("target"));
+            }
+            if is(ACTOR_TYPES) {
+                for property in ["inbox", "outbox"] {
+                    if !object.contains_key(property) {
+                        self.report(path, ViolationKind::MissingProperty(property));
+                    }
+                }
+            }
+        }
+        if is_link && !object.contains_key("href") {
+            self.report(path, ViolationKind::MissingProperty("href"));
+        }
+        if is_transitive && !object.contains_key("object") {
+            self.report(path, ViolationKind::MissingProperty("object"));
+        }
+        if is_intransitive && object.contains_key("object") {
+            self.report(
+                &format!("{}.object", path),
+                ViolationKind::UnexpectedProperty("object"),
+            );
+        }
+        if object.contains_key("oneOf") && object.contains_key("anyOf") {
+            self.report(
+                &format!("{}.anyOf", path),
+                ViolationKind::UnexpectedProperty("anyOf"),
+            );
+        }
+
+        for (property, value) in object {
+            let path = format!("{}.{}", path, property);
+            self.property(property, value, &path);
+        }
+    }
+
+    fn types<'a>(&mut self, object: &'a Map<String, Value>, path: &str) -> Vec<&'a str> {
+        match object.get("type") {
+            None => Vec::new(),
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) if types.iter().all(Value::is_string) => {
+                types.iter().filter_map(Value::as_str).collect()
+            }
+            Some(_) => {
+                self.report(
+                    &format!("{}.type", path),
+                    ViolationKind::WrongValueType {
+                        expected: "string or array of strings",
+                    },
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    fn property(&mut self, property: &str, value: &Value, path: &str) {
+        if property == "id" || property == "href" {
+            self.iri(value, path);
+        } else if REFERENCE_PROPERTIES.contains(&property) {
+            self.references(value, path);
+        } else if property == "endpoints" || property == "publicKey" {
+            self.auxiliary(value, path);
+        } else if property == "mediaType" {
+            match value.as_str() {
+                Some(media_type) if !is_media_type(media_type) => self.report(
+                    path,
+                    ViolationKind::InvalidMediaType(media_type.to_string()),
+                ),
+                Some(_) => {}
+                None => self.report(path, ViolationKind::WrongValueType { expected: "string" }),
+            }
+        } else if STRING_PROPERTIES.contains(&property) {
+            if !value.is_string() {
+                self.report(path, ViolationKind::WrongValueType { expected: "string" });
+            }
+        } else if DATETIME_PROPERTIES.contains(&property) {
+            if !is_datetime(value) {
+                self.report(
+                    path,
+                    ViolationKind::WrongValueType {
+                        expected: "datetime",
+                    },
+                );
+            }
+        } else if property == "closed" {
+            if !value.is_boolean() && !is_datetime(value) {
+                self.report(
+                    path,
+                    ViolationKind::WrongValueType {
+                        expected: "datetime or boolean",
+                    },
+                );
+            }
+        } else if NON_NEGATIVE_INTEGER_PROPERTIES.contains(&property) {
+            if !value.is_u64() {
+                self.report(
+                    path,
+                    ViolationKind::WrongValueType {
+                        expected: "non-negative integer",
+                    },
+                );
+            }
+        } else if NUMBER_PROPERTIES.contains(&property) {
+            if !value.is_number() {
+                self.report(path, ViolationKind::WrongValueType { expected: "number" });
+            }
+        } else if LANGUAGE_MAP_PROPERTIES.contains(&property) {
+            let is_language_map = value
+                .as_object()
+                .is_some_and(|map| map.values().all(Value::is_string));
+            if !is_language_map {
+                self.report(
+                    path,
+                    ViolationKind::WrongValueType {
+                        expected: "language map",
+                    },
+                );
+            }
+        }
+    }
+
+    fn references(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Array(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    self.reference(value, &format!("{}[{}]", path, index));
+                }
+            }
+            value => self.reference(value, path),
+        }
+    }
+
+    fn reference(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::String(_) => self.iri(value, path),
+            Value::Object(object) => self.object(object, path, false),
+            _ => self.report(
+                path,
+                ViolationKind::WrongValueType {
+                    expected: "IRI, object or link",
+                },
+            ),
+        }
+    }
+
+    /// Validates `endpoints` and `publicKey`, plain JSON objects whose string
+    /// members are IRIs, except for the key itself.
+    fn auxiliary(&mut self, value: &Value, path: &str) {
+        let Value::Object(object) = value else {
+            return self.iri(value, path);
+        };
+        for (property, value) in object {
+            if property != "publicKeyPem" && value.is_string() {
+                self.iri(value, &format!("{}.{}", path, property));
+            }
+        }
+    }
+
+    fn iri(&mut self, value: &Value, path: &str) {
+        match value.as_str() {
+            Some(iri) if !is_absolute_iri(iri) => {
+                self.report(path, ViolationKind::NonAbsoluteIri(iri.to_string()))
+            }
+            Some(_) => {}
+            None => self.report(path, ViolationKind::WrongValueType { expected: "IRI" }),
+        }
+    }
+}
+
+fn is_datetime(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|datetime| DateTime::parse_from_rfc3339(datetime).is_ok())
+}
+
+/// Whether `iri` is a well-formed IRI with a scheme (RFC 3987).
+pub(crate) fn is_absolute_iri(iri: &str) -> bool {
+    iri.parse::<Iri>().is_ok_and(|iri| iri.is_absolute())
+}
+
+/// Whether `media_type` is a `type/subtype` pair, optionally followed by
+/// parameters (RFC 6838).
+fn is_media_type(media_type: &str) -> bool {
+    fn is_restricted_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        name.len() <= 127
+            && chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+            && chars.all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c))
+    }
+
+    let mut parts = media_type.split(';');
+    let essence = parts.next().unwrap_or_default();
+    let is_essence = essence
+        .split_once('/')
+        .is_some_and(|(t, subtype)| is_restricted_name(t) && is_restricted_name(subtype));
+    is_essence
+        && parts.all(|parameter| {
+            parameter
+                .trim()
+                .split_once('=')
+                .is_some_and(|(name, value)| is_restricted_name(name) && !value.is_empty())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Document, LinkBuilder, Null, Object, ObjectBuilder, UriBuilder};
+    use crate::extended::{FollowBuilder, PersonBuilder};
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn violation(path: &str, kind: ViolationKind) -> Violation {
+        Violation {
+            path: path.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn validate_follow_without_object() {
+        let follow = FollowBuilder::new(String::from("Sally followed John"))
+            .actor("https://example.com/sally".parse::<Iri>().unwrap())
+            .build();
+        assert_eq!(
+            follow.validate(Mode::Lenient).unwrap(),
+            vec![violation("$", ViolationKind::MissingProperty("object"))]
+        );
+        assert_eq!(
+            follow.validate(Mode::Strict).unwrap(),
+            vec![
+                violation("$", ViolationKind::MissingProperty("id")),
+                violation("$", ViolationKind::MissingProperty("object")),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_follow_without_object() {
+        let follow = FollowBuilder::new(String::from("Sally followed John"))
+            .actor("https://example.com/sally".parse::<Iri>().unwrap())
+            .build();
+        let error = follow.check(Mode::Lenient).unwrap_err();
+        assert_eq!(error.path(), Some("$"));
+        assert_eq!(
+            error.to_string(),
+            "invalid document: $: missing property object"
+        );
+    }
+
+    #[test]
+    fn validate_relative_href() {
+        let object: Object<Null> = ObjectBuilder::new()
+            .object_type(String::from("Note"))
+            .id("https://example.com/notes/1".parse::<Iri>().unwrap())
+            .url(LinkBuilder::new(UriBuilder::new(
+                "/notes/1".parse::<Iri>().unwrap(),
+            )))
+            .build();
+        assert_eq!(
+            object.validate(Mode::Lenient).unwrap(),
+            vec![violation(
+                "$.url.href",
+                ViolationKind::NonAbsoluteIri(String::from("/notes/1"))
+            )]
+        );
+    }
+
+    #[test]
+    fn validate_json_documents() {
+        let document = json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "type": "Travel",
+            "actor": "sally",
+            "object": "https://example.com/home",
+            "published": "yesterday",
+            "attachment": [
+                { "type": "Image", "url": "https://example.com/1.png", "mediaType": "image" },
+                { "type": "Mention", "name": "@sally" },
+                42
+            ],
+            "width": -1
+        });
+        assert_eq!(
+            validate_json(&document, Mode::Lenient),
+            vec![
+                violation("$.object", ViolationKind::UnexpectedProperty("object")),
+                violation(
+                    "$.actor",
+                    ViolationKind::NonAbsoluteIri(String::from("sally"))
+                ),
+                violation(
+                    "$.attachment[0].mediaType",
+                    ViolationKind::InvalidMediaType(String::from("image"))
+                ),
+                violation("$.attachment[1]", ViolationKind::MissingProperty("href")),
+                violation(
+                    "$.attachment[2]",
+                    ViolationKind::WrongValueType {
+                        expected: "IRI, object or link"
+                    }
+                ),
+                violation(
+                    "$.published",
+                    ViolationKind::WrongValueType {
+                        expected: "datetime"
+                    }
+                ),
+                violation(
+                    "$.width",
+                    ViolationKind::WrongValueType {
+                        expected: "non-negative integer"
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_strict_actor() {
+        let person = PersonBuilder::new()
+            .id("https://example.com/sally".parse::<Iri>().unwrap())
+            .inbox("https://example.com/sally/inbox".parse::<Iri>().unwrap())
+            .build();
+        assert!(person.validate(Mode::Lenient).unwrap().is_empty());
+        assert_eq!(
+            person.validate(Mode::Strict).unwrap(),
+            vec![violation("$", ViolationKind::MissingProperty("outbox"))]
+        );
+
+        let person = PersonBuilder::new()
+            .id("https://example.com/sally".parse::<Iri>().unwrap())
+            .inbox("https://example.com/sally/inbox".parse::<Iri>().unwrap())
+            .outbox("https://example.com/sally/outbox".parse::<Iri>().unwrap())
+            .shared_inbox("https://example.com/inbox".parse::<Iri>().unwrap())
+            .public_key(
+                "#main-key".parse::<Iri>().unwrap(),
+                "https://example.com/sally".parse::<Iri>().unwrap(),
+                String::from("-----BEGIN PUBLIC KEY-----"),
+            )
+            .build();
+        assert_eq!(
+            Document::new(crate::core::ContextBuilder::new().build(), person)
+                .validate(Mode::Strict)
+                .unwrap(),
+            vec![violation(
+                "$.publicKey.id",
+                ViolationKind::NonAbsoluteIri(String::from("#main-key"))
+            )]
+        );
+    }
+
+    #[test]
+    fn validate_strict_question() {
+        let poll = json!({
+            "id": "https://example.com/sally/statuses/1",
+            "type": "Question",
+            "attributedTo": "https://example.com/sally",
+            "oneOf": [{"type": "Note", "name": "Yes"}],
+        });
+        assert!(validate_json(&poll, Mode::Strict).is_empty());
+
+        let question = json!({
+            "id": "https://example.com/sally/statuses/1",
+            "type": "Question",
+        });
+        assert_eq!(
+            validate_json(&question, Mode::Strict),
+            vec![violation("$", ViolationKind::MissingProperty("actor"))]
+        );
+    }
+
+    #[test]
+    fn validate_unserializable() {
+        #[derive(Serialize, Deserialize)]
+        struct Keyed(BTreeMap<(u8, u8), String>);
+
+        impl Serde for Keyed {}
+
+        let keyed = Keyed(BTreeMap::from([((1, 2), String::from("a"))]));
+        assert!(matches!(
+            keyed.validate(Mode::Lenient),
+            Err(Error::Serialize(_))
+        ));
+        assert!(matches!(
+            keyed.check(Mode::Lenient),
+            Err(Error::Serialize(_))
+        ));
+    }
+
+    #[test]
+    fn media_types() {
+        assert!(is_media_type("text/html"));
+        assert!(is_media_type(
+            "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\""
+        ));
+        assert!(is_media_type("image/svg+xml"));
+        assert!(!is_media_type("image"));
+        assert!(!is_media_type("text/"));
+        assert!(!is_media_type("text/html; charset"));
+    }
+
+    #[test]
+    fn absolute_iris() {
+        assert!(is_absolute_iri("https://example.com/ä"));
+        assert!(is_absolute_iri("urn:uuid:1234"));
+        assert!(is_absolute_iri("as:Public"));
+        assert!(!is_absolute_iri("/notes/1"));
+        assert!(!is_absolute_iri("https://example.com/a b"));
+        assert!(!is_absolute_iri("1http://example.com/"));
+    }
+}